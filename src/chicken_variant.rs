@@ -3,7 +3,7 @@
 
 use crate::{
     ident::Ident,
-    nbt::to_network as to_network_nbt,
+    nbt::{ NbtSerError, to_network as to_network_nbt },
     registry_entry::*
 };
 use std::io::Write;
@@ -49,11 +49,11 @@ include!("../../pipeworkmc-vanilla-datagen/output/generated/chicken_variant.rs")
 impl RegistryEntryType for ChickenVariant {
     const REGISTRY_ID : Ident = Ident::new("minecraft:chicken_variant");
 
-    fn to_network_nbt<W>(&self, writer : W) -> bool
+    fn to_network_nbt<W>(&self, writer : W) -> Result<bool, NbtSerError>
     where
         W : Write
     {
-        to_network_nbt(writer, self).unwrap();
-        true
+        to_network_nbt(writer, self)?;
+        Ok(true)
     }
 }
@@ -1,6 +1,9 @@
 //! Chunk positions.
 
 
+use crate::region::RegionPos;
+
+
 /// The position of a chunk in a dimension.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct ChunkPos {
@@ -21,4 +24,10 @@ impl ChunkPos {
         if (x < z) { x } else { z }
     }
 
+    /// Returns the position of the region that this chunk position falls into.
+    pub const fn region(self) -> RegionPos { RegionPos {
+        x : self.x.div_euclid(32),
+        z : self.z.div_euclid(32)
+    } }
+
 }
@@ -3,7 +3,7 @@
 
 use crate::{
     ident::Ident,
-    nbt::to_network as to_network_nbt,
+    nbt::{ NbtSerError, to_network as to_network_nbt },
     registry_entry::*
 };
 use crate::is_default;
@@ -104,11 +104,11 @@ include!("../../pipeworkmc-vanilla-datagen/output/generated/damage_type.rs");
 impl RegistryEntryType for DamageType<'_> {
     const REGISTRY_ID : Ident = Ident::new("minecraft:damage_type");
 
-    fn to_network_nbt<W>(&self, writer : W) -> bool
+    fn to_network_nbt<W>(&self, writer : W) -> Result<bool, NbtSerError>
     where
         W : Write
     {
-        to_network_nbt(writer, self).unwrap();
-        true
+        to_network_nbt(writer, self)?;
+        Ok(true)
     }
 }
@@ -4,7 +4,7 @@
 use crate::{
     ident::Ident,
     light_level::{ LightLevel, LightLevelProvider },
-    nbt::to_network as to_network_nbt,
+    nbt::{ NbtSerError, to_network as to_network_nbt },
     num::multiple16::{
         Multiple16I32,
         Multiple16U32
@@ -119,11 +119,11 @@ include!("../../pipeworkmc-vanilla-datagen/output/generated/dimension_type.rs");
 impl RegistryEntryType for DimensionType<'_> {
     const REGISTRY_ID : Ident = Ident::new("minecraft:dimension_type");
 
-    fn to_network_nbt<W>(&self, writer : W) -> bool
+    fn to_network_nbt<W>(&self, writer : W) -> Result<bool, NbtSerError>
     where
         W : Write
     {
-        to_network_nbt(writer, self).unwrap();
-        true
+        to_network_nbt(writer, self)?;
+        Ok(true)
     }
 }
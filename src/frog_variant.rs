@@ -3,7 +3,7 @@
 
 use crate::{
     ident::Ident,
-    nbt::to_network as to_network_nbt,
+    nbt::{ NbtSerError, to_network as to_network_nbt },
     registry_entry::*
 };
 use std::io::Write;
@@ -37,11 +37,11 @@ include!("../../pipeworkmc-vanilla-datagen/output/generated/frog_variant.rs");
 impl RegistryEntryType for FrogVariant {
     const REGISTRY_ID : Ident = Ident::new("minecraft:frog_variant");
 
-    fn to_network_nbt<W>(&self, writer : W) -> bool
+    fn to_network_nbt<W>(&self, writer : W) -> Result<bool, NbtSerError>
     where
         W : Write
     {
-        to_network_nbt(writer, self).unwrap();
-        true
+        to_network_nbt(writer, self)?;
+        Ok(true)
     }
 }
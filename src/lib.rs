@@ -54,6 +54,7 @@ pub mod particle;
 pub mod pig_variant;
 pub mod profile;
 pub mod redacted;
+pub mod region;
 pub mod registry_entry;
 pub mod selected_hotbar;
 pub mod server_status;
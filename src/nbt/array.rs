@@ -0,0 +1,139 @@
+use core::{
+    fmt::{ self, Formatter },
+    marker::PhantomData
+};
+use serde::{
+    Serialize as Ser,
+    Serializer as Serer,
+    Deserialize as Deser,
+    Deserializer as Deserer,
+    de::{
+        SeqAccess,
+        Visitor,
+        value::SeqAccessDeserializer
+    }
+};
+
+
+pub(in crate::nbt) const BARRAY_TOKEN : &str = "__pipeworkmc_nbt_barray";
+pub(in crate::nbt) const IARRAY_TOKEN : &str = "__pipeworkmc_nbt_iarray";
+pub(in crate::nbt) const LARRAY_TOKEN : &str = "__pipeworkmc_nbt_larray";
+/// Requested as a newtype struct by [`NbtElement`](super::NbtElement), so that the NBT deserialisers present
+///  int and long arrays as a single entry map keyed by [`IARRAY_TOKEN`] or [`LARRAY_TOKEN`], instead of a sequence.
+pub(in crate::nbt) const ELEMENT_TOKEN : &str = "__pipeworkmc_nbt_element";
+
+
+/// Marks a sequence of `i8`s or bytes to be serialised as an NBT byte array.
+///
+/// Other serialisers see the inner value unchanged.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[repr(transparent)]
+pub struct NbtBArray<T>(pub T);
+
+/// Marks a sequence of `i32`s to be serialised as an NBT int array.
+///
+/// Other serialisers see the inner value unchanged.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[repr(transparent)]
+pub struct NbtIArray<T>(pub T);
+
+/// Marks a sequence of `i64`s to be serialised as an NBT long array.
+///
+/// Other serialisers see the inner value unchanged.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[repr(transparent)]
+pub struct NbtLArray<T>(pub T);
+
+
+macro impl_array_marker( $ty:ident , $token:ident $(,)? ) {
+
+    impl<T> Ser for $ty<T>
+    where
+        T : Ser
+    {
+        #[inline]
+        fn serialize<S>(&self, serer : S) -> Result<S::Ok, S::Error>
+        where
+            S : Serer
+        { serer.serialize_newtype_struct($token, &self.0) }
+    }
+
+    impl<'de, T> Deser<'de> for $ty<T>
+    where
+        T : Deser<'de>
+    {
+        #[inline]
+        fn deserialize<D>(deserer : D) -> Result<Self, D::Error>
+        where
+            D : Deserer<'de>
+        { Ok(Self(deserer.deserialize_newtype_struct($token, NbtArrayVisitor { _marker : PhantomData })?)) }
+    }
+
+}
+impl_array_marker!(NbtBArray, BARRAY_TOKEN);
+impl_array_marker!(NbtIArray, IARRAY_TOKEN);
+impl_array_marker!(NbtLArray, LARRAY_TOKEN);
+
+
+struct NbtArrayVisitor<T> {
+    _marker : PhantomData<fn() -> T>
+}
+
+impl<'de, T> Visitor<'de> for NbtArrayVisitor<T>
+where
+    T : Deser<'de>
+{
+    type Value = T;
+
+    #[inline]
+    fn expecting(&self, f : &mut Formatter) -> fmt::Result {
+        write!(f, "an NBT array")
+    }
+
+    #[inline]
+    fn visit_newtype_struct<D>(self, deserer : D) -> Result<Self::Value, D::Error>
+    where
+        D : Deserer<'de>
+    { T::deserialize(deserer) }
+
+    #[inline]
+    fn visit_seq<A>(self, seq : A) -> Result<Self::Value, A::Error>
+    where
+        A : SeqAccess<'de>
+    { T::deserialize(SeqAccessDeserializer::new(seq)) }
+
+}
+
+
+macro_rules! array_with_module { ( $module:ident , $ty:ident , $doc:literal $(,)? ) => {
+    #[doc = $doc]
+    pub mod $module {
+        use super::$ty;
+        use serde::{
+            Serialize as Ser,
+            Serializer as Serer,
+            Deserialize as Deser,
+            Deserializer as Deserer
+        };
+
+        /// Serialise a value wrapped in the array marker.
+        #[inline]
+        pub fn serialize<S, T>(value : &T, serer : S) -> Result<S::Ok, S::Error>
+        where
+            S : Serer,
+            T : Ser
+        { $ty(value).serialize(serer) }
+
+        /// Deserialise a value wrapped in the array marker.
+        #[inline]
+        pub fn deserialize<'de, D, T>(deserer : D) -> Result<T, D::Error>
+        where
+            D : Deserer<'de>,
+            T : Deser<'de>
+        { Ok($ty::<T>::deserialize(deserer)?.0) }
+
+    }
+}; }
+array_with_module!(barray, NbtBArray, "(De)serialise a field as an NBT byte array, using `#[serde(with = \"nbt::barray\")]`.");
+array_with_module!(iarray, NbtIArray, "(De)serialise a field as an NBT int array, using `#[serde(with = \"nbt::iarray\")]`.");
+array_with_module!(larray, NbtLArray, "(De)serialise a field as an NBT long array, using `#[serde(with = \"nbt::larray\")]`.");
@@ -0,0 +1,27 @@
+/// Compression wrapping an NBT file.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum NbtCompression {
+    /// Uncompressed
+    None,
+    /// Gzip
+    ///
+    /// Used by `level.dat`, player data, and structure templates.
+    #[default]
+    Gzip,
+    /// Zlib
+    Zlib
+}
+
+impl NbtCompression {
+
+    /// Guesses the compression of an NBT file from its first bytes.
+    ///
+    /// Uncompressed NBT files start with a compound tag, which is never a valid gzip or zlib header.
+    pub fn detect(header : &[u8]) -> Self { match (header) {
+        [0x1F, 0x8B, ..] => Self::Gzip,
+        // Zlib headers use the deflate method, and are a multiple of 31 when read as a big-endian `u16`.
+        [cmf, flg, ..] if ((cmf & 0x0F) == 8 && u16::from_be_bytes([*cmf, *flg]) % 31 == 0) => Self::Zlib,
+        _ => Self::None
+    } }
+
+}
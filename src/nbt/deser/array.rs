@@ -0,0 +1,82 @@
+use super::{
+    NbtDeserError,
+    NbtSource, NbtRead,
+    NbtSeqDeserer
+};
+use serde::{
+    de::{
+        DeserializeSeed,
+        Deserializer as Deserer,
+        IntoDeserializer,
+        MapAccess,
+        Visitor
+    },
+    forward_to_deserialize_any
+};
+
+
+/// Presents an int or long array to [`NbtElement`](crate::nbt::NbtElement) as a single entry map,
+///  keyed by the array's marker token.
+pub(super) struct NbtArrayMapAccess<'l, R> {
+    reader   : &'l mut NbtSource<R>,
+    token    : Option<&'static str>,
+    elem_tag : u8
+}
+
+impl<'l, R> NbtArrayMapAccess<'l, R> { pub(super) fn new(reader : &'l mut NbtSource<R>, token : &'static str, elem_tag : u8) -> Self { Self {
+    reader, token : Some(token), elem_tag
+} } }
+
+
+impl<'de, R> MapAccess<'de> for NbtArrayMapAccess<'_, R>
+where
+    R : NbtRead<'de>
+{
+    type Error = NbtDeserError;
+
+    #[inline]
+    fn next_key_seed<K>(&mut self, seed : K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K : DeserializeSeed<'de>
+    { self.token.take().map(|token| seed.deserialize(token.into_deserializer())).transpose() }
+
+    #[inline]
+    fn next_value_seed<V>(&mut self, seed : V) -> Result<V::Value, Self::Error>
+    where
+        V : DeserializeSeed<'de>
+    { seed.deserialize(NbtArrayDeserer { reader : &mut*self.reader, elem_tag : self.elem_tag }) }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> { Some(if (self.token.is_some()) { 1 } else { 0 }) }
+
+}
+
+
+/// Reads the length prefixed payload of an array as a sequence.
+struct NbtArrayDeserer<'l, R> {
+    reader   : &'l mut NbtSource<R>,
+    elem_tag : u8
+}
+
+impl<'de, R> Deserer<'de> for NbtArrayDeserer<'_, R>
+where
+    R : NbtRead<'de>
+{
+    type Error = NbtDeserError;
+
+    fn deserialize_any<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    {
+        let len = self.reader.read_len()?;
+        self.reader.accounter().check_list(self.elem_tag, len)?;
+        visitor.visit_seq(NbtSeqDeserer::new(self.reader, self.elem_tag, len))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+
+}
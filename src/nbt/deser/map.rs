@@ -0,0 +1,57 @@
+use super::{
+    NbtDeserError,
+    NbtSource, NbtRead,
+    NbtRootDeserer, TagRead,
+    NbtErrorSegment,
+    tag
+};
+use core::mem;
+use std::borrow::Cow;
+use serde::de::{
+    DeserializeSeed,
+    value::BorrowedStrDeserializer,
+    IntoDeserializer,
+    MapAccess
+};
+
+
+pub(super) struct NbtMapDeserer<'de, 'l, R> {
+    reader    : &'l mut NbtSource<R>,
+    value_tag : u8,
+    key       : Cow<'de, str>
+}
+
+impl<'l, R> From<&'l mut NbtSource<R>> for NbtMapDeserer<'_, 'l, R> { fn from(reader : &'l mut NbtSource<R>) -> Self { Self {
+    reader, value_tag : tag::END, key : Cow::Borrowed("")
+} } }
+
+
+impl<'de, R> MapAccess<'de> for NbtMapDeserer<'de, '_, R>
+where
+    R : NbtRead<'de>
+{
+    type Error = NbtDeserError;
+
+    fn next_key_seed<K>(&mut self, seed : K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K : DeserializeSeed<'de>
+    {
+        self.value_tag = self.reader.read_u8()?;
+        if (self.value_tag == tag::END) { return Ok(None); }
+        // Keep the key, so that it can be added to the path of any error in the value.
+        self.key = self.reader.read_string()?;
+        match (&self.key) {
+            Cow::Borrowed(key) => seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some),
+            Cow::Owned(key)    => seed.deserialize(key.as_str().into_deserializer()).map(Some)
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed : V) -> Result<V::Value, Self::Error>
+    where
+        V : DeserializeSeed<'de>
+    {
+        seed.deserialize(&mut NbtRootDeserer::new(&mut*self.reader, TagRead::Known(self.value_tag)))
+            .map_err(|err| err.within(NbtErrorSegment::Key(mem::take(&mut self.key).into_owned()), self.reader.accounter().offset()))
+    }
+
+}
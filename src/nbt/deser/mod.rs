@@ -1,4 +1,10 @@
-use super::tag;
+use super::{
+    SnbtParseError,
+    NbtCompression,
+    NbtLimits,
+    NbtLimit,
+    tag
+};
 use core::{
     error::Error as StdError,
     fmt::{ self, Display, Formatter }
@@ -9,10 +15,25 @@ use serde::de::{
     Deserialize as Deser
 };
 use cesu8::Cesu8DecodingError;
+use flate2::read::{ GzDecoder, ZlibDecoder };
 
 
+mod source;
+use source::{ NbtSource, NbtRead, IoRead, SliceRead };
+mod path;
+pub use path::*;
 mod root;
 use root::{ NbtRootDeserer, TagRead };
+mod seq;
+use seq::NbtSeqDeserer;
+mod map;
+use map::NbtMapDeserer;
+mod array;
+use array::NbtArrayMapAccess;
+mod variant;
+use variant::NbtVariantDeserer;
+mod visit;
+pub use visit::*;
 
 
 /// Errors emitted while deserialising NBT as a value.
@@ -28,7 +49,25 @@ pub enum NbtDeserError {
     /// A badly encoded string element was found.
     BadString(Cesu8DecodingError),
     /// A badly encoded char element was found.
-    BadChar
+    BadChar,
+    /// A negative list or array length, or a non-empty list with an end element tag, was found.
+    BadLength(i32),
+    /// An enum variant compound did not contain exactly one entry.
+    BadVariant,
+    /// Badly formed SNBT was found.
+    BadSnbt(SnbtParseError),
+    /// Decoding used more resources than allowed by its [`NbtLimits`].
+    LimitExceeded(NbtLimit),
+
+    /// An error occured at a known position in the input.
+    At {
+        /// The path to the element which failed to decode.
+        path   : NbtErrorPath,
+        /// The offset of the first byte which was not decoded.
+        offset : usize,
+        /// The error.
+        err    : Box<NbtDeserError>
+    }
 }
 impl From<io::Error> for NbtDeserError {
     #[inline]
@@ -42,17 +81,76 @@ impl From<Cesu8DecodingError> for NbtDeserError {
         Self::BadString(err)
     }
 }
+impl From<SnbtParseError> for NbtDeserError {
+    #[inline]
+    fn from(err : SnbtParseError) -> Self {
+        Self::BadSnbt(err)
+    }
+}
 
 impl Display for NbtDeserError {
     fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result { match (self) {
-        Self::Io(err)        => write!(f, "{err}"),
-        Self::Custom(err)    => write!(f, "{err}"),
-        Self::UnknownTag(v)  => write!(f, "unknown tag 0x{v:0>2X}"),
-        Self::BadString(err) => write!(f, "bad string {err}"),
-        Self::BadChar        => write!(f, "bad char")
+        Self::Io(err)              => write!(f, "{err}"),
+        Self::Custom(err)          => write!(f, "{err}"),
+        Self::UnknownTag(v)        => write!(f, "unknown tag 0x{v:0>2X}"),
+        Self::BadString(err)       => write!(f, "bad string {err}"),
+        Self::BadChar              => write!(f, "bad char"),
+        Self::BadLength(v)         => write!(f, "bad length {v}"),
+        Self::BadVariant           => write!(f, "bad enum variant"),
+        Self::BadSnbt(err)         => write!(f, "bad SNBT {err}"),
+        Self::LimitExceeded(limit) => write!(f, "exceeded {limit}"),
+        Self::At { path, offset, err } => {
+            if (path.is_empty()) {
+                write!(f, "{err} at byte {offset}")
+            } else {
+                write!(f, "{err} at {path} (byte {offset})")
+            }
+        }
+    } }
+}
+impl StdError for NbtDeserError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> { match (self) {
+        Self::At { err, .. } => Some(&**err),
+        _                    => None
+    } }
+}
+impl NbtDeserError {
+
+    /// Returns this error without its position in the input.
+    pub fn kind(&self) -> &NbtDeserError { match (self) {
+        Self::At { err, .. } => err.kind(),
+        err                  => err
+    } }
+
+    /// Returns the path to the element which failed to decode, if known.
+    pub fn path(&self) -> Option<&NbtErrorPath> { match (self) {
+        Self::At { path, .. } => Some(path),
+        _                     => None
+    } }
+
+    /// Returns the offset of the first byte which was not decoded, if known.
+    pub fn offset(&self) -> Option<usize> { match (self) {
+        Self::At { offset, .. } => Some(*offset),
+        _                       => None
+    } }
+
+    /// Adds a segment to the front of the path of this error,
+    ///  positioning it at `offset` if it has no position yet.
+    pub(in crate::nbt) fn within(self, segment : NbtErrorSegment, offset : usize) -> Self { match (self) {
+        Self::At { mut path, offset, err } => {
+            path.segments.insert(0, segment);
+            Self::At { path, offset, err }
+        },
+        err => Self::At { path : NbtErrorPath { segments : vec![segment] }, offset, err : Box::new(err) }
+    } }
+
+    /// Positions this error at `offset` in the root element, if it has no position yet.
+    pub(in crate::nbt) fn at(self, offset : usize) -> Self { match (self) {
+        err @ Self::At { .. } => err,
+        err                   => Self::At { path : NbtErrorPath::default(), offset, err : Box::new(err) }
     } }
+
 }
-impl StdError for NbtDeserError { }
 impl DeserError for NbtDeserError {
     fn custom<T>(msg : T) -> Self
     where
@@ -63,11 +161,265 @@ impl DeserError for NbtDeserError {
 }
 
 
-/// Deserialise network NBT as a value.
-pub fn from_network<R, T>(mut reader : R) -> Result<T, NbtDeserError>
+/// Deserialise network NBT as a value, within [`NbtLimits::NETWORK`].
+#[inline]
+pub fn from_network<R, T>(reader : R) -> Result<T, NbtDeserError>
+where
+            R : Read,
+    for<'l> T : Deser<'l>
+{ from_network_with_limits(reader, NbtLimits::NETWORK) }
+
+/// Deserialise network NBT as a value, within the given limits.
+#[inline]
+pub fn from_network_with_limits<R, T>(reader : R, limits : NbtLimits) -> Result<T, NbtDeserError>
 where
             R : Read,
     for<'l> T : Deser<'l>
+{ read_network(IoRead(reader), limits) }
+
+/// Deserialise network NBT from a slice as a value, within [`NbtLimits::NETWORK`].
+///
+/// Strings and byte arrays are borrowed from `slice` where the value allows it,
+///  and the string is already valid UTF-8.
+#[inline]
+pub fn from_network_slice<'de, T>(slice : &'de [u8]) -> Result<T, NbtDeserError>
+where
+    T : Deser<'de>
+{ from_network_slice_with_limits(slice, NbtLimits::NETWORK) }
+
+/// Deserialise network NBT from a slice as a value, within the given limits.
+///
+/// Strings and byte arrays are borrowed from `slice` where the value allows it,
+///  and the string is already valid UTF-8.
+#[inline]
+pub fn from_network_slice_with_limits<'de, T>(slice : &'de [u8], limits : NbtLimits) -> Result<T, NbtDeserError>
+where
+    T : Deser<'de>
+{ read_network(SliceRead(slice), limits) }
+
+fn read_network<'de, R, T>(reader : R, limits : NbtLimits) -> Result<T, NbtDeserError>
+where
+    R : NbtRead<'de>,
+    T : Deser<'de>
 {
-    T::deserialize(&mut NbtRootDeserer::new(&mut reader, TagRead::Tag))
+    let mut source = NbtSource::new(reader, limits);
+    let     value  = T::deserialize(&mut NbtRootDeserer::new(&mut source, TagRead::Tag));
+    value.map_err(|err| err.at(source.accounter().offset()))
+}
+
+
+/// Deserialise an NBT file with a named root as a value, returning the root name alongside it.
+///
+/// Decoding is bound by [`NbtLimits::FILE`]. Error offsets are into the decompressed data.
+pub fn from_file<R, T>(reader : R, compression : NbtCompression) -> Result<(String, T,), NbtDeserError>
+where
+            R : Read,
+    for<'l> T : Deser<'l>
+{
+    fn read_root<R, T>(reader : R) -> Result<(String, T,), NbtDeserError>
+    where
+                R : Read,
+        for<'l> T : Deser<'l>
+    {
+        let mut source = NbtSource::new(IoRead(reader), NbtLimits::FILE);
+        let     root   = (|| {
+            let tag       = source.read_u8()?;
+            let root_name = source.read_string()?.into_owned();
+            let value     = T::deserialize(&mut NbtRootDeserer::new(&mut source, TagRead::Known(tag)))?;
+            Ok((root_name, value,))
+        })();
+        root.map_err(|err : NbtDeserError| err.at(source.accounter().offset()))
+    }
+    match (compression) {
+        NbtCompression::None => read_root(reader),
+        NbtCompression::Gzip => read_root(GzDecoder::new(reader)),
+        NbtCompression::Zlib => read_root(ZlibDecoder::new(reader))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::nbt::{ *, tag };
+    use std::borrow::Cow;
+    use serde::{
+        Serialize as Ser,
+        Deserialize as Deser
+    };
+
+    #[derive(Ser)]
+    struct Arrays {
+        #[serde(with = "iarray")]
+        ints  : Vec<i32>,
+        #[serde(with = "larray")]
+        longs : Vec<i64>
+    }
+
+    #[derive(Deser, PartialEq, Debug)]
+    #[serde(untagged)]
+    enum Untagged {
+        Seq(Vec<i64>)
+    }
+
+    #[derive(Deser, PartialEq, Debug)]
+    struct UntaggedArrays {
+        ints  : Untagged,
+        longs : Untagged
+    }
+
+    #[test]
+    fn arrays_are_sequences_to_self_describing_visitors() {
+        let mut buf = Vec::new();
+        to_network(&mut buf, &Arrays { ints : vec![1, 2], longs : vec![3] }).unwrap();
+        let expected = UntaggedArrays { ints : Untagged::Seq(vec![1, 2]), longs : Untagged::Seq(vec![3]) };
+        assert_eq!(from_network::<_, UntaggedArrays>(&*buf).unwrap(), expected);
+        let element = from_network::<_, NbtElement>(&*buf).unwrap();
+        assert_eq!(element.to_string(), "{ints:[I;1,2],longs:[L;3L]}");
+        assert_eq!(from_element::<UntaggedArrays>(&element).unwrap(), expected);
+        assert_eq!(from_element::<NbtElement>(&element).unwrap(), element);
+    }
+
+
+    fn bad_length(err : NbtDeserError) -> Option<i32> { match (err) {
+        NbtDeserError::At { err, .. } => bad_length(*err),
+        NbtDeserError::BadLength(v)   => Some(v),
+        _                             => None
+    } }
+
+    struct Ignore;
+    impl NbtVisitor<'_> for Ignore { }
+
+    #[test]
+    fn bad_list_lengths() {
+        // {a:[-1 ints]} and {a:[1 ends]}
+        for (elem_tag, len,) in [(tag::INT, -1i32,), (tag::END, 1,)] {
+            let mut buf = vec![tag::COMPOUND, tag::LIST, 0, 1, b'a', elem_tag];
+            buf.extend_from_slice(&len.to_be_bytes());
+            buf.push(tag::END);
+            assert_eq!(bad_length(from_network_slice::<NbtElement>(&buf).unwrap_err()), Some(len));
+            assert_eq!(bad_length(visit_network_slice(&buf, &mut Ignore).unwrap_err()), Some(len));
+        }
+    }
+
+
+    /// Records the callbacks it receives, skipping or stopping at the given keys.
+    #[derive(Default)]
+    struct Record {
+        events     : Vec<String>,
+        skip_key   : Option<&'static str>,
+        stop_key   : Option<&'static str>,
+        skip_lists : bool
+    }
+    impl NbtVisitor<'_> for Record {
+        fn begin_compound(&mut self) -> NbtVisit {
+            self.events.push("{".to_string());
+            NbtVisit::Continue
+        }
+        fn key(&mut self, key : &str) -> NbtVisit {
+            self.events.push(format!("{key}:"));
+            if (self.stop_key == Some(key)) { NbtVisit::Stop }
+            else if (self.skip_key == Some(key)) { NbtVisit::Skip }
+            else { NbtVisit::Continue }
+        }
+        fn end_compound(&mut self) -> NbtVisit {
+            self.events.push("}".to_string());
+            NbtVisit::Continue
+        }
+        fn begin_list(&mut self, len : usize) -> NbtVisit {
+            self.events.push(format!("[{len}"));
+            if (self.skip_lists) { NbtVisit::Skip } else { NbtVisit::Continue }
+        }
+        fn end_list(&mut self) -> NbtVisit {
+            self.events.push("]".to_string());
+            NbtVisit::Continue
+        }
+        fn value(&mut self, element : NbtElement<'_>) -> NbtVisit {
+            self.events.push(element.to_string());
+            NbtVisit::Continue
+        }
+    }
+
+    fn visit_events(buf : &[u8], mut visitor : Record) -> Vec<String> {
+        visit_network_slice(buf, &mut visitor).unwrap();
+        visitor.events
+    }
+
+    #[test]
+    fn visitor_callbacks() {
+        let element = NbtElement::from_snbt(r#"{a:1b,l:[1,2],n:{m:[L;1L],s:"x"},mixed:[1b,"y"],e:[],z:2}"#).unwrap();
+        let mut buf = Vec::new();
+        to_network(&mut buf, &element).unwrap();
+        assert_eq!(visit_events(&buf, Record::default()), [
+            "{", "a:", "1b", "l:", "[2", "1", "2", "]", "n:", "{", "m:", "[L;1L]", "s:", "\"x\"", "}",
+            "mixed:", "[2", "{", ":", "1b", "}", "{", ":", "\"y\"", "}", "]", "e:", "[0", "]", "z:", "2", "}"
+        ]);
+        // Skipped elements are read past without reporting their contents.
+        assert_eq!(visit_events(&buf, Record { skip_key : Some("n"), ..Record::default() }), [
+            "{", "a:", "1b", "l:", "[2", "1", "2", "]", "n:",
+            "mixed:", "[2", "{", ":", "1b", "}", "{", ":", "\"y\"", "}", "]", "e:", "[0", "]", "z:", "2", "}"
+        ]);
+        assert_eq!(visit_events(&buf, Record { skip_lists : true, ..Record::default() }), [
+            "{", "a:", "1b", "l:", "[2", "n:", "{", "m:", "[L;1L]", "s:", "\"x\"", "}", "mixed:", "[2", "e:", "[0", "z:", "2", "}"
+        ]);
+        // Stopping leaves the rest of the input unread, starting with the payload of `n`.
+        let mut r = &buf[..];
+        let mut visitor = Record { stop_key : Some("n"), ..Record::default() };
+        visit_network(&mut r, &mut visitor).unwrap();
+        assert_eq!(visitor.events, ["{", "a:", "1b", "l:", "[2", "1", "2", "]", "n:"]);
+        assert_eq!(r.first(), Some(&tag::LARRAY));
+    }
+
+    #[test]
+    fn visit_files() {
+        let element = NbtElement::from_snbt(r#"{a:[I;1,2],b:[B;-1b]}"#).unwrap();
+        for compression in [NbtCompression::None, NbtCompression::Gzip, NbtCompression::Zlib] {
+            let mut buf = Vec::new();
+            to_file(&mut buf, compression, "root", &element).unwrap();
+            let mut visitor = Record::default();
+            assert_eq!(visit_file(&*buf, compression, &mut visitor).unwrap(), "root");
+            assert_eq!(visitor.events, ["{", "a:", "[I;1,2]", "b:", "[B;-1B]", "}"]);
+        }
+        // Files are not bound by the byte limit, so lengths from corrupt files must not be allocated up front.
+        for array_tag in [tag::BARRAY, tag::IARRAY, tag::LARRAY] {
+            let mut buf = vec![array_tag, 0, 0];
+            buf.extend_from_slice(&i32::MAX.to_be_bytes());
+            buf.extend_from_slice(&[0; 16]);
+            assert!(visit_file(&*buf, NbtCompression::None, &mut Ignore).is_err());
+            assert!(from_file::<_, NbtElement>(&*buf, NbtCompression::None).is_err());
+        }
+    }
+
+
+    #[derive(Deser)]
+    struct Borrowing<'l> {
+        #[serde(borrow, deserialize_with = "NbtElement::deserialize_borrowed")]
+        element  : NbtElement<'l>,
+        #[serde(borrow, deserialize_with = "NbtCompound::deserialize_borrowed")]
+        compound : NbtCompound<'l>
+    }
+
+    #[test]
+    fn borrowed_elements() {
+        let mut compound = NbtCompound::new();
+        compound.insert("key", NbtElement::String("value".into()));
+        compound.insert("bytes", NbtElement::BArray(vec![1, -1].into()));
+        let mut outer = NbtCompound::new();
+        outer.insert("element", NbtElement::Compound(compound.clone()));
+        outer.insert("compound", NbtElement::Compound(compound.clone()));
+        let mut buf = Vec::new();
+        to_network(&mut buf, &outer).unwrap();
+
+        let value = from_network_slice::<Borrowing>(&buf).unwrap();
+        let NbtElement::Compound(element) = &value.element else { panic!("expected compound"); };
+        for borrowed in [element, &value.compound] {
+            assert_eq!(borrowed, &compound);
+            assert!(borrowed.entries.iter().all(|(key, _,)| matches!(key, Cow::Borrowed(_))));
+            assert!(matches!(borrowed.get("key"), Some(NbtElement::String(Cow::Borrowed(_)))));
+            assert!(matches!(borrowed.get("bytes"), Some(NbtElement::BArray(Cow::Borrowed(_)))));
+        }
+        let NbtElement::Compound(owned) = from_network_slice::<NbtElement>(&buf).unwrap() else { panic!("expected compound"); };
+        let Some(NbtElement::Compound(owned)) = owned.get("compound") else { panic!("expected compound"); };
+        assert!(matches!(owned.get("key"), Some(NbtElement::String(Cow::Owned(_)))));
+    }
+
 }
@@ -0,0 +1,46 @@
+use crate::nbt::write_quoted;
+use core::fmt::{ self, Display, Formatter };
+
+
+/// The keys and indices leading to an element in some NBT, written like `effects.music[2].sound`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct NbtErrorPath {
+    /// The segments of this path, outermost first.
+    pub segments : Vec<NbtErrorSegment>
+}
+
+/// A segment of an [`NbtErrorPath`].
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum NbtErrorSegment {
+    /// An entry in a compound.
+    Key(String),
+    /// An element in a list or array.
+    Index(usize)
+}
+
+
+impl NbtErrorPath {
+
+    /// Returns `true` if this path points to the root element.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.segments.is_empty() }
+
+}
+
+
+impl Display for NbtErrorPath {
+    fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result {
+        for (i, segment,) in self.segments.iter().enumerate() { match (segment) {
+            NbtErrorSegment::Key(key) => {
+                if (i > 0) { write!(f, ".")?; }
+                if (! key.is_empty() && key.chars().all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '+'))) {
+                    write!(f, "{key}")?;
+                } else {
+                    write_quoted(f, key)?;
+                }
+            },
+            NbtErrorSegment::Index(index) => write!(f, "[{index}]")?
+        } }
+        Ok(())
+    }
+}
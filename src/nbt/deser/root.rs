@@ -1,113 +1,105 @@
 use super::{
     NbtDeserError,
+    NbtSource, NbtRead,
+    NbtSeqDeserer,
+    NbtMapDeserer,
+    NbtArrayMapAccess,
+    NbtVariantDeserer,
     tag
 };
-use std::{
-    borrow::Cow,
-    io::{ self, Read }
+use crate::nbt::{
+    IARRAY_TOKEN,
+    LARRAY_TOKEN,
+    ELEMENT_TOKEN
 };
-use cesu8::from_java_cesu8;
+use std::borrow::Cow;
 use serde::de::{
     Deserializer as Deserer,
+    IgnoredAny,
+    IntoDeserializer,
     Visitor
 };
 
 
-pub(super) struct NbtRootDeserer<'l, R>
-where
-    R : Read
-{
-    reader    : &'l mut R,
+pub(super) struct NbtRootDeserer<'l, R> {
+    reader   : &'l mut NbtSource<R>,
     tag_read : TagRead
 }
 
-impl<'l, R> NbtRootDeserer<'l, R>
-where
-    R : Read
-{
-
-    pub(super) fn new(reader : &'l mut R, tag_read : TagRead) -> Self {
+impl<'l, R> NbtRootDeserer<'l, R> {
+    pub(super) fn new(reader : &'l mut NbtSource<R>, tag_read : TagRead) -> Self {
         Self { reader, tag_read }
     }
+}
 
-    fn handle_tag_read(&mut self, expected_tag : u8) -> Result<(), NbtDeserError> { match (&self.tag_read) {
-        TagRead::None => Ok(()),
-        TagRead::Tag => {
-            let tag = self.read_u8()?;
-            if (tag == expected_tag) {
-                Ok(())
-            } else {
-                Err(NbtDeserError::UnknownTag(tag))
-            }
-        }
-    } }
-
-    fn read_u8(&mut self) -> io::Result<u8> {
-        let mut buf = [0u8];
-        self.reader.read_exact(&mut buf)?;
-        Ok(buf[0])
-    }
-
-    fn read_u16(&mut self) -> io::Result<u16> {
-        let mut buf = [0u8; 2];
-        self.reader.read_exact(&mut buf)?;
-        Ok(u16::from_be_bytes(buf))
-    }
-
-    fn read_u32(&mut self) -> io::Result<u32> {
-        let mut buf = [0u8; 4];
-        self.reader.read_exact(&mut buf)?;
-        Ok(u32::from_be_bytes(buf))
-    }
-
-    fn read_u64(&mut self) -> io::Result<u64> {
-        let mut buf = [0u8; 8];
-        self.reader.read_exact(&mut buf)?;
-        Ok(u64::from_be_bytes(buf))
-    }
+impl<'de, R> NbtRootDeserer<'_, R>
+where
+    R : NbtRead<'de>
+{
 
-    fn read_f32(&mut self) -> io::Result<f32> {
-        let mut buf = [0u8; 4];
-        self.reader.read_exact(&mut buf)?;
-        Ok(f32::from_be_bytes(buf))
+    fn read_tag(&mut self) -> Result<u8, NbtDeserError> {
+        let tag = match (self.tag_read) {
+            TagRead::Known(tag) => tag,
+            TagRead::Tag        => self.reader.read_u8()?
+        };
+        self.tag_read = TagRead::Known(tag);
+        Ok(tag)
+    }
+
+    fn handle_tag_read(&mut self, expected_tag : u8) -> Result<(), NbtDeserError> {
+        let tag = self.read_tag()?;
+        if (tag == expected_tag) {
+            Ok(())
+        } else {
+            Err(NbtDeserError::UnknownTag(tag))
+        }
     }
 
-    fn read_f64(&mut self) -> io::Result<f64> {
-        let mut buf = [0u8; 8];
-        self.reader.read_exact(&mut buf)?;
-        Ok(f64::from_be_bytes(buf))
+    fn read_array_header(&mut self, elem_tag : u8) -> Result<(u8, usize,), NbtDeserError> {
+        let len = self.reader.read_len()?;
+        self.reader.accounter().check_list(elem_tag, len)?;
+        Ok((elem_tag, len,))
     }
 
-    fn read_string(&mut self) -> Result<String, NbtDeserError> {
-        let     len = self.read_u16()? as usize;
-        let mut buf = Box::new_uninit_slice(len);
-        self.reader.read_exact(unsafe { buf.assume_init_mut() })?;
-        let buf    = unsafe { buf.assume_init() };
-        Ok(match (from_java_cesu8(&buf)?) {
-            Cow::Owned(v)    => v,
-            Cow::Borrowed(_) => unsafe { String::from_utf8_unchecked(buf.into_vec()) }
-        })
-    }
+    /// Presents any element to a visitor, with int and long arrays as a single entry map keyed by their marker token,
+    ///  so that [`NbtElement`](crate::nbt::NbtElement) can tell them apart from lists.
+    fn deserialize_element<V>(&mut self, visitor : V) -> Result<V::Value, NbtDeserError>
+    where
+        V : Visitor<'de>
+    { match (self.read_tag()?) {
+        tag::IARRAY => visitor.visit_map(NbtArrayMapAccess::new(self.reader, IARRAY_TOKEN, tag::INT)),
+        tag::LARRAY => visitor.visit_map(NbtArrayMapAccess::new(self.reader, LARRAY_TOKEN, tag::LONG)),
+        _           => self.deserialize_any(visitor)
+    } }
 
-    fn read_barray(&mut self) -> io::Result<Vec<u8>> {
-        let    len = self.read_u32()? as usize;
-        let mut buf = Box::new_uninit_slice(len);
-        self.reader.read_exact(unsafe { buf.assume_init_mut() })?;
-        Ok(unsafe { buf.assume_init() }.into_vec())
+    /// Reads the header of a list or array, returning the element tag and length.
+    fn read_seq_header(&mut self) -> Result<(u8, usize,), NbtDeserError> {
+        match (self.read_tag()?) {
+            tag::LIST => {
+                let elem_tag = self.reader.read_u8()?;
+                let len      = self.reader.read_len()?;
+                self.reader.accounter().check_list(elem_tag, len)?;
+                Ok((elem_tag, len,))
+            },
+            tag::BARRAY => self.read_array_header(tag::BYTE),
+            tag::IARRAY => self.read_array_header(tag::INT),
+            tag::LARRAY => self.read_array_header(tag::LONG),
+            v           => Err(NbtDeserError::UnknownTag(v))
+        }
     }
 
 }
 
 
 pub(super) enum TagRead {
-    None,
+    Known(u8),
     Tag
 }
 
 
 impl<'de, R> Deserer<'de> for &mut NbtRootDeserer<'_, R>
 where
-    R : Read
+    R : NbtRead<'de>
 {
     type Error = NbtDeserError;
 
@@ -115,25 +107,20 @@ where
     where
         V : Visitor<'de>
     {
-        let TagRead::Tag = self.tag_read
-            else { panic!("Can not tagless any NBT deserialize."); };
-        let mut prefix = [0u8];
-        self.reader.read_exact(&mut prefix)?;
-        self.tag_read = TagRead::None;
-        match (prefix[0]) {
+        match (self.read_tag()?) {
             tag::END      => self.deserialize_unit(visitor),
             tag::BYTE     => self.deserialize_i8(visitor),
             tag::SHORT    => self.deserialize_i16(visitor),
             tag::INT      => self.deserialize_i32(visitor),
             tag::LONG     => self.deserialize_i64(visitor),
             tag::FLOAT    => self.deserialize_f32(visitor),
-            tag::DOUBLE   => self.deserialize_i64(visitor),
-            // tODO: BArray
+            tag::DOUBLE   => self.deserialize_f64(visitor),
+            tag::BARRAY   => self.deserialize_byte_buf(visitor),
             tag::STRING   => self.deserialize_string(visitor),
-            tag::LIST     => self.deserialize_seq(visitor),
+            tag::LIST
+            | tag::IARRAY
+            | tag::LARRAY => self.deserialize_seq(visitor),
             tag::COMPOUND => self.deserialize_map(visitor),
-            // TODO: IArray
-            // TODO: LArray
             v => Err(NbtDeserError::UnknownTag(v))
         }
     }
@@ -143,7 +130,7 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::BYTE)?;
-        visitor.visit_bool(self.read_u8()? != 0)
+        visitor.visit_bool(self.reader.read_u8()? != 0)
     }
 
     fn deserialize_i8<V>(self, visitor : V) -> Result<V::Value, Self::Error>
@@ -151,7 +138,7 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::BYTE)?;
-        visitor.visit_i8(self.read_u8()?.cast_signed())
+        visitor.visit_i8(self.reader.read_u8()?.cast_signed())
     }
 
     fn deserialize_i16<V>(self, visitor : V) -> Result<V::Value, Self::Error>
@@ -159,7 +146,7 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::SHORT)?;
-        visitor.visit_i16(self.read_u16()?.cast_signed())
+        visitor.visit_i16(self.reader.read_u16()?.cast_signed())
     }
 
     fn deserialize_i32<V>(self, visitor : V) -> Result<V::Value, Self::Error>
@@ -167,7 +154,7 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::INT)?;
-        visitor.visit_i32(self.read_u32()?.cast_signed())
+        visitor.visit_i32(self.reader.read_u32()?.cast_signed())
     }
 
     fn deserialize_i64<V>(self, visitor : V) -> Result<V::Value, Self::Error>
@@ -175,7 +162,7 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::LONG)?;
-        visitor.visit_i64(self.read_u64()?.cast_signed())
+        visitor.visit_i64(self.reader.read_u64()?.cast_signed())
     }
 
     fn deserialize_u8<V>(self, visitor : V) -> Result<V::Value, Self::Error>
@@ -183,7 +170,7 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::BYTE)?;
-        visitor.visit_u8(self.read_u8()?)
+        visitor.visit_u8(self.reader.read_u8()?)
     }
 
     fn deserialize_u16<V>(self, visitor : V) -> Result<V::Value, Self::Error>
@@ -191,7 +178,7 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::SHORT)?;
-        visitor.visit_u16(self.read_u16()?)
+        visitor.visit_u16(self.reader.read_u16()?)
     }
 
     fn deserialize_u32<V>(self, visitor : V) -> Result<V::Value, Self::Error>
@@ -199,7 +186,7 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::INT)?;
-        visitor.visit_u32(self.read_u32()?)
+        visitor.visit_u32(self.reader.read_u32()?)
     }
 
     fn deserialize_u64<V>(self, visitor : V) -> Result<V::Value, Self::Error>
@@ -207,7 +194,7 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::LONG)?;
-        visitor.visit_u64(self.read_u64()?)
+        visitor.visit_u64(self.reader.read_u64()?)
     }
 
     fn deserialize_f32<V>(self, visitor : V) -> Result<V::Value, Self::Error>
@@ -215,7 +202,7 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::FLOAT)?;
-        visitor.visit_f32(self.read_f32()?)
+        visitor.visit_f32(self.reader.read_f32()?)
     }
 
     fn deserialize_f64<V>(self, visitor : V) -> Result<V::Value, Self::Error>
@@ -223,7 +210,7 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::DOUBLE)?;
-        visitor.visit_f64(self.read_f64()?)
+        visitor.visit_f64(self.reader.read_f64()?)
     }
 
     fn deserialize_char<V>(self, visitor : V) -> Result<V::Value, Self::Error>
@@ -231,7 +218,7 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::STRING)?;
-        let     string = self.read_string()?;
+        let     string = self.reader.read_string()?;
         let mut chars  = string.chars();
         let (Some(char), None,) = (chars.next(), chars.next(),)
             else { return Err(NbtDeserError::BadChar); };
@@ -243,7 +230,10 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::STRING)?;
-        visitor.visit_string(self.read_string()?)
+        match (self.reader.read_string()?) {
+            Cow::Borrowed(v) => visitor.visit_borrowed_str(v),
+            Cow::Owned(v)    => visitor.visit_string(v)
+        }
     }
 
     fn deserialize_string<V>(self, visitor : V) -> Result<V::Value, Self::Error>
@@ -251,31 +241,37 @@ where
         V : Visitor<'de>
     {
         self.handle_tag_read(tag::STRING)?;
-        visitor.visit_string(self.read_string()?)
+        match (self.reader.read_string()?) {
+            Cow::Borrowed(v) => visitor.visit_borrowed_str(v),
+            Cow::Owned(v)    => visitor.visit_string(v)
+        }
     }
 
+    #[inline]
     fn deserialize_bytes<V>(self, visitor : V) -> Result<V::Value, Self::Error>
     where
         V : Visitor<'de>
-    {
-        self.handle_tag_read(tag::BARRAY)?;
-        visitor.visit_byte_buf(self.read_barray()?)
-    }
+    { self.deserialize_byte_buf(visitor) }
 
     fn deserialize_byte_buf<V>(self, visitor : V) -> Result<V::Value, Self::Error>
     where
         V : Visitor<'de>
     {
-        self.handle_tag_read(tag::BARRAY)?;
-        visitor.visit_byte_buf(self.read_barray()?)
+        if (self.read_tag()? == tag::BARRAY) {
+            match (self.reader.read_barray()?) {
+                Cow::Borrowed(v) => visitor.visit_borrowed_bytes(v),
+                Cow::Owned(v)    => visitor.visit_byte_buf(v)
+            }
+        } else {
+            self.deserialize_seq(visitor)
+        }
     }
 
-    fn deserialize_option<V>(self, _visitor : V) -> Result<V::Value, Self::Error>
+    #[inline]
+    fn deserialize_option<V>(self, visitor : V) -> Result<V::Value, Self::Error>
     where
         V : Visitor<'de>
-    {
-        todo!()
-    }
+    { visitor.visit_some(self) }
 
     fn deserialize_unit<V>(self, visitor : V) -> Result<V::Value, Self::Error>
     where
@@ -287,86 +283,105 @@ where
 
     fn deserialize_unit_struct<V>(
         self,
-        _name    : &'static str,
-        _visitor : V,
+        _name   : &'static str,
+        visitor : V,
     ) -> Result<V::Value, Self::Error>
     where
         V : Visitor<'de>
     {
-        unreachable!("unit structs are not supported by the NBT deserialiser");
+        self.deserialize_map(IgnoredAny)?;
+        visitor.visit_unit()
     }
 
     fn deserialize_newtype_struct<V>(
         self,
-        _name    : &'static str,
-        _visitor : V,
+        name    : &'static str,
+        visitor : V,
     ) -> Result<V::Value, Self::Error>
     where
         V : Visitor<'de>
     {
-        unreachable!("newtype structs are not supported by the NBT deserialiser");
+        if (name == ELEMENT_TOKEN) {
+            self.deserialize_element(visitor)
+        } else {
+            visitor.visit_newtype_struct(self)
+        }
     }
 
-    fn deserialize_seq<V>(self, _visitor : V) -> Result<V::Value, Self::Error>
+    fn deserialize_seq<V>(self, visitor : V) -> Result<V::Value, Self::Error>
     where
-        V : Visitor<'de> {
-        todo!()
+        V : Visitor<'de>
+    {
+        let (elem_tag, len,) = self.read_seq_header()?;
+        self.reader.accounter().push_depth()?;
+        let value = visitor.visit_seq(NbtSeqDeserer::new(&mut*self.reader, elem_tag, len))?;
+        self.reader.accounter().pop_depth();
+        Ok(value)
     }
 
-    fn deserialize_tuple<V>(self, _len : usize, _visitor : V) -> Result<V::Value, Self::Error>
+    #[inline]
+    fn deserialize_tuple<V>(self, _len : usize, visitor : V) -> Result<V::Value, Self::Error>
     where
-        V : Visitor<'de> {
-        todo!()
-    }
+        V : Visitor<'de>
+    { self.deserialize_seq(visitor) }
 
+    #[inline]
     fn deserialize_tuple_struct<V>(
         self,
-        _name    : &'static str,
-        _len     : usize,
-        _visitor : V,
+        _name   : &'static str,
+        _len    : usize,
+        visitor : V,
     ) -> Result<V::Value, Self::Error>
     where
         V : Visitor<'de>
-    {
-        unreachable!("tuple structs are not supported by the NBT deserialiser");
-    }
+    { self.deserialize_seq(visitor) }
 
-    fn deserialize_map<V>(self, _visitor : V) -> Result<V::Value, Self::Error>
+    fn deserialize_map<V>(self, visitor : V) -> Result<V::Value, Self::Error>
     where
         V : Visitor<'de>
     {
-        todo!()
+        self.handle_tag_read(tag::COMPOUND)?;
+        self.reader.accounter().push_depth()?;
+        let value = visitor.visit_map(NbtMapDeserer::from(&mut*self.reader))?;
+        self.reader.accounter().pop_depth();
+        Ok(value)
     }
 
+    #[inline]
     fn deserialize_struct<V>(
         self,
-        _name    : &'static str,
-        _fields  : &'static [&'static str],
-        _visitor : V,
+        _name   : &'static str,
+        _fields : &'static [&'static str],
+        visitor : V,
     ) -> Result<V::Value, Self::Error>
     where
-        V : Visitor<'de> {
-        todo!()
-    }
+        V : Visitor<'de>
+    { self.deserialize_map(visitor) }
 
     fn deserialize_enum<V>(
         self,
         _name     : &'static str,
         _variants : &'static [&'static str],
-        _visitor  : V,
+        visitor   : V,
     ) -> Result<V::Value, Self::Error>
     where
         V : Visitor<'de>
-    {
-        todo!()
-    }
+    { match (self.read_tag()?) {
+        tag::STRING   => visitor.visit_enum(self.reader.read_string()?.into_deserializer()),
+        tag::COMPOUND => {
+            self.reader.accounter().push_depth()?;
+            let value = visitor.visit_enum(NbtVariantDeserer::from(&mut*self.reader))?;
+            self.reader.accounter().pop_depth();
+            Ok(value)
+        },
+        v             => Err(NbtDeserError::UnknownTag(v))
+    } }
 
-    fn deserialize_identifier<V>(self, _visitor : V) -> Result<V::Value, Self::Error>
+    #[inline]
+    fn deserialize_identifier<V>(self, visitor : V) -> Result<V::Value, Self::Error>
     where
         V : Visitor<'de>
-    {
-        unreachable!("identifiers are not supported by the NBT deserialiser");
-    }
+    { self.deserialize_string(visitor) }
 
     fn deserialize_ignored_any<V>(self, visitor : V) -> Result<V::Value, Self::Error>
     where
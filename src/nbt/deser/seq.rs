@@ -0,0 +1,47 @@
+use super::{
+    NbtDeserError,
+    NbtSource, NbtRead,
+    NbtRootDeserer, TagRead,
+    NbtErrorSegment
+};
+use serde::de::{
+    DeserializeSeed,
+    SeqAccess
+};
+
+
+pub(super) struct NbtSeqDeserer<'l, R> {
+    reader    : &'l mut NbtSource<R>,
+    elem_tag  : u8,
+    index     : usize,
+    remaining : usize
+}
+
+impl<'l, R> NbtSeqDeserer<'l, R> { pub(super) fn new(reader : &'l mut NbtSource<R>, elem_tag : u8, len : usize) -> Self { Self {
+    reader, elem_tag, index : 0, remaining : len
+} } }
+
+
+impl<'de, R> SeqAccess<'de> for NbtSeqDeserer<'_, R>
+where
+    R : NbtRead<'de>
+{
+    type Error = NbtDeserError;
+
+    fn next_element_seed<T>(&mut self, seed : T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T : DeserializeSeed<'de>
+    {
+        if (self.remaining == 0) { return Ok(None); }
+        self.remaining -= 1;
+        let index = self.index;
+        self.index += 1;
+        seed.deserialize(&mut NbtRootDeserer::new(&mut*self.reader, TagRead::Known(self.elem_tag)))
+            .map(Some)
+            .map_err(|err| err.within(NbtErrorSegment::Index(index), self.reader.accounter().offset()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> { Some(self.remaining) }
+
+}
@@ -0,0 +1,184 @@
+use super::{
+    NbtDeserError,
+    tag
+};
+use crate::nbt::{
+    NbtLimits,
+    NbtAccounter
+};
+use std::{
+    borrow::Cow,
+    io::{ self, Read }
+};
+use cesu8::from_java_cesu8;
+
+
+/// Input which NBT can be read from.
+pub(super) trait NbtRead<'de> {
+
+    /// Reads exactly enough bytes to fill `buf`.
+    fn read_exact(&mut self, buf : &mut [u8]) -> io::Result<()>;
+
+    /// Reads `len` bytes, borrowing them from the input if possible.
+    fn read_bytes(&mut self, len : usize) -> io::Result<Cow<'de, [u8]>>;
+
+    /// Reads past `len` bytes without keeping them.
+    fn skip(&mut self, len : usize) -> io::Result<()>;
+
+}
+
+
+/// Reads NBT from an [`io::Read`], copying out anything which is borrowed.
+pub(super) struct IoRead<R>(pub(super) R)
+where
+    R : Read;
+
+impl<'de, R> NbtRead<'de> for IoRead<R>
+where
+    R : Read
+{
+
+    #[inline]
+    fn read_exact(&mut self, buf : &mut [u8]) -> io::Result<()> {
+        self.0.read_exact(buf)
+    }
+
+    fn read_bytes(&mut self, len : usize) -> io::Result<Cow<'de, [u8]>> {
+        // Bound the preallocation, as the length comes from the input.
+        let mut buf = Vec::with_capacity(len.min(4096));
+        (&mut self.0).take(len as u64).read_to_end(&mut buf)?;
+        if (buf.len() < len) { return Err(io::ErrorKind::UnexpectedEof.into()); }
+        Ok(Cow::Owned(buf))
+    }
+
+    fn skip(&mut self, len : usize) -> io::Result<()> {
+        let skipped = io::copy(&mut (&mut self.0).take(len as u64), &mut io::sink())?;
+        if (skipped < len as u64) { return Err(io::ErrorKind::UnexpectedEof.into()); }
+        Ok(())
+    }
+
+}
+
+
+/// Reads NBT from a byte slice, borrowing from it where possible.
+pub(super) struct SliceRead<'de>(pub(super) &'de [u8]);
+
+impl<'de> NbtRead<'de> for SliceRead<'de> {
+
+    #[inline]
+    fn read_exact(&mut self, buf : &mut [u8]) -> io::Result<()> {
+        self.0.read_exact(buf)
+    }
+
+    fn read_bytes(&mut self, len : usize) -> io::Result<Cow<'de, [u8]>> {
+        let Some((bytes, rest,)) = self.0.split_at_checked(len)
+            else { return Err(io::ErrorKind::UnexpectedEof.into()); };
+        self.0 = rest;
+        Ok(Cow::Borrowed(bytes))
+    }
+
+    fn skip(&mut self, len : usize) -> io::Result<()> {
+        self.read_bytes(len)?;
+        Ok(())
+    }
+
+}
+
+
+/// A reader which counts everything read from it against an [`NbtAccounter`].
+pub(super) struct NbtSource<R> {
+    reader    : R,
+    accounter : NbtAccounter
+}
+
+impl<R> NbtSource<R> {
+
+    pub(super) fn new(reader : R, limits : NbtLimits) -> Self {
+        Self { reader, accounter : NbtAccounter::new(limits) }
+    }
+
+    #[inline]
+    pub(super) fn accounter(&mut self) -> &mut NbtAccounter { &mut self.accounter }
+
+}
+
+impl<'de, R> NbtSource<R>
+where
+    R : NbtRead<'de>
+{
+
+    fn read_array<const N : usize>(&mut self) -> Result<[u8; N], NbtDeserError> {
+        self.accounter.account_bytes(N)?;
+        let mut buf = [0u8; N];
+        self.reader.read_exact(&mut buf)?;
+        self.accounter.advance(N);
+        Ok(buf)
+    }
+
+    #[inline]
+    pub(super) fn read_u8(&mut self) -> Result<u8, NbtDeserError> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    #[inline]
+    pub(super) fn read_u16(&mut self) -> Result<u16, NbtDeserError> {
+        Ok(u16::from_be_bytes(self.read_array()?))
+    }
+
+    #[inline]
+    pub(super) fn read_u32(&mut self) -> Result<u32, NbtDeserError> {
+        Ok(u32::from_be_bytes(self.read_array()?))
+    }
+
+    #[inline]
+    pub(super) fn read_u64(&mut self) -> Result<u64, NbtDeserError> {
+        Ok(u64::from_be_bytes(self.read_array()?))
+    }
+
+    #[inline]
+    pub(super) fn read_f32(&mut self) -> Result<f32, NbtDeserError> {
+        Ok(f32::from_be_bytes(self.read_array()?))
+    }
+
+    #[inline]
+    pub(super) fn read_f64(&mut self) -> Result<f64, NbtDeserError> {
+        Ok(f64::from_be_bytes(self.read_array()?))
+    }
+
+    pub(super) fn read_len(&mut self) -> Result<usize, NbtDeserError> {
+        let len = self.read_u32()?.cast_signed();
+        usize::try_from(len).map_err(|_| NbtDeserError::BadLength(len))
+    }
+
+    pub(super) fn read_string(&mut self) -> Result<Cow<'de, str>, NbtDeserError> {
+        let len = self.read_u16()? as usize;
+        Ok(match (self.read_bytes(len)?) {
+            Cow::Borrowed(buf) => from_java_cesu8(buf)?,
+            Cow::Owned(buf)    => match (from_java_cesu8(&buf)?) {
+                Cow::Owned(v)    => Cow::Owned(v),
+                Cow::Borrowed(_) => Cow::Owned(unsafe { String::from_utf8_unchecked(buf) })
+            }
+        })
+    }
+
+    pub(super) fn read_barray(&mut self) -> Result<Cow<'de, [u8]>, NbtDeserError> {
+        let len = self.read_len()?;
+        self.accounter.check_list(tag::BYTE, len)?;
+        self.read_bytes(len)
+    }
+
+    fn read_bytes(&mut self, len : usize) -> Result<Cow<'de, [u8]>, NbtDeserError> {
+        self.accounter.account_bytes(len)?;
+        let buf = self.reader.read_bytes(len)?;
+        self.accounter.advance(len);
+        Ok(buf)
+    }
+
+    pub(super) fn skip_bytes(&mut self, len : usize) -> Result<(), NbtDeserError> {
+        self.accounter.account_bytes(len)?;
+        self.reader.skip(len)?;
+        self.accounter.advance(len);
+        Ok(())
+    }
+
+}
@@ -0,0 +1,114 @@
+use super::{
+    NbtDeserError,
+    NbtSource, NbtRead,
+    NbtRootDeserer, TagRead,
+    NbtErrorSegment,
+    tag
+};
+use core::mem;
+use std::borrow::Cow;
+use serde::de::{
+    Deserialize as Deser,
+    DeserializeSeed,
+    Deserializer as Deserer,
+    EnumAccess,
+    IgnoredAny,
+    IntoDeserializer,
+    VariantAccess,
+    Visitor
+};
+
+
+/// Reads an enum variant written as a compound with a single entry, keyed by the variant name.
+pub(super) struct NbtVariantDeserer<'de, 'l, R> {
+    reader    : &'l mut NbtSource<R>,
+    value_tag : u8,
+    variant   : Cow<'de, str>
+}
+
+impl<'l, R> From<&'l mut NbtSource<R>> for NbtVariantDeserer<'_, 'l, R> { fn from(reader : &'l mut NbtSource<R>) -> Self { Self {
+    reader, value_tag : tag::END, variant : Cow::Borrowed("")
+} } }
+
+impl<'de, R> NbtVariantDeserer<'de, '_, R>
+where
+    R : NbtRead<'de>
+{
+
+    fn value(&mut self) -> NbtRootDeserer<'_, R> {
+        NbtRootDeserer::new(&mut*self.reader, TagRead::Known(self.value_tag))
+    }
+
+    /// Adds the variant name to the path of an error in the variant value.
+    fn within(&mut self, err : NbtDeserError) -> NbtDeserError {
+        err.within(NbtErrorSegment::Key(mem::take(&mut self.variant).into_owned()), self.reader.accounter().offset())
+    }
+
+    /// Reads the end of the compound wrapping the variant.
+    fn close<T>(self, value : T) -> Result<T, NbtDeserError> {
+        if (self.reader.read_u8()? != tag::END) {
+            return Err(NbtDeserError::BadVariant);
+        }
+        Ok(value)
+    }
+
+}
+
+
+impl<'de, 'l, R> EnumAccess<'de> for NbtVariantDeserer<'de, 'l, R>
+where
+    R : NbtRead<'de>
+{
+    type Error   = NbtDeserError;
+    type Variant = Self;
+
+    fn variant_seed<V>(mut self, seed : V) -> Result<(V::Value, Self::Variant,), Self::Error>
+    where
+        V : DeserializeSeed<'de>
+    {
+        self.value_tag = self.reader.read_u8()?;
+        if (self.value_tag == tag::END) { return Err(NbtDeserError::BadVariant); }
+        self.variant = self.reader.read_string()?;
+        let variant = seed.deserialize(IntoDeserializer::<NbtDeserError>::into_deserializer(&*self.variant))?;
+        Ok((variant, self,))
+    }
+
+}
+
+
+impl<'de, 'l, R> VariantAccess<'de> for NbtVariantDeserer<'de, 'l, R>
+where
+    R : NbtRead<'de>
+{
+    type Error = NbtDeserError;
+
+    fn unit_variant(mut self) -> Result<(), Self::Error> {
+        IgnoredAny::deserialize(&mut self.value()).map_err(|err| self.within(err))?;
+        self.close(())
+    }
+
+    fn newtype_variant_seed<T>(mut self, seed : T) -> Result<T::Value, Self::Error>
+    where
+        T : DeserializeSeed<'de>
+    {
+        let value = seed.deserialize(&mut self.value()).map_err(|err| self.within(err))?;
+        self.close(value)
+    }
+
+    fn tuple_variant<V>(mut self, _len : usize, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    {
+        let value = self.value().deserialize_seq(visitor).map_err(|err| self.within(err))?;
+        self.close(value)
+    }
+
+    fn struct_variant<V>(mut self, _fields : &'static [&'static str], visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    {
+        let value = self.value().deserialize_map(visitor).map_err(|err| self.within(err))?;
+        self.close(value)
+    }
+
+}
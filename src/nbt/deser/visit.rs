@@ -0,0 +1,275 @@
+use super::{
+    NbtDeserError,
+    NbtSource, NbtRead, IoRead, SliceRead,
+    NbtErrorSegment,
+    tag
+};
+use crate::nbt::{
+    NbtElement,
+    NbtCompression,
+    NbtLimits
+};
+use std::{
+    borrow::Cow,
+    io::Read
+};
+use flate2::read::{ GzDecoder, ZlibDecoder };
+
+
+/// What an [`NbtVisitor`] wants to happen next.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum NbtVisit {
+    /// Keep reading.
+    Continue,
+    /// Read past the element which is about to be read, without decoding it.
+    ///
+    /// Has the same effect as [`NbtVisit::Continue`] when returned after an element has been read.
+    Skip,
+    /// Stop reading, leaving the rest of the input unread.
+    Stop
+}
+
+/// Receives the structure of NBT as it is read, without building a tree.
+///
+/// Elements are reported as they are encoded, so lists of mixed element types appear as lists of compounds
+///  which wrap each element under an empty key.
+pub trait NbtVisitor<'de> {
+
+    /// Called at the start of a compound.
+    #[inline]
+    fn begin_compound(&mut self) -> NbtVisit { NbtVisit::Continue }
+
+    /// Called before the value of each entry of a compound is read.
+    #[inline]
+    fn key(&mut self, _key : &str) -> NbtVisit { NbtVisit::Continue }
+
+    /// Called at the end of a compound which was not skipped.
+    #[inline]
+    fn end_compound(&mut self) -> NbtVisit { NbtVisit::Continue }
+
+    /// Called at the start of a list, with its length.
+    #[inline]
+    fn begin_list(&mut self, _len : usize) -> NbtVisit { NbtVisit::Continue }
+
+    /// Called at the end of a list which was not skipped.
+    #[inline]
+    fn end_list(&mut self) -> NbtVisit { NbtVisit::Continue }
+
+    /// Called with each element which is not a list or compound.
+    #[inline]
+    fn value(&mut self, _element : NbtElement<'de>) -> NbtVisit { NbtVisit::Continue }
+
+}
+
+
+/// Reads network NBT into a visitor, within [`NbtLimits::NETWORK`].
+#[inline]
+pub fn visit_network<R, V>(reader : R, visitor : &mut V) -> Result<(), NbtDeserError>
+where
+    R : Read,
+    V : NbtVisitor<'static> + ?Sized
+{ visit_root(&mut NbtSource::new(IoRead(reader), NbtLimits::NETWORK), visitor) }
+
+/// Reads network NBT from a slice into a visitor, within [`NbtLimits::NETWORK`].
+///
+/// Strings and byte arrays are borrowed from `slice` where possible.
+#[inline]
+pub fn visit_network_slice<'de, V>(slice : &'de [u8], visitor : &mut V) -> Result<(), NbtDeserError>
+where
+    V : NbtVisitor<'de> + ?Sized
+{ visit_root(&mut NbtSource::new(SliceRead(slice), NbtLimits::NETWORK), visitor) }
+
+/// Reads an NBT file with a named root into a visitor, returning the root name.
+///
+/// Reading is bound by [`NbtLimits::FILE`]. Error offsets are into the decompressed data.
+pub fn visit_file<R, V>(reader : R, compression : NbtCompression, visitor : &mut V) -> Result<String, NbtDeserError>
+where
+    R : Read,
+    V : NbtVisitor<'static> + ?Sized
+{
+    fn read_root<R, V>(reader : R, visitor : &mut V) -> Result<String, NbtDeserError>
+    where
+        R : Read,
+        V : NbtVisitor<'static> + ?Sized
+    {
+        let mut source = NbtSource::new(IoRead(reader), NbtLimits::FILE);
+        let     root   = (|| {
+            let tag       = source.read_u8()?;
+            let root_name = source.read_string()?.into_owned();
+            walk(&mut source, visitor, tag)?;
+            Ok(root_name)
+        })();
+        root.map_err(|err : NbtDeserError| err.at(source.accounter().offset()))
+    }
+    match (compression) {
+        NbtCompression::None => read_root(reader, visitor),
+        NbtCompression::Gzip => read_root(GzDecoder::new(reader), visitor),
+        NbtCompression::Zlib => read_root(ZlibDecoder::new(reader), visitor)
+    }
+}
+
+fn visit_root<'de, R, V>(source : &mut NbtSource<R>, visitor : &mut V) -> Result<(), NbtDeserError>
+where
+    R : NbtRead<'de>,
+    V : NbtVisitor<'de> + ?Sized
+{
+    let root = (|| {
+        let tag = source.read_u8()?;
+        walk(source, visitor, tag)?;
+        Ok(())
+    })();
+    root.map_err(|err : NbtDeserError| err.at(source.accounter().offset()))
+}
+
+
+/// Reads the payload of an element into a visitor, returning `false` if the visitor stopped.
+fn walk<'de, R, V>(source : &mut NbtSource<R>, visitor : &mut V, tag : u8) -> Result<bool, NbtDeserError>
+where
+    R : NbtRead<'de>,
+    V : NbtVisitor<'de> + ?Sized
+{
+    let visit = match (tag) {
+        tag::BYTE   => visitor.value(NbtElement::Byte(source.read_u8()?.cast_signed())),
+        tag::SHORT  => visitor.value(NbtElement::Short(source.read_u16()?.cast_signed())),
+        tag::INT    => visitor.value(NbtElement::Int(source.read_u32()?.cast_signed())),
+        tag::LONG   => visitor.value(NbtElement::Long(source.read_u64()?.cast_signed())),
+        tag::FLOAT  => visitor.value(NbtElement::Float(source.read_f32()?)),
+        tag::DOUBLE => visitor.value(NbtElement::Double(source.read_f64()?)),
+        tag::BARRAY => visitor.value(NbtElement::BArray(match (source.read_barray()?) {
+            // SAFETY: `i8` and `u8` have the same size and alignment.
+            Cow::Borrowed(v) => Cow::Borrowed(unsafe { &*(v as *const [u8] as *const [i8]) }),
+            Cow::Owned(v)    => Cow::Owned(v.into_iter().map(u8::cast_signed).collect())
+        })),
+        tag::STRING => visitor.value(NbtElement::String(source.read_string()?)),
+        tag::LIST   => {
+            let elem_tag = source.read_u8()?;
+            let len      = source.read_len()?;
+            source.accounter().check_list(elem_tag, len)?;
+            match (visitor.begin_list(len)) {
+                NbtVisit::Continue => { },
+                NbtVisit::Skip     => { return skip_list(source, elem_tag, len).map(|_| true); },
+                NbtVisit::Stop     => { return Ok(false); }
+            }
+            source.accounter().push_depth()?;
+            for i in 0..len {
+                let walked = walk(source, visitor, elem_tag);
+                if (! walked.map_err(|err| err.within(NbtErrorSegment::Index(i), source.accounter().offset()))?) { return Ok(false); }
+            }
+            source.accounter().pop_depth();
+            visitor.end_list()
+        },
+        tag::COMPOUND => {
+            match (visitor.begin_compound()) {
+                NbtVisit::Continue => { },
+                NbtVisit::Skip     => { return skip(source, tag::COMPOUND).map(|_| true); },
+                NbtVisit::Stop     => { return Ok(false); }
+            }
+            source.accounter().push_depth()?;
+            loop {
+                let value_tag = source.read_u8()?;
+                if (value_tag == tag::END) { break; }
+                let key    = source.read_string()?;
+                let walked = match (visitor.key(&key)) {
+                    NbtVisit::Continue => walk(source, visitor, value_tag),
+                    NbtVisit::Skip     => skip(source, value_tag).map(|_| true),
+                    NbtVisit::Stop     => { return Ok(false); }
+                };
+                if (! walked.map_err(|err| err.within(NbtErrorSegment::Key(key.into_owned()), source.accounter().offset()))?) { return Ok(false); }
+            }
+            source.accounter().pop_depth();
+            visitor.end_compound()
+        },
+        tag::IARRAY => {
+            let len = source.read_len()?;
+            source.accounter().check_list(tag::INT, len)?;
+            // Bound the preallocation, as the length comes from the input.
+            let mut v = Vec::with_capacity(len.min(4096));
+            for _ in 0..len { v.push(source.read_u32()?.cast_signed()); }
+            visitor.value(NbtElement::IArray(Cow::Owned(v)))
+        },
+        tag::LARRAY => {
+            let len = source.read_len()?;
+            source.accounter().check_list(tag::LONG, len)?;
+            // Bound the preallocation, as the length comes from the input.
+            let mut v = Vec::with_capacity(len.min(4096));
+            for _ in 0..len { v.push(source.read_u64()?.cast_signed()); }
+            visitor.value(NbtElement::LArray(Cow::Owned(v)))
+        },
+        tag => { return Err(NbtDeserError::UnknownTag(tag)); }
+    };
+    Ok(visit != NbtVisit::Stop)
+}
+
+
+/// Reads past the payload of an element without decoding it.
+fn skip<'de, R>(source : &mut NbtSource<R>, tag : u8) -> Result<(), NbtDeserError>
+where
+    R : NbtRead<'de>
+{
+    if let Some(len) = fixed_payload_len(tag) {
+        return source.skip_bytes(len);
+    }
+    match (tag) {
+        tag::STRING => {
+            let len = source.read_u16()? as usize;
+            source.skip_bytes(len)
+        },
+        tag::LIST => {
+            let elem_tag = source.read_u8()?;
+            let len      = source.read_len()?;
+            source.accounter().check_list(elem_tag, len)?;
+            skip_list(source, elem_tag, len)
+        },
+        tag::COMPOUND => {
+            source.accounter().push_depth()?;
+            loop {
+                let value_tag = source.read_u8()?;
+                if (value_tag == tag::END) { break; }
+                let key_len = source.read_u16()? as usize;
+                source.skip_bytes(key_len)?;
+                skip(source, value_tag)?;
+            }
+            source.accounter().pop_depth();
+            Ok(())
+        },
+        tag::BARRAY => skip_array(source, tag::BYTE),
+        tag::IARRAY => skip_array(source, tag::INT),
+        tag::LARRAY => skip_array(source, tag::LONG),
+        tag => Err(NbtDeserError::UnknownTag(tag))
+    }
+}
+
+/// Reads past the payload of an array.
+fn skip_array<'de, R>(source : &mut NbtSource<R>, elem_tag : u8) -> Result<(), NbtDeserError>
+where
+    R : NbtRead<'de>
+{
+    let len = source.read_len()?;
+    source.accounter().check_list(elem_tag, len)?;
+    skip_list(source, elem_tag, len)
+}
+
+/// Reads past the elements of a list, after its header.
+fn skip_list<'de, R>(source : &mut NbtSource<R>, elem_tag : u8, len : usize) -> Result<(), NbtDeserError>
+where
+    R : NbtRead<'de>
+{
+    if let Some(elem_len) = fixed_payload_len(elem_tag) {
+        return source.skip_bytes(len.saturating_mul(elem_len));
+    }
+    source.accounter().push_depth()?;
+    for _ in 0..len {
+        skip(source, elem_tag)?;
+    }
+    source.accounter().pop_depth();
+    Ok(())
+}
+
+/// Returns the length of the payload of an element with the given tag, if it is always the same.
+const fn fixed_payload_len(tag : u8) -> Option<usize> { match (tag) {
+    tag::BYTE                => Some(1),
+    tag::SHORT               => Some(2),
+    tag::INT   | tag::FLOAT  => Some(4),
+    tag::LONG  | tag::DOUBLE => Some(8),
+    _                        => None
+} }
@@ -0,0 +1,155 @@
+use super::{
+    NbtDeserError,
+    tag
+};
+use core::fmt::{ self, Display, Formatter };
+
+
+/// Limits on the resources used while decoding NBT, mirroring vanilla's `NbtAccounter`.
+///
+/// Bytes are counted as they appear on the wire. Lengths are checked against the budget
+///  before anything is allocated for them.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct NbtLimits {
+    /// The maximum number of bytes that may be read.
+    pub max_bytes    : usize,
+    /// The maximum nesting depth of lists and compounds.
+    pub max_depth    : usize,
+    /// The maximum number of elements in a list or array.
+    pub max_list_len : usize
+}
+
+impl NbtLimits {
+
+    /// The limits vanilla applies to NBT received over the network.
+    pub const NETWORK : Self = Self {
+        max_bytes    : 0x200000,
+        max_depth    : 512,
+        max_list_len : usize::MAX
+    };
+
+    /// The limits vanilla applies to NBT read from files.
+    pub const FILE : Self = Self {
+        max_bytes    : usize::MAX,
+        max_depth    : 512,
+        max_list_len : usize::MAX
+    };
+
+    /// No limits.
+    pub const UNLIMITED : Self = Self {
+        max_bytes    : usize::MAX,
+        max_depth    : usize::MAX,
+        max_list_len : usize::MAX
+    };
+
+}
+
+impl Default for NbtLimits {
+    #[inline]
+    fn default() -> Self { Self::NETWORK }
+}
+
+
+/// A limit in [`NbtLimits`] which was exceeded.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum NbtLimit {
+    /// [`NbtLimits::max_bytes`]
+    Bytes,
+    /// [`NbtLimits::max_depth`]
+    Depth,
+    /// [`NbtLimits::max_list_len`]
+    ListLen
+}
+
+impl Display for NbtLimit {
+    fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result { match (self) {
+        Self::Bytes   => write!(f, "byte limit"),
+        Self::Depth   => write!(f, "depth limit"),
+        Self::ListLen => write!(f, "list length limit")
+    } }
+}
+
+
+/// Tracks the position in, and resources used while decoding, NBT against a set of [`NbtLimits`].
+pub(in crate::nbt) struct NbtAccounter {
+    limits : NbtLimits,
+    bytes  : usize,
+    depth  : usize,
+    offset : usize
+}
+
+impl NbtAccounter {
+
+    #[inline]
+    pub(in crate::nbt) const fn new(limits : NbtLimits) -> Self {
+        Self { limits, bytes : 0, depth : 0, offset : 0 }
+    }
+
+    /// Returns the number of bytes which have been read in full.
+    #[inline]
+    pub(in crate::nbt) fn offset(&self) -> usize { self.offset }
+
+    /// Counts bytes which have been read in full, after being accounted.
+    #[inline]
+    pub(in crate::nbt) fn advance(&mut self, len : usize) {
+        self.offset += len;
+    }
+
+    /// Counts bytes which are about to be read.
+    pub(in crate::nbt) fn account_bytes(&mut self, len : usize) -> Result<(), NbtDeserError> {
+        self.bytes = self.bytes.saturating_add(len);
+        if (self.bytes > self.limits.max_bytes) {
+            return Err(NbtDeserError::LimitExceeded(NbtLimit::Bytes));
+        }
+        Ok(())
+    }
+
+    /// Checks that a list or array of the given length is well formed and could fit in the remaining budget,
+    ///  without counting its bytes.
+    pub(in crate::nbt) fn check_list(&self, elem_tag : u8, len : usize) -> Result<(), NbtDeserError> {
+        // Like in vanilla, only empty lists may have an end element tag.
+        if (elem_tag == tag::END && len != 0) {
+            return Err(NbtDeserError::BadLength(i32::try_from(len).unwrap_or(i32::MAX)));
+        }
+        if (len > self.limits.max_list_len) {
+            return Err(NbtDeserError::LimitExceeded(NbtLimit::ListLen));
+        }
+        if (self.bytes.saturating_add(len.saturating_mul(Self::min_payload_len(elem_tag))) > self.limits.max_bytes) {
+            return Err(NbtDeserError::LimitExceeded(NbtLimit::Bytes));
+        }
+        Ok(())
+    }
+
+    /// Enters a list or compound.
+    pub(in crate::nbt) fn push_depth(&mut self) -> Result<(), NbtDeserError> {
+        self.depth += 1;
+        if (self.depth > self.limits.max_depth) {
+            return Err(NbtDeserError::LimitExceeded(NbtLimit::Depth));
+        }
+        Ok(())
+    }
+
+    /// Leaves a list or compound.
+    #[inline]
+    pub(in crate::nbt) fn pop_depth(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Returns the smallest number of bytes the payload of an element with the given tag can take.
+    const fn min_payload_len(tag : u8) -> usize { match (tag) {
+        tag::BYTE     => 1,
+        tag::SHORT    => 2,
+        tag::INT      => 4,
+        tag::LONG     => 8,
+        tag::FLOAT    => 4,
+        tag::DOUBLE   => 8,
+        tag::BARRAY   => 4,
+        tag::STRING   => 2,
+        tag::LIST     => 5,
+        tag::COMPOUND => 1,
+        tag::IARRAY   => 4,
+        tag::LARRAY   => 4,
+        _             => 0
+    } }
+
+}
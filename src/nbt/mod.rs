@@ -10,6 +10,24 @@ pub use deser::*;
 mod value;
 pub use value::*;
 
+mod array;
+pub use array::*;
+
+mod snbt;
+pub use snbt::*;
+
+mod compression;
+pub use compression::*;
+
+mod limits;
+pub use limits::*;
+
+mod path;
+pub use path::*;
+
+mod net;
+pub use net::*;
+
 mod tag {
     pub const END      : u8 = 0;
     pub const BYTE     : u8 = 1;
@@ -18,10 +36,10 @@ mod tag {
     pub const LONG     : u8 = 4;
     pub const FLOAT    : u8 = 5;
     pub const DOUBLE   : u8 = 6;
-    pub const BARRAY   : u8 = 7; // TODO: Serialiser
+    pub const BARRAY   : u8 = 7;
     pub const STRING   : u8 = 8;
     pub const LIST     : u8 = 9;
     pub const COMPOUND : u8 = 10;
-    // pub const IARRAY   : u8 = 11; // TODO: Serialiser
-    // pub const LARRAY   : u8 = 12; // TODO: Serialiser
+    pub const IARRAY   : u8 = 11;
+    pub const LARRAY   : u8 = 12;
 }
@@ -0,0 +1,213 @@
+use crate::Minecraft;
+use super::{
+    NbtDeserError,
+    NbtLimits,
+    NbtAccounter,
+    to_element,
+    from_network_slice_with_limits,
+    value::deser_error,
+    tag
+};
+use core::ops::{ Deref, DerefMut };
+use netzer::prelude::*;
+use serde::{
+    Serialize as Ser,
+    de::DeserializeOwned as DeserOwned
+};
+
+
+/// Encodes and decodes a serde value as network NBT on netzer's async writers and readers,
+///  so that it can be used directly as a packet field.
+///
+/// Values are encoded and decoded following the same rules as [`to_network`] and [`from_network`](super::from_network).
+/// As serde serialisers are synchronous, a value is first converted with [`to_element`], which follows the rules of
+///  [`to_network`], and the element is then written to the writer as it is walked, without encoding it into a buffer.
+/// Likewise, exactly the bytes of one element are read, within the limits, before a value is deserialised from them.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[repr(transparent)]
+pub struct Nbt<T>(pub T);
+
+impl<T> Deref for Nbt<T> {
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &Self::Target { &self.0 }
+}
+impl<T> DerefMut for Nbt<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target { &mut self.0 }
+}
+
+impl<T> From<T> for Nbt<T> {
+    #[inline]
+    fn from(value : T) -> Self { Self(value) }
+}
+
+
+impl<T> NetEncode<Minecraft> for Nbt<T>
+where
+    T : Ser
+{
+    #[inline]
+    async fn encode<W : netzer::AsyncWrite>(&self, w : W) -> netzer::Result {
+        to_element(&self.0)?.encode(w).await
+    }
+}
+
+impl<T> NetDecode<Minecraft> for Nbt<T>
+where
+    T : DeserOwned
+{
+    #[inline]
+    async fn decode<R : netzer::AsyncRead>(r : R) -> netzer::Result<Self> {
+        Self::decode_with_limits(r, NbtLimits::NETWORK).await
+    }
+}
+
+impl<T> Nbt<T>
+where
+    T : DeserOwned
+{
+
+    /// Decodes a network NBT value, within the given limits.
+    ///
+    /// [`NetDecode::decode`] uses [`NbtLimits::NETWORK`].
+    pub async fn decode_with_limits<R : netzer::AsyncRead>(mut r : R, limits : NbtLimits) -> netzer::Result<Self> {
+        let mut accounter = NbtAccounter::new(limits);
+        let mut buf       = Vec::new();
+        let     read      = async {
+            let tag = read_raw_bytes(&mut r, &mut accounter, &mut buf, 1).await?[0];
+            if (tag != tag::END) {
+                read_raw_payload(tag, &mut r, &mut accounter, &mut buf).await?;
+            }
+            Ok(())
+        }.await;
+        read.map_err(|err : netzer::Error| deser_error(err).at(accounter.offset()))?;
+        Ok(Self(from_network_slice_with_limits(&buf, limits)?))
+    }
+
+}
+
+
+/// Reads the payload of an element into a buffer without decoding it.
+async fn read_raw_payload<R : netzer::AsyncRead>(tag : u8, r : &mut R, a : &mut NbtAccounter, buf : &mut Vec<u8>) -> netzer::Result {
+    match (tag) {
+        tag::BYTE                   => { read_raw_bytes(r, a, buf, 1).await?; },
+        tag::SHORT                  => { read_raw_bytes(r, a, buf, 2).await?; },
+        tag::INT | tag::FLOAT       => { read_raw_bytes(r, a, buf, 4).await?; },
+        tag::LONG | tag::DOUBLE     => { read_raw_bytes(r, a, buf, 8).await?; },
+        tag::BARRAY                 => { read_raw_array(r, a, buf, tag::BYTE, 1).await?; },
+        tag::IARRAY                 => { read_raw_array(r, a, buf, tag::INT, 4).await?; },
+        tag::LARRAY                 => { read_raw_array(r, a, buf, tag::LONG, 8).await?; },
+        tag::STRING                 => { read_raw_string(r, a, buf).await?; },
+        tag::LIST                   => {
+            let elem_tag = read_raw_bytes(r, a, buf, 1).await?[0];
+            let len      = read_raw_len(r, a, buf, elem_tag).await?;
+            a.push_depth()?;
+            for _ in 0..len {
+                Box::pin(read_raw_payload(elem_tag, r, a, buf)).await?;
+            }
+            a.pop_depth();
+        },
+        tag::COMPOUND               => {
+            a.push_depth()?;
+            loop {
+                let value_tag = read_raw_bytes(r, a, buf, 1).await?[0];
+                if (value_tag == tag::END) { break; }
+                read_raw_string(r, a, buf).await?;
+                Box::pin(read_raw_payload(value_tag, r, a, buf)).await?;
+            }
+            a.pop_depth();
+        },
+        _ => { return Err(NbtDeserError::UnknownTag(tag).into()); }
+    }
+    Ok(())
+}
+
+/// Reads some bytes onto the end of a buffer, returning them.
+async fn read_raw_bytes<'l, R : netzer::AsyncRead>(r : &mut R, a : &mut NbtAccounter, buf : &'l mut Vec<u8>, len : usize) -> netzer::Result<&'l [u8]> {
+    a.account_bytes(len)?;
+    let start = buf.len();
+    buf.resize(start + len, 0);
+    r.read_exact(&mut buf[start..]).await?;
+    a.advance(len);
+    Ok(&buf[start..])
+}
+
+/// Reads the length of a list or array, checking that it fits in the remaining budget.
+async fn read_raw_len<R : netzer::AsyncRead>(r : &mut R, a : &mut NbtAccounter, buf : &mut Vec<u8>, elem_tag : u8) -> netzer::Result<usize> {
+    let len = i32::from_be_bytes(read_raw_bytes(r, a, buf, 4).await?.try_into()?);
+    let len = usize::try_from(len).map_err(|_| NbtDeserError::BadLength(len))?;
+    a.check_list(elem_tag, len)?;
+    Ok(len)
+}
+
+async fn read_raw_array<R : netzer::AsyncRead>(r : &mut R, a : &mut NbtAccounter, buf : &mut Vec<u8>, elem_tag : u8, elem_len : usize) -> netzer::Result {
+    let len = read_raw_len(r, a, buf, elem_tag).await?;
+    read_raw_bytes(r, a, buf, len * elem_len).await?;
+    Ok(())
+}
+
+async fn read_raw_string<R : netzer::AsyncRead>(r : &mut R, a : &mut NbtAccounter, buf : &mut Vec<u8>) -> netzer::Result {
+    let len = u16::from_be_bytes(read_raw_bytes(r, a, buf, 2).await?.try_into()?);
+    read_raw_bytes(r, a, buf, len as usize).await?;
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::{ NbtElement, to_network, from_network };
+    use core::{
+        pin::pin,
+        task::{ Context, Poll, Waker }
+    };
+
+    /// Polls a future which never waits, as reading from and writing to slices is never pending.
+    fn ready<F : Future>(future : F) -> F::Output {
+        match (pin!(future).poll(&mut Context::from_waker(Waker::noop()))) {
+            Poll::Ready(output) => output,
+            Poll::Pending       => panic!("future is pending")
+        }
+    }
+
+    #[test]
+    fn same_rules_as_blocking() {
+        let value = (vec!["a".to_string()], [1i64, 2],);
+        let mut buf = Vec::new();
+        ready(Nbt(&value).encode(&mut buf)).unwrap();
+        let mut expected = Vec::new();
+        to_network(&mut expected, &value).unwrap();
+        assert_eq!(buf, expected);
+        let element = NbtElement::from_snbt(r#"{a:[I;1,2],b:[[1],["x"]],c:[1b,"s",{"":2}],d:{},e:[L;]}"#).unwrap();
+        let mut element_buf = Vec::new();
+        ready(Nbt(&element).encode(&mut element_buf)).unwrap();
+        let mut element_expected = Vec::new();
+        to_network(&mut element_expected, &element).unwrap();
+        assert_eq!(element_buf, element_expected);
+        assert!(ready(Nbt(&None::<i32>).encode(&mut Vec::new())).is_err());
+
+        // Trailing bytes are left unread.
+        buf.push(0xFF);
+        let mut r = &buf[..];
+        let Nbt(decoded) = ready(Nbt::<(Vec<String>, [i64; 2],)>::decode(&mut r)).unwrap();
+        assert_eq!(decoded, value);
+        assert_eq!(r, [0xFF]);
+
+        // Ints are not longs to `from_network`, so they are not to `Nbt` either.
+        let mut buf = Vec::new();
+        to_network(&mut buf, &5i32).unwrap();
+        assert!(from_network::<_, i64>(&*buf).is_err());
+        assert!(ready(Nbt::<i64>::decode(&*buf)).is_err());
+    }
+
+    #[test]
+    fn limits() {
+        let mut buf = Vec::new();
+        to_network(&mut buf, &vec![vec![vec![1u8]]]).unwrap();
+        let limits = NbtLimits { max_depth : 2, ..NbtLimits::NETWORK };
+        assert!(ready(Nbt::<Vec<Vec<Vec<u8>>>>::decode_with_limits(&*buf, limits)).is_err());
+        assert!(ready(Nbt::<Vec<Vec<Vec<u8>>>>::decode(&buf[..(buf.len() - 1)])).is_err());
+    }
+
+}
@@ -0,0 +1,318 @@
+use super::{
+    NbtElement,
+    NbtCompound,
+    SnbtParseErrorKind,
+    write_quoted
+};
+use core::{
+    error::Error as StdError,
+    fmt::{ self, Display, Formatter }
+};
+use std::borrow::Cow;
+
+
+mod parse;
+
+mod node;
+
+
+/// A path to elements in an NBT tree, written in vanilla's NBT path syntax,
+///  such as `Items[{Slot:0b}].tag.display.Name`.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct NbtPath {
+    /// The nodes of this path, outermost first.
+    pub nodes : Vec<NbtPathNode>
+}
+
+/// A node of an [`NbtPath`].
+#[derive(Clone, PartialEq, Debug)]
+pub enum NbtPathNode {
+    /// `{...}`, selecting the root if it is a compound matching the pattern.
+    ///
+    /// Only allowed as the first node of a path.
+    MatchRoot(NbtCompound<'static>),
+    /// `key`, selecting an entry of a compound.
+    Key(String),
+    /// `key{...}`, selecting an entry of a compound if it is a compound matching the pattern.
+    MatchKey(String, NbtCompound<'static>),
+    /// `[]`, selecting every element of a list or array.
+    All,
+    /// `[index]`, selecting an element of a list or array. Negative indices count back from the end.
+    Index(i32),
+    /// `[{...}]`, selecting every element of a list which is a compound matching the pattern.
+    MatchElements(NbtCompound<'static>)
+}
+
+
+impl NbtPath {
+
+    /// Returns the elements selected by this path.
+    ///
+    /// Elements of byte, int, and long arrays are returned as copies.
+    pub fn get<'k, 'l>(&self, root : &'k NbtElement<'l>) -> Vec<Cow<'k, NbtElement<'l>>> {
+        let mut elements = vec![Cow::Borrowed(root)];
+        for node in &self.nodes {
+            let mut next = Vec::new();
+            for element in elements {
+                if let Cow::Borrowed(element) = element {
+                    node.get(element, &mut next);
+                }
+            }
+            elements = next;
+        }
+        elements
+    }
+
+    /// Returns mutable references to the elements selected by this path.
+    ///
+    /// Elements of byte, int, and long arrays are skipped.
+    /// Clones any borrowed lists and compounds along the way.
+    pub fn get_mut<'k, 'l>(&self, root : &'k mut NbtElement<'l>) -> Vec<&'k mut NbtElement<'l>> {
+        node::get_mut(&self.nodes, root)
+    }
+
+    /// Returns the number of elements selected by this path.
+    #[inline]
+    pub fn count(&self, root : &NbtElement<'_>) -> usize { self.get(root).len() }
+
+    /// Sets the elements selected by this path to `value`, like `/data modify ... set`,
+    ///  returning the number of elements which were changed.
+    ///
+    /// Missing compound entries and empty lists along the way are created.
+    pub fn set<'l>(&self, root : &mut NbtElement<'l>, value : NbtElement<'l>) -> usize {
+        let Some((last, parents,)) = self.nodes.split_last() else {
+            if (*root == value) { return 0; }
+            *root = value;
+            return 1;
+        };
+        node::get_or_create(parents, || last.preferred_parent(), root).into_iter()
+            .map(|element| last.set(element, &value))
+            .sum()
+    }
+
+    /// Removes the elements selected by this path, like `/data remove`,
+    ///  returning the number of elements which were removed.
+    pub fn remove(&self, root : &mut NbtElement<'_>) -> usize {
+        let Some((last, parents,)) = self.nodes.split_last()
+            else { return 0; };
+        node::get_mut(parents, root).into_iter()
+            .map(|element| last.remove(element))
+            .sum()
+    }
+
+    /// Inserts `value` into the lists and arrays selected by this path, like `/data modify ... insert`,
+    ///  returning the number of collections it was inserted into.
+    ///
+    /// Negative indices count back from the end, with `-1` inserting after the last element.
+    /// Missing compound entries and empty lists along the way, including the collection itself, are created.
+    /// Nothing is changed if an error is returned.
+    pub fn insert<'l>(&self, root : &mut NbtElement<'l>, index : i32, value : NbtElement<'l>) -> Result<usize, NbtPathError> {
+        let create = || NbtElement::List(Cow::Borrowed(&[]));
+        // Every target is checked before anything is created or inserted.
+        let positions = node::get_or_default(&self.nodes, create, root).into_iter().map(|target| {
+            let len      = node::collection_len(&target).ok_or(NbtPathError::NotACollection)?;
+            let position = if (index < 0) { len as i64 + index as i64 + 1 } else { index as i64 };
+            usize::try_from(position).ok().filter(|&position| position <= len).ok_or(NbtPathError::BadIndex(index))
+        }).collect::<Result<Vec<_>, _>>()?;
+        let mut count = 0;
+        for (target, position,) in node::get_or_create(&self.nodes, create, root).into_iter().zip(positions) {
+            if (node::insert(target, position, &value)) { count += 1; }
+        }
+        Ok(count)
+    }
+
+    /// Appends `value` to the lists and arrays selected by this path, like `/data modify ... append`,
+    ///  returning the number of collections it was appended to.
+    #[inline]
+    pub fn append<'l>(&self, root : &mut NbtElement<'l>, value : NbtElement<'l>) -> Result<usize, NbtPathError> {
+        self.insert(root, -1, value)
+    }
+
+}
+
+
+impl Display for NbtPath {
+    fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result {
+        for (i, node,) in self.nodes.iter().enumerate() { match (node) {
+            NbtPathNode::MatchRoot(pattern) => write!(f, "{pattern}")?,
+            NbtPathNode::Key(key) => {
+                if (i > 0) { write!(f, ".")?; }
+                write_key(f, key)?;
+            },
+            NbtPathNode::MatchKey(key, pattern) => {
+                if (i > 0) { write!(f, ".")?; }
+                write_key(f, key)?;
+                write!(f, "{pattern}")?;
+            },
+            NbtPathNode::All                    => write!(f, "[]")?,
+            NbtPathNode::Index(index)           => write!(f, "[{index}]")?,
+            NbtPathNode::MatchElements(pattern) => write!(f, "[{pattern}]")?
+        } }
+        Ok(())
+    }
+}
+
+fn write_key(f : &mut Formatter<'_>, key : &str) -> fmt::Result {
+    if (! key.is_empty() && key.chars().all(is_unquoted_key_char)) {
+        write!(f, "{key}")
+    } else {
+        write_quoted(f, key)
+    }
+}
+
+/// Returns `true` if the character may appear in an unquoted key of an NBT path.
+fn is_unquoted_key_char(ch : char) -> bool {
+    ! matches!(ch, ' ' | '"' | '\'' | '[' | ']' | '.' | '{' | '}')
+}
+
+
+/// Returned by [`NbtPath::insert`] and [`NbtPath::append`] when the value could not be inserted.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum NbtPathError {
+    /// A selected element is not a list or array.
+    NotACollection,
+    /// The index is out of bounds for a selected list or array.
+    BadIndex(i32)
+}
+
+impl Display for NbtPathError {
+    fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result { match (self) {
+        Self::NotACollection => write!(f, "expected list or array"),
+        Self::BadIndex(v)    => write!(f, "bad index {v}")
+    } }
+}
+impl StdError for NbtPathError { }
+
+
+/// Returned by [`NbtPath::from_str`](core::str::FromStr::from_str) when the input is not a valid NBT path.
+#[derive(Debug)]
+pub struct NbtPathParseError {
+    /// The byte offset in the input at which the error was found.
+    pub offset : usize,
+    /// What went wrong.
+    pub kind   : NbtPathParseErrorKind
+}
+
+/// The kind of an [`NbtPathParseError`].
+#[derive(Debug)]
+pub enum NbtPathParseErrorKind {
+    /// The input ended in the middle of a node.
+    UnexpectedEnd,
+    /// An unexpected character was found.
+    UnexpectedChar(char),
+    /// A list index is not a valid int.
+    BadIndex,
+    /// A key or pattern is not valid SNBT.
+    BadSnbt(SnbtParseErrorKind)
+}
+
+impl Display for NbtPathParseError {
+    #[inline]
+    fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.kind, self.offset)
+    }
+}
+impl StdError for NbtPathParseError { }
+
+impl Display for NbtPathParseErrorKind {
+    fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result { match (self) {
+        Self::UnexpectedEnd      => write!(f, "unexpected end of input"),
+        Self::UnexpectedChar(ch) => write!(f, "unexpected character {ch:?}"),
+        Self::BadIndex           => write!(f, "bad index"),
+        Self::BadSnbt(kind)      => write!(f, "bad SNBT {kind}")
+    } }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn el(snbt : &str) -> NbtElement<'static> { NbtElement::from_snbt(snbt).unwrap().into_owned() }
+
+    fn path(path : &str) -> NbtPath { path.parse().unwrap() }
+
+    fn get(path_str : &str, root : &NbtElement<'_>) -> Vec<NbtElement<'static>> {
+        path(path_str).get(root).into_iter().map(|element| element.into_owned().into_owned()).collect()
+    }
+
+    #[test]
+    fn get_elements() {
+        let root = el(r#"{Items:[{Slot:0b,id:"a"},{Slot:1b,id:"b"}],a:[I;1,2,3],n:{x:1}}"#);
+        assert_eq!(get("Items[].id", &root), [el(r#""a""#), el(r#""b""#)]);
+        assert_eq!(get("Items[{Slot:1b}].id", &root), [el(r#""b""#)]);
+        assert_eq!(get("Items[-1].Slot", &root), [el("1b")]);
+        assert_eq!(get("Items[-3]", &root), []);
+        assert_eq!(get("a[]", &root), [el("1"), el("2"), el("3")]);
+        assert_eq!(get("a[-1]", &root), [el("3")]);
+        assert_eq!(get("a[3]", &root), []);
+        assert_eq!(get("{n:{x:1}}.n.x", &root), [el("1")]);
+        assert_eq!(get("{n:{x:2}}.n", &root), []);
+        assert_eq!(get("n{x:1}", &root), [el("{x:1}")]);
+        assert_eq!(get("n.y", &root), []);
+        assert_eq!(path("Items[]").count(&root), 2);
+    }
+
+    #[test]
+    fn get_mut() {
+        let mut root = el(r#"{Items:[{Slot:0b},{Slot:1b}],a:[I;1,2]}"#);
+        for element in path("Items[].Slot").get_mut(&mut root) {
+            *element = NbtElement::Byte(5);
+        }
+        assert!(path("a[]").get_mut(&mut root).is_empty());
+        assert_eq!(root, el(r#"{Items:[{Slot:5b},{Slot:5b}],a:[I;1,2]}"#));
+    }
+
+    #[test]
+    fn set() {
+        let mut root = el(r#"{Items:[{Slot:0b,id:"a"},{Slot:1b,id:"b"}],a:[I;1,2,3]}"#);
+        assert_eq!(path("n.y.z").set(&mut root, el("1b")), 1);
+        assert_eq!(path("n.y.z").set(&mut root, el("1b")), 0);
+        assert_eq!(path("a[]").set(&mut root, el("7")), 3);
+        assert_eq!(path("a[-1]").set(&mut root, el("8")), 1);
+        assert_eq!(path("Items[{Slot:0b}].id").set(&mut root, el(r#""c""#)), 1);
+        assert_eq!(path("l[]").set(&mut root, el("1")), 1);
+        assert_eq!(root, el(r#"{Items:[{Slot:0b,id:"c"},{Slot:1b,id:"b"}],a:[I;7,7,8],n:{y:{z:1b}},l:[1]}"#));
+    }
+
+    #[test]
+    fn remove() {
+        let mut root = el(r#"{Items:[{Slot:0b},{Slot:1b},{Slot:2b}],a:[I;1,2,3],n:{x:1,y:2}}"#);
+        assert_eq!(path("Items[{Slot:1b}]").remove(&mut root), 1);
+        assert_eq!(path("Items[-1]").remove(&mut root), 1);
+        assert_eq!(path("a[-1]").remove(&mut root), 1);
+        assert_eq!(path("n.x").remove(&mut root), 1);
+        assert_eq!(path("n.x").remove(&mut root), 0);
+        assert_eq!(path("missing.x").remove(&mut root), 0);
+        assert_eq!(root, el(r#"{Items:[{Slot:0b}],a:[I;1,2],n:{y:2}}"#));
+        assert_eq!(path("a[]").remove(&mut root), 2);
+        assert_eq!(root, el(r#"{Items:[{Slot:0b}],a:[I;],n:{y:2}}"#));
+    }
+
+    #[test]
+    fn insert() {
+        let mut root = el("{l:[1,2],a:[I;1]}");
+        assert_eq!(path("l").insert(&mut root, 0, el("0")), Ok(1));
+        assert_eq!(path("l").append(&mut root, el("4")), Ok(1));
+        assert_eq!(path("l").insert(&mut root, -2, el("3")), Ok(1));
+        assert_eq!(path("a").insert(&mut root, 0, el("5b")), Ok(1));
+        assert_eq!(path("a").append(&mut root, el(r#""x""#)), Ok(0));
+        assert_eq!(path("new.list").append(&mut root, el("5")), Ok(1));
+        assert_eq!(root, el("{l:[0,1,2,3,4],a:[I;5,1],new:{list:[5]}}"));
+        assert_eq!(path("l").insert(&mut root, 6, el("9")), Err(NbtPathError::BadIndex(6)));
+        assert_eq!(path("l").insert(&mut root, -7, el("9")), Err(NbtPathError::BadIndex(-7)));
+        assert_eq!(path("new").append(&mut root, el("9")), Err(NbtPathError::NotACollection));
+        assert_eq!(root, el("{l:[0,1,2,3,4],a:[I;5,1],new:{list:[5]}}"));
+    }
+
+    #[test]
+    fn insert_checks_every_target_first() {
+        // The second target would be created empty, so index 2 is out of bounds for it.
+        let mut root = el("{x:[{l:[1,2]},{}]}");
+        assert_eq!(path("x[].l").insert(&mut root, 2, el("3")), Err(NbtPathError::BadIndex(2)));
+        assert_eq!(root, el("{x:[{l:[1,2]},{}]}"));
+        assert_eq!(path("x[].l").insert(&mut root, -1, el("3")), Ok(2));
+        assert_eq!(root, el("{x:[{l:[1,2,3]},{l:[3]}]}"));
+    }
+
+}
@@ -0,0 +1,447 @@
+use super::NbtPathNode;
+use crate::nbt::{
+    NbtElement,
+    NbtCompound
+};
+use std::borrow::Cow;
+
+
+/// Returns mutable references to the elements selected by `nodes`.
+pub(super) fn get_mut<'k, 'l>(nodes : &[NbtPathNode], root : &'k mut NbtElement<'l>) -> Vec<&'k mut NbtElement<'l>> {
+    let mut elements = vec![root];
+    for node in nodes {
+        let mut next = Vec::new();
+        for element in elements {
+            node.get_mut(element, &mut next);
+        }
+        elements = next;
+    }
+    elements
+}
+
+/// Returns mutable references to the elements selected by `nodes`, creating missing elements along the way.
+///
+/// Created elements are the preferred parent of the following node, or made by `create` for the last node.
+pub(super) fn get_or_create<'k, 'l, F>(nodes : &[NbtPathNode], create : F, root : &'k mut NbtElement<'l>) -> Vec<&'k mut NbtElement<'l>>
+where
+    F : Fn() -> NbtElement<'l>
+{
+    let mut elements = vec![root];
+    for (i, node,) in nodes.iter().enumerate() {
+        let mut next = Vec::new();
+        for element in elements {
+            match (nodes.get(i + 1)) {
+                Some(following) => node.get_or_create(element, || following.preferred_parent(), &mut next),
+                None            => node.get_or_create(element, &create, &mut next)
+            }
+        }
+        elements = next;
+    }
+    elements
+}
+
+/// Returns the elements which [`get_or_create`] would return, without creating anything.
+///
+/// Elements which would be created are returned as owned copies.
+pub(super) fn get_or_default<'k, 'l, F>(nodes : &[NbtPathNode], create : F, root : &'k NbtElement<'l>) -> Vec<Cow<'k, NbtElement<'l>>>
+where
+    F : Fn() -> NbtElement<'l>
+{
+    let mut elements = vec![Cow::Borrowed(root)];
+    for (i, node,) in nodes.iter().enumerate() {
+        let mut next = Vec::new();
+        for element in elements {
+            match (nodes.get(i + 1)) {
+                Some(following) => node.get_or_default(element, || following.preferred_parent(), &mut next),
+                None            => node.get_or_default(element, &create, &mut next)
+            }
+        }
+        elements = next;
+    }
+    elements
+}
+
+
+impl NbtPathNode {
+
+    /// Returns the element created in place of a missing parent of this node.
+    pub(super) fn preferred_parent<'l>(&self) -> NbtElement<'l> { match (self) {
+        Self::MatchRoot(_)
+        | Self::Key(_)
+        | Self::MatchKey(_, _)   => NbtElement::Compound(NbtCompound::new()),
+        Self::All
+        | Self::Index(_)
+        | Self::MatchElements(_) => NbtElement::List(Cow::Borrowed(&[]))
+    } }
+
+    pub(super) fn get<'k, 'l>(&self, element : &'k NbtElement<'l>, out : &mut Vec<Cow<'k, NbtElement<'l>>>) { match (self) {
+        Self::MatchRoot(pattern) => {
+            if (matches_compound(pattern, element)) { out.push(Cow::Borrowed(element)); }
+        },
+        Self::Key(key) => {
+            if let NbtElement::Compound(compound) = element && let Some(child) = compound.get(key) {
+                out.push(Cow::Borrowed(child));
+            }
+        },
+        Self::MatchKey(key, pattern) => {
+            if let NbtElement::Compound(compound) = element && let Some(child) = compound.get(key)
+                && matches_compound(pattern, child)
+            { out.push(Cow::Borrowed(child)); }
+        },
+        Self::All => match (element) {
+            NbtElement::List(v)   => out.extend(v.iter().map(Cow::Borrowed)),
+            NbtElement::BArray(v) => out.extend(v.iter().map(|&v| Cow::Owned(NbtElement::Byte(v)))),
+            NbtElement::IArray(v) => out.extend(v.iter().map(|&v| Cow::Owned(NbtElement::Int(v)))),
+            NbtElement::LArray(v) => out.extend(v.iter().map(|&v| Cow::Owned(NbtElement::Long(v)))),
+            _                     => { }
+        },
+        Self::Index(index) => {
+            let Some(i) = collection_len(element).and_then(|len| resolve_index(*index, len))
+                else { return; };
+            out.push(match (element) {
+                NbtElement::List(v)   => Cow::Borrowed(&v[i]),
+                NbtElement::BArray(v) => Cow::Owned(NbtElement::Byte(v[i])),
+                NbtElement::IArray(v) => Cow::Owned(NbtElement::Int(v[i])),
+                NbtElement::LArray(v) => Cow::Owned(NbtElement::Long(v[i])),
+                _                     => { return; }
+            });
+        },
+        Self::MatchElements(pattern) => {
+            if let NbtElement::List(v) = element {
+                out.extend(v.iter().filter(|child| matches_compound(pattern, child)).map(Cow::Borrowed));
+            }
+        }
+    } }
+
+    fn get_mut<'k, 'l>(&self, element : &'k mut NbtElement<'l>, out : &mut Vec<&'k mut NbtElement<'l>>) { match (self) {
+        Self::MatchRoot(pattern) => {
+            if (matches_compound(pattern, element)) { out.push(element); }
+        },
+        Self::Key(key) => {
+            if let NbtElement::Compound(compound) = element && let Some(child) = compound.get_mut(key) {
+                out.push(child);
+            }
+        },
+        Self::MatchKey(key, pattern) => {
+            if let NbtElement::Compound(compound) = element && let Some(child) = compound.get_mut(key)
+                && matches_compound(pattern, child)
+            { out.push(child); }
+        },
+        Self::All => {
+            if let NbtElement::List(v) = element { out.extend(v.to_mut().iter_mut()); }
+        },
+        Self::Index(index) => {
+            if let NbtElement::List(v) = element && let Some(i) = resolve_index(*index, v.len()) {
+                out.push(&mut v.to_mut()[i]);
+            }
+        },
+        Self::MatchElements(pattern) => {
+            if let NbtElement::List(v) = element {
+                out.extend(v.to_mut().iter_mut().filter(|child| matches_compound(pattern, child)));
+            }
+        }
+    } }
+
+    fn get_or_create<'k, 'l, F>(&self, element : &'k mut NbtElement<'l>, create : F, out : &mut Vec<&'k mut NbtElement<'l>>)
+    where
+        F : Fn() -> NbtElement<'l>
+    { match (self) {
+        Self::Key(key) => {
+            if let NbtElement::Compound(compound) = element {
+                if (! compound.contains_key(key)) { compound.insert(key.clone(), create()); }
+                out.extend(compound.get_mut(key));
+            }
+        },
+        Self::MatchKey(key, pattern) => {
+            if let NbtElement::Compound(compound) = element {
+                if (! compound.contains_key(key)) { compound.insert(key.clone(), copy_compound(pattern)); }
+                out.extend(compound.get_mut(key).filter(|child| matches_compound(pattern, child)));
+            }
+        },
+        Self::All => {
+            if let NbtElement::List(v) = element {
+                let v = v.to_mut();
+                if (v.is_empty()) { v.push(create()); }
+                out.extend(v.iter_mut());
+            }
+        },
+        Self::MatchElements(pattern) => {
+            if let NbtElement::List(v) = element {
+                let v = v.to_mut();
+                if (! v.iter().any(|child| matches_compound(pattern, child))) {
+                    v.push(NbtElement::Compound(copy_compound(pattern)));
+                }
+                out.extend(v.iter_mut().filter(|child| matches_compound(pattern, child)));
+            }
+        },
+        Self::MatchRoot(_) | Self::Index(_) => self.get_mut(element, out)
+    } }
+
+    fn get_or_default<'k, 'l, F>(&self, element : Cow<'k, NbtElement<'l>>, create : F, out : &mut Vec<Cow<'k, NbtElement<'l>>>)
+    where
+        F : Fn() -> NbtElement<'l>
+    { match (element) {
+        Cow::Borrowed(element) => self.get_or_default_borrowed(element, create, out),
+        Cow::Owned(element)    => {
+            let mut next = Vec::new();
+            self.get_or_default_borrowed(&element, create, &mut next);
+            out.extend(next.into_iter().map(|child| Cow::Owned(child.into_owned())));
+        }
+    } }
+
+    /// Mirrors [`NbtPathNode::get_or_create`], with created children returned as owned copies.
+    fn get_or_default_borrowed<'k, 'l, F>(&self, element : &'k NbtElement<'l>, create : F, out : &mut Vec<Cow<'k, NbtElement<'l>>>)
+    where
+        F : Fn() -> NbtElement<'l>
+    { match (self) {
+        Self::MatchRoot(pattern) => {
+            if (matches_compound(pattern, element)) { out.push(Cow::Borrowed(element)); }
+        },
+        Self::Key(key) => {
+            if let NbtElement::Compound(compound) = element {
+                out.push(compound.get(key).map_or_else(|| Cow::Owned(create()), Cow::Borrowed));
+            }
+        },
+        Self::MatchKey(key, pattern) => {
+            if let NbtElement::Compound(compound) = element {
+                match (compound.get(key)) {
+                    Some(child) => { if (matches_compound(pattern, child)) { out.push(Cow::Borrowed(child)); } },
+                    None        => { out.push(Cow::Owned(NbtElement::Compound(copy_compound(pattern)))); }
+                }
+            }
+        },
+        Self::All => {
+            if let NbtElement::List(v) = element {
+                if (v.is_empty()) { out.push(Cow::Owned(create())); }
+                out.extend(v.iter().map(Cow::Borrowed));
+            }
+        },
+        Self::Index(index) => {
+            if let NbtElement::List(v) = element && let Some(i) = resolve_index(*index, v.len()) {
+                out.push(Cow::Borrowed(&v[i]));
+            }
+        },
+        Self::MatchElements(pattern) => {
+            if let NbtElement::List(v) = element {
+                if (! v.iter().any(|child| matches_compound(pattern, child))) {
+                    out.push(Cow::Owned(NbtElement::Compound(copy_compound(pattern))));
+                }
+                out.extend(v.iter().filter(|child| matches_compound(pattern, child)).map(Cow::Borrowed));
+            }
+        }
+    } }
+
+    /// Sets the children of `element` selected by this node to `value`, returning the number which were changed.
+    pub(super) fn set<'l>(&self, element : &mut NbtElement<'l>, value : &NbtElement<'l>) -> usize { match (self) {
+        Self::MatchRoot(_) => 0,
+        Self::Key(key) => {
+            let NbtElement::Compound(compound) = element
+                else { return 0; };
+            if (compound.get(key) == Some(value)) { return 0; }
+            compound.insert(key.clone(), value.clone());
+            1
+        },
+        Self::MatchKey(key, pattern) => {
+            let NbtElement::Compound(compound) = element
+                else { return 0; };
+            match (compound.get(key)) {
+                Some(child) if (matches_compound(pattern, child) && child != value) => {
+                    compound.insert(key.clone(), value.clone());
+                    1
+                },
+                _ => 0
+            }
+        },
+        Self::All => {
+            if let NbtElement::List(v) = element && v.is_empty() {
+                v.to_mut().push(value.clone());
+                return 1;
+            }
+            let len = collection_len(element).unwrap_or(0);
+            (0..len).filter(|&i| set_index(element, i, value)).count()
+        },
+        Self::Index(index) => {
+            let Some(i) = collection_len(element).and_then(|len| resolve_index(*index, len))
+                else { return 0; };
+            set_index(element, i, value) as usize
+        },
+        Self::MatchElements(pattern) => {
+            let NbtElement::List(v) = element
+                else { return 0; };
+            let mut count = 0;
+            for child in v.to_mut() {
+                if (matches_compound(pattern, child) && child != value) {
+                    *child = value.clone();
+                    count += 1;
+                }
+            }
+            count
+        }
+    } }
+
+    /// Removes the children of `element` selected by this node, returning the number which were removed.
+    pub(super) fn remove(&self, element : &mut NbtElement<'_>) -> usize { match (self) {
+        Self::MatchRoot(_) => 0,
+        Self::Key(key) => match (element) {
+            NbtElement::Compound(compound) => compound.remove(key).is_some() as usize,
+            _                              => 0
+        },
+        Self::MatchKey(key, pattern) => match (element) {
+            NbtElement::Compound(compound) if (compound.get(key).is_some_and(|child| matches_compound(pattern, child))) => {
+                compound.remove(key);
+                1
+            },
+            _ => 0
+        },
+        Self::All => {
+            let len = collection_len(element).unwrap_or(0);
+            match (element) {
+                NbtElement::List(v)   => { *v = Cow::Borrowed(&[]); },
+                NbtElement::BArray(v) => { *v = Cow::Borrowed(&[]); },
+                NbtElement::IArray(v) => { *v = Cow::Borrowed(&[]); },
+                NbtElement::LArray(v) => { *v = Cow::Borrowed(&[]); },
+                _                     => { }
+            }
+            len
+        },
+        Self::Index(index) => {
+            let Some(i) = collection_len(element).and_then(|len| resolve_index(*index, len))
+                else { return 0; };
+            match (element) {
+                NbtElement::List(v)   => { v.to_mut().remove(i); },
+                NbtElement::BArray(v) => { v.to_mut().remove(i); },
+                NbtElement::IArray(v) => { v.to_mut().remove(i); },
+                NbtElement::LArray(v) => { v.to_mut().remove(i); },
+                _                     => { return 0; }
+            }
+            1
+        },
+        Self::MatchElements(pattern) => {
+            let NbtElement::List(v) = element
+                else { return 0; };
+            let len = v.len();
+            v.to_mut().retain(|child| ! matches_compound(pattern, child));
+            len - v.len()
+        }
+    } }
+
+}
+
+
+/// Returns the number of elements in a list or array.
+pub(super) fn collection_len(element : &NbtElement<'_>) -> Option<usize> { match (element) {
+    NbtElement::List(v)   => Some(v.len()),
+    NbtElement::BArray(v) => Some(v.len()),
+    NbtElement::IArray(v) => Some(v.len()),
+    NbtElement::LArray(v) => Some(v.len()),
+    _                     => None
+} }
+
+/// Inserts an element into a list or array, returning `false` if it does not fit the array.
+///
+/// Numbers are converted to the type of array elements, as vanilla does.
+pub(super) fn insert<'l>(collection : &mut NbtElement<'l>, i : usize, value : &NbtElement<'l>) -> bool {
+    match (collection) {
+        NbtElement::List(v)   => { v.to_mut().insert(i, value.clone()); },
+        NbtElement::BArray(v) => { let Some(n) = numeric(value) else { return false; }; v.to_mut().insert(i, n as i8); },
+        NbtElement::IArray(v) => { let Some(n) = numeric(value) else { return false; }; v.to_mut().insert(i, n as i32); },
+        NbtElement::LArray(v) => { let Some(n) = numeric(value) else { return false; }; v.to_mut().insert(i, n); },
+        _                     => { return false; }
+    }
+    true
+}
+
+/// Sets an element of a list or array, returning `true` if it was changed.
+fn set_index<'l>(collection : &mut NbtElement<'l>, i : usize, value : &NbtElement<'l>) -> bool {
+    match (collection) {
+        NbtElement::List(v) => {
+            if (v[i] == *value) { return false; }
+            v.to_mut()[i] = value.clone();
+        },
+        NbtElement::BArray(v) => {
+            let Some(n) = numeric(value).map(|n| n as i8) else { return false; };
+            if (v[i] == n) { return false; }
+            v.to_mut()[i] = n;
+        },
+        NbtElement::IArray(v) => {
+            let Some(n) = numeric(value).map(|n| n as i32) else { return false; };
+            if (v[i] == n) { return false; }
+            v.to_mut()[i] = n;
+        },
+        NbtElement::LArray(v) => {
+            let Some(n) = numeric(value) else { return false; };
+            if (v[i] == n) { return false; }
+            v.to_mut()[i] = n;
+        },
+        _ => { return false; }
+    }
+    true
+}
+
+/// Resolves an index into a collection of the given length, counting negative indices back from the end.
+fn resolve_index(index : i32, len : usize) -> Option<usize> {
+    let i = if (index < 0) { len as i64 + index as i64 } else { index as i64 };
+    usize::try_from(i).ok().filter(|&i| i < len)
+}
+
+/// Returns the value of a number element as a long, flooring floats.
+fn numeric(element : &NbtElement<'_>) -> Option<i64> { match (element) {
+    NbtElement::Byte(v)   => Some(*v as i64),
+    NbtElement::Short(v)  => Some(*v as i64),
+    NbtElement::Int(v)    => Some(*v as i64),
+    NbtElement::Long(v)   => Some(*v),
+    NbtElement::Float(v)  => Some(v.floor() as i64),
+    NbtElement::Double(v) => Some(v.floor() as i64),
+    _                     => None
+} }
+
+
+/// Returns `true` if `element` is a compound containing everything in `pattern`.
+fn matches_compound(pattern : &NbtCompound<'_>, element : &NbtElement<'_>) -> bool {
+    let NbtElement::Compound(element) = element
+        else { return false; };
+    pattern.iter().all(|(key, pattern,)| element.get(key).is_some_and(|child| matches(pattern, child)))
+}
+
+/// Returns `true` if `element` matches `pattern`, as vanilla does for NBT predicates.
+///
+/// Compounds match if they contain everything in the pattern. Lists match if every pattern element
+///  matches some element of the list, and an empty pattern only matches an empty list.
+fn matches(pattern : &NbtElement<'_>, element : &NbtElement<'_>) -> bool { match (pattern, element,) {
+    (NbtElement::Compound(pattern), _,)              => matches_compound(pattern, element),
+    (NbtElement::List(pattern), NbtElement::List(v),) => {
+        if (pattern.is_empty()) { v.is_empty() }
+        else { pattern.iter().all(|pattern| v.iter().any(|child| matches(pattern, child))) }
+    },
+    (NbtElement::Byte(a),   NbtElement::Byte(b),)   => a == b,
+    (NbtElement::Short(a),  NbtElement::Short(b),)  => a == b,
+    (NbtElement::Int(a),    NbtElement::Int(b),)    => a == b,
+    (NbtElement::Long(a),   NbtElement::Long(b),)   => a == b,
+    (NbtElement::Float(a),  NbtElement::Float(b),)  => a == b,
+    (NbtElement::Double(a), NbtElement::Double(b),) => a == b,
+    (NbtElement::BArray(a), NbtElement::BArray(b),) => **a == **b,
+    (NbtElement::String(a), NbtElement::String(b),) => **a == **b,
+    (NbtElement::IArray(a), NbtElement::IArray(b),) => **a == **b,
+    (NbtElement::LArray(a), NbtElement::LArray(b),) => **a == **b,
+    _                                               => false
+} }
+
+/// Copies a pattern into a tree with any lifetime.
+fn copy_compound<'l>(compound : &NbtCompound<'_>) -> NbtCompound<'l> {
+    compound.iter().map(|(key, element,)| (key.to_string(), copy_element(element),)).collect()
+}
+
+fn copy_element<'l>(element : &NbtElement<'_>) -> NbtElement<'l> { match (element) {
+    NbtElement::Byte(v)     => NbtElement::Byte(*v),
+    NbtElement::Short(v)    => NbtElement::Short(*v),
+    NbtElement::Int(v)      => NbtElement::Int(*v),
+    NbtElement::Long(v)     => NbtElement::Long(*v),
+    NbtElement::Float(v)    => NbtElement::Float(*v),
+    NbtElement::Double(v)   => NbtElement::Double(*v),
+    NbtElement::BArray(v)   => NbtElement::BArray(Cow::Owned(v.to_vec())),
+    NbtElement::String(v)   => NbtElement::String(Cow::Owned(v.to_string())),
+    NbtElement::List(v)     => NbtElement::List(Cow::Owned(v.iter().map(copy_element).collect())),
+    NbtElement::Compound(v) => NbtElement::Compound(copy_compound(v)),
+    NbtElement::IArray(v)   => NbtElement::IArray(Cow::Owned(v.to_vec())),
+    NbtElement::LArray(v)   => NbtElement::LArray(Cow::Owned(v.to_vec()))
+} }
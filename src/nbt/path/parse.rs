@@ -0,0 +1,136 @@
+use super::{
+    NbtPath,
+    NbtPathNode,
+    NbtPathParseError,
+    NbtPathParseErrorKind,
+    is_unquoted_key_char
+};
+use crate::nbt::{
+    NbtCompound,
+    SnbtParseError,
+    parse_compound_prefix,
+    parse_quoted_prefix
+};
+use core::str::FromStr;
+
+
+impl FromStr for NbtPath {
+    type Err = NbtPathParseError;
+
+    /// Parses an NBT path, such as `Items[{Slot:0b}].tag.display.Name`.
+    fn from_str(s : &str) -> Result<Self, Self::Err> {
+        let mut parser = NbtPathParser { src : s, pos : 0 };
+        let mut nodes  = Vec::new();
+        loop {
+            nodes.push(parser.parse_node(nodes.is_empty())?);
+            match (parser.peek()) {
+                None            => { break; },
+                Some('[' | '{') => { },
+                Some('.')       => { parser.pos += 1; },
+                Some(ch)        => { return Err(parser.error(NbtPathParseErrorKind::UnexpectedChar(ch))); }
+            }
+        }
+        Ok(NbtPath { nodes })
+    }
+}
+
+
+struct NbtPathParser<'l> {
+    src : &'l str,
+    pos : usize
+}
+
+impl NbtPathParser<'_> {
+
+    #[inline]
+    fn error(&self, kind : NbtPathParseErrorKind) -> NbtPathParseError {
+        NbtPathParseError { offset : self.pos, kind }
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn unexpected(&self) -> NbtPathParseError { match (self.peek()) {
+        Some(ch) => self.error(NbtPathParseErrorKind::UnexpectedChar(ch)),
+        None     => self.error(NbtPathParseErrorKind::UnexpectedEnd)
+    } }
+
+    fn expect(&mut self, expected : char) -> Result<(), NbtPathParseError> {
+        if (self.peek() == Some(expected)) {
+            self.pos += expected.len_utf8();
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn snbt_error(&self, err : SnbtParseError) -> NbtPathParseError {
+        NbtPathParseError { offset : self.pos + err.offset, kind : NbtPathParseErrorKind::BadSnbt(err.kind) }
+    }
+
+    fn parse_node(&mut self, first : bool) -> Result<NbtPathNode, NbtPathParseError> {
+        match (self.peek()) {
+            Some('{') if (first) => Ok(NbtPathNode::MatchRoot(self.parse_compound()?)),
+            Some('[') => {
+                self.pos += 1;
+                match (self.peek()) {
+                    Some('{') => {
+                        let pattern = self.parse_compound()?;
+                        self.expect(']')?;
+                        Ok(NbtPathNode::MatchElements(pattern))
+                    },
+                    Some(']') => {
+                        self.pos += 1;
+                        Ok(NbtPathNode::All)
+                    },
+                    _ => {
+                        let index = self.parse_index()?;
+                        self.expect(']')?;
+                        Ok(NbtPathNode::Index(index))
+                    }
+                }
+            },
+            Some('"' | '\'') => {
+                let (key, len,) = parse_quoted_prefix(&self.src[self.pos..]).map_err(|err| self.snbt_error(err))?;
+                self.pos += len;
+                self.parse_key_node(key.into_owned())
+            },
+            Some(ch) if (is_unquoted_key_char(ch)) => {
+                let start = self.pos;
+                while let Some(ch) = self.peek() && is_unquoted_key_char(ch) {
+                    self.pos += ch.len_utf8();
+                }
+                let key = self.src[start..self.pos].to_string();
+                self.parse_key_node(key)
+            },
+            _ => Err(self.unexpected())
+        }
+    }
+
+    fn parse_key_node(&mut self, key : String) -> Result<NbtPathNode, NbtPathParseError> {
+        if (self.peek() == Some('{')) {
+            Ok(NbtPathNode::MatchKey(key, self.parse_compound()?))
+        } else {
+            Ok(NbtPathNode::Key(key))
+        }
+    }
+
+    fn parse_index(&mut self) -> Result<i32, NbtPathParseError> {
+        let start = self.pos;
+        while let Some(ch) = self.peek() && (ch.is_ascii_digit() || ch == '-') {
+            self.pos += 1;
+        }
+        if (self.pos == start) { return Err(self.unexpected()); }
+        self.src[start..self.pos].parse::<i32>()
+            .map_err(|_| NbtPathParseError { offset : start, kind : NbtPathParseErrorKind::BadIndex })
+    }
+
+    fn parse_compound(&mut self) -> Result<NbtCompound<'static>, NbtPathParseError> {
+        let (compound, len,) = parse_compound_prefix(&self.src[self.pos..]).map_err(|err| self.snbt_error(err))?;
+        self.pos += len;
+        Ok(compound.into_owned())
+    }
+
+}
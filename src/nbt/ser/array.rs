@@ -0,0 +1,148 @@
+use super::{
+    NbtSerError,
+    NbtRootSerer, TagWrite,
+    never::Never
+};
+use std::io::Write;
+use serde::ser::{
+    Serialize as Ser,
+    SerializeSeq as SerSeq,
+    SerializeTuple as SerTuple,
+    Serializer as Serer
+};
+
+
+pub(super) struct NbtArraySerer<'l, W>
+where
+    W : Write
+{
+    writer   : &'l mut W,
+    elem_tag : u8
+}
+
+impl<'l, W> NbtArraySerer<'l, W>
+where
+    W : Write
+{ pub(super) fn new(writer : &'l mut W, elem_tag : u8) -> Self { Self {
+    writer, elem_tag
+} } }
+
+
+impl<'l, W> Serer for NbtArraySerer<'l, W>
+where
+    W : Write
+{
+    type Ok    = ();
+    type Error = NbtSerError;
+
+    type SerializeSeq           = NbtArraySeqSerer<'l, W>;
+    type SerializeTuple         = NbtArraySeqSerer<'l, W>;
+    type SerializeTupleStruct   = Never;
+    type SerializeTupleVariant  = Never;
+    type SerializeMap           = Never;
+    type SerializeStruct        = Never;
+    type SerializeStructVariant = Never;
+
+    fn serialize_bytes(self, v : &[u8]) -> Result<Self::Ok, Self::Error> {
+        if (self.elem_tag != super::tag::BYTE) { return Err(NbtSerError::BadArray); }
+        self.writer.write_all(&i32::try_from(v.len()).map_err(|_| NbtSerError::BadArray)?.to_be_bytes())?;
+        self.writer.write_all(v)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T>(self, _ : &'static str, value : &T) -> Result<Self::Ok, Self::Error>
+    where
+        T : ?Sized + Ser
+    { value.serialize(self) }
+
+    fn serialize_seq(self, len : Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        let Some(len) = len else { return Err(NbtSerError::BadArray); };
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple(self, len : usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.writer.write_all(&i32::try_from(len).map_err(|_| NbtSerError::BadArray)?.to_be_bytes())?;
+        Ok(NbtArraySeqSerer { writer : self.writer, elem_tag : self.elem_tag, remaining : len })
+    }
+
+    fn serialize_bool(self, _ : bool) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_i8(self, _ : i8) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_i16(self, _ : i16) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_i32(self, _ : i32) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_i64(self, _ : i64) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_u8(self, _ : u8) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_u16(self, _ : u16) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_u32(self, _ : u32) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_u64(self, _ : u64) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_f32(self, _ : f32) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_f64(self, _ : f64) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_char(self, _ : char) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_str(self, _ : &str) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_some<T>(self, _ : &T) -> Result<Self::Ok, Self::Error> where T : ?Sized + Ser { Err(NbtSerError::BadArray) }
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_unit_struct(self, _ : &'static str) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_unit_variant(self, _ : &'static str, _ : u32, _ : &'static str) -> Result<Self::Ok, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_newtype_variant<T>(self, _ : &'static str, _ : u32, _ : &'static str, _ : &T) -> Result<Self::Ok, Self::Error> where T : ?Sized + Ser { Err(NbtSerError::BadArray) }
+    fn serialize_tuple_struct(self, _ : &'static str, _ : usize) -> Result<Self::SerializeTupleStruct, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_tuple_variant(self, _ : &'static str, _ : u32, _ : &'static str, _ : usize) -> Result<Self::SerializeTupleVariant, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_map(self, _ : Option<usize>) -> Result<Self::SerializeMap, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_struct(self, _ : &'static str, _ : usize) -> Result<Self::SerializeStruct, Self::Error> { Err(NbtSerError::BadArray) }
+    fn serialize_struct_variant(self, _ : &'static str, _ : u32, _ : &'static str, _ : usize) -> Result<Self::SerializeStructVariant, Self::Error> { Err(NbtSerError::BadArray) }
+
+}
+
+
+pub(super) struct NbtArraySeqSerer<'l, W>
+where
+    W : Write
+{
+    writer    : &'l mut W,
+    elem_tag  : u8,
+    remaining : usize
+}
+
+
+impl<'l, W> SerSeq for NbtArraySeqSerer<'l, W>
+where
+    W : Write
+{
+    type Ok    = ();
+    type Error = NbtSerError;
+
+    #[inline]
+    fn serialize_element<T>(&mut self, value : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    { SerTuple::serialize_element(self, value) }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerTuple::end(self)
+    }
+
+}
+
+
+impl<'l, W> SerTuple for NbtArraySeqSerer<'l, W>
+where
+    W : Write
+{
+    type Ok    = ();
+    type Error = NbtSerError;
+
+    fn serialize_element<T>(&mut self, value : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    {
+        self.remaining = self.remaining.checked_sub(1).ok_or(NbtSerError::BadArray)?;
+        value.serialize(NbtRootSerer::new(&mut*self.writer, TagWrite::Expect(self.elem_tag)))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        if (self.remaining != 0) { return Err(NbtSerError::BadArray); }
+        Ok(())
+    }
+
+}
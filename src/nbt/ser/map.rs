@@ -6,9 +6,11 @@ use super::{
 };
 use std::io::Write;
 use serde::ser::{
+    Error as SerError,
     Serialize as Ser,
     SerializeMap as SerMap,
-    SerializeStruct as SerStruct
+    SerializeStruct as SerStruct,
+    SerializeStructVariant as SerStructVariant
 };
 
 
@@ -16,16 +18,40 @@ pub(super) struct NbtMapSerer<'l, W>
 where
     W : Write
 {
-    writer : &'l mut W
+    writer     : &'l mut W,
+    /// The key given to [`SerMap::serialize_key`], waiting for its value.
+    key        : Option<String>,
+    in_variant : bool
 }
 
 impl<'l, W> From<&'l mut W> for NbtMapSerer<'l, W>
 where
     W : Write
 { fn from(writer : &'l mut W) -> Self { Self {
-    writer
+    writer, key : None, in_variant : false
 } } }
 
+impl<'l, W> NbtMapSerer<'l, W>
+where
+    W : Write
+{
+
+    /// Also closes the compound wrapping an enum variant when ended.
+    pub(super) fn in_variant(mut self) -> Self {
+        self.in_variant = true;
+        self
+    }
+
+    fn close(self) -> Result<(), NbtSerError> {
+        self.writer.write_all(&[tag::END])?;
+        if (self.in_variant) {
+            self.writer.write_all(&[tag::END])?;
+        }
+        Ok(())
+    }
+
+}
+
 
 impl<'l, W> SerMap for NbtMapSerer<'l, W>
 where
@@ -34,10 +60,24 @@ where
     type Ok    = ();
     type Error = NbtSerError;
 
-    #[inline]
-    fn serialize_key<T>(&mut self, _ : &T) -> Result<(), Self::Error> where T : ?Sized + Ser { unreachable!() }
-    #[inline]
-    fn serialize_value<T>(&mut self, _ : &T) -> Result<(), Self::Error> where T : ?Sized + Ser { unreachable!() }
+    fn serialize_key<T>(&mut self, key : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    {
+        let mut key_ser = NbtStrSerer::default();
+        key.serialize(&mut key_ser)?;
+        self.key = Some(key_ser.string);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    {
+        let Some(key) = self.key.take()
+            else { return Err(NbtSerError::custom("NBT compound value serialised before its key")); };
+        value.serialize(NbtRootSerer::new(self.writer, TagWrite::Entry(&key)))
+    }
 
     fn serialize_entry<K, V>(&mut self, key : &K, value : &V) -> Result<(), Self::Error>
     where
@@ -46,14 +86,13 @@ where
     {
         let mut key_ser = NbtStrSerer::default();
         key.serialize(&mut key_ser)?;
-        value.serialize(NbtRootSerer::new(self.writer, TagWrite::TagAndString(&key_ser.string)))?;
+        value.serialize(NbtRootSerer::new(self.writer, TagWrite::Entry(&key_ser.string)))?;
         Ok(())
     }
 
     #[inline]
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.writer.write_all(&[tag::END])?;
-        Ok(())
+        self.close()
     }
 
 }
@@ -70,11 +109,30 @@ where
     fn serialize_field<T>(&mut self, key : &'static str, value : &T) -> Result<(), Self::Error>
     where
         T : ?Sized + Ser
-    { value.serialize(NbtRootSerer::new(self.writer, TagWrite::TagAndString(key))) }
+    { value.serialize(NbtRootSerer::new(self.writer, TagWrite::Entry(key))) }
 
     #[inline]
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.writer.write_all(&[tag::END])?;
-        Ok(())
+        self.close()
+    }
+}
+
+
+impl<'l, W> SerStructVariant for NbtMapSerer<'l, W>
+where
+    W : Write
+{
+    type Ok    = ();
+    type Error = NbtSerError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, key : &'static str, value : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    { SerStruct::serialize_field(self, key, value) }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.close()
     }
 }
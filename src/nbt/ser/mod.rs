@@ -1,4 +1,7 @@
-use super::tag;
+use super::{
+    NbtCompression,
+    tag
+};
 use core::{
     error::Error as StdError,
     fmt::{ self, Display, Formatter }
@@ -8,6 +11,10 @@ use serde::ser::{
     Error as SerError,
     Serialize as Ser
 };
+use flate2::{
+    Compression,
+    write::{ GzEncoder, ZlibEncoder }
+};
 
 
 mod root;
@@ -18,6 +25,10 @@ mod map;
 use map::NbtMapSerer;
 mod str;
 use str::NbtStrSerer;
+mod array;
+use array::NbtArraySerer;
+mod size;
+use size::NbtSizeCounter;
 
 mod never;
 
@@ -28,7 +39,29 @@ pub enum NbtSerError {
     /// An io error occured.
     Io(io::Error),
     /// A value serialiser returned a custom error.
-    Custom(String)
+    Custom(String),
+
+    /// An NBT array marker wrapped a value which is not a sequence of the array's element type.
+    BadArray,
+    /// A sequence contained elements of different types, which can not be written as an NBT list.
+    MixedList {
+        /// The tag of the first element.
+        expected : u8,
+        /// The tag of the element which did not match it.
+        found    : u8
+    },
+    /// `None` or `()` was serialised somewhere other than a compound entry or the root of network NBT.
+    MissingValue,
+    /// A string or compound key is longer than 65535 bytes when encoded as CESU-8.
+    StringTooLong(usize),
+    /// A compound key was not a string.
+    KeyMustBeString,
+    /// A sequence did not give its length in advance, which NBT lists are prefixed with.
+    UnknownLength,
+    /// A sequence is longer than the longest NBT list.
+    ListTooLong(usize),
+    /// A sequence serialised a different number of elements than the length it gave in advance.
+    LengthMismatch(usize)
 }
 impl From<io::Error> for NbtSerError {
     #[inline]
@@ -39,8 +72,16 @@ impl From<io::Error> for NbtSerError {
 
 impl Display for NbtSerError {
     fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result { match (self) {
-        Self::Io(err)     => write!(f, "{err}"),
-        Self::Custom(err) => write!(f, "{err}")
+        Self::Io(err)                       => write!(f, "{err}"),
+        Self::Custom(err)                   => write!(f, "{err}"),
+        Self::BadArray                      => write!(f, "bad array"),
+        Self::MixedList { expected, found } => write!(f, "mixed list (expected tag 0x{expected:0>2X}, found 0x{found:0>2X})"),
+        Self::MissingValue                  => write!(f, "missing value"),
+        Self::StringTooLong(v)              => write!(f, "string too long ({v} bytes)"),
+        Self::KeyMustBeString               => write!(f, "key must be a string"),
+        Self::UnknownLength                 => write!(f, "sequence length not known in advance"),
+        Self::ListTooLong(v)                => write!(f, "list too long ({v} elements)"),
+        Self::LengthMismatch(v)             => write!(f, "sequence length mismatch (expected {v} elements)")
     } }
 }
 impl StdError for NbtSerError { }
@@ -54,6 +95,12 @@ impl SerError for NbtSerError {
 }
 
 
+/// Returns the length prefix of a string encoded as CESU-8.
+pub(in crate::nbt) fn string_len(jstring : &[u8]) -> Result<u16, NbtSerError> {
+    u16::try_from(jstring.len()).map_err(|_| NbtSerError::StringTooLong(jstring.len()))
+}
+
+
 /// Serialise a value as network NBT.
 pub fn to_network<W, T>(mut writer : W, value : &T) -> Result<(), NbtSerError>
 where
@@ -63,3 +110,193 @@ where
     value.serialize(NbtRootSerer::new(&mut writer, TagWrite::Tag))?;
     Ok(())
 }
+
+/// Returns the number of bytes that [`to_network`] would write for a value, without writing them.
+pub fn network_size<T>(value : &T) -> Result<usize, NbtSerError>
+where
+    T : Ser
+{
+    let mut counter = NbtSizeCounter::default();
+    value.serialize(NbtRootSerer::new(&mut counter, TagWrite::Tag))?;
+    Ok(counter.size)
+}
+
+
+/// Serialise a value as an NBT file with a named root.
+pub fn to_file<W, T>(writer : W, compression : NbtCompression, root_name : &str, value : &T) -> Result<(), NbtSerError>
+where
+    W : Write,
+    T : Ser
+{
+    fn write_root<W, T>(mut writer : W, root_name : &str, value : &T) -> Result<W, NbtSerError>
+    where
+        W : Write,
+        T : Ser
+    {
+        value.serialize(NbtRootSerer::new(&mut writer, TagWrite::TagAndString(root_name)))?;
+        Ok(writer)
+    }
+    match (compression) {
+        NbtCompression::None => { write_root(writer, root_name, value)?; },
+        NbtCompression::Gzip => { write_root(GzEncoder::new(writer, Compression::default()), root_name, value)?.finish()?; },
+        NbtCompression::Zlib => { write_root(ZlibEncoder::new(writer, Compression::default()), root_name, value)?.finish()?; }
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::nbt::{ *, tag };
+    use serde::{
+        Serialize as Ser,
+        Deserialize as Deser
+    };
+
+    #[derive(Ser, Deser, PartialEq, Debug)]
+    struct Newtype(i32);
+
+    #[derive(Ser, Deser, PartialEq, Debug)]
+    struct Tuple(i32, i32, i32);
+
+    #[derive(Ser, Deser, PartialEq, Debug)]
+    struct Mixed(i32, String);
+
+    #[derive(Ser, Deser, PartialEq, Debug)]
+    enum Enum {
+        Unit,
+        Newtype(Option<i64>),
+        Tuple(i16, i16),
+        Struct { a : String, b : Option<u8> }
+    }
+
+    #[derive(Ser, Deser, PartialEq, Debug)]
+    struct All {
+        newtype      : Newtype,
+        tuple        : Tuple,
+        unit_variant : Enum,
+        enums        : Vec<Enum>,
+        optional     : Option<i32>
+    }
+
+    fn round_trip<T>(value : &T) -> T
+    where
+        T : Ser + for<'l> Deser<'l>
+    {
+        let mut buf = Vec::new();
+        to_network(&mut buf, value).unwrap();
+        assert_eq!(buf.len(), network_size(value).unwrap());
+        assert_eq!(to_element(value).unwrap(), from_network::<_, NbtElement>(&*buf).unwrap());
+        from_network(&*buf).unwrap()
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let value = All {
+            newtype      : Newtype(5),
+            tuple        : Tuple(1, 2, 3),
+            unit_variant : Enum::Unit,
+            enums        : vec![Enum::Newtype(Some(7)), Enum::Tuple(-1, 1), Enum::Struct { a : "a".to_string(), b : None }],
+            optional     : None
+        };
+        assert_eq!(round_trip(&value), value);
+        assert_eq!(round_trip(&Tuple(4, 5, 6)), Tuple(4, 5, 6));
+        assert_eq!(round_trip(&Vec::<Tuple>::new()), Vec::new());
+        let mixed = NbtElement::List(vec![NbtElement::Int(1), NbtElement::String("a".into()), NbtElement::List(vec![].into())].into());
+        assert_eq!(round_trip(&mixed), mixed);
+    }
+
+    #[test]
+    fn mixed_lists() {
+        assert!(matches!(
+            to_network(Vec::new(), &Mixed(1, "a".to_string())),
+            Err(NbtSerError::MixedList { expected : tag::INT, found : tag::STRING })
+        ));
+        assert!(matches!(
+            to_network(Vec::new(), &vec![Enum::Unit, Enum::Tuple(1, 2)]),
+            Err(NbtSerError::MixedList { expected : tag::STRING, found : tag::COMPOUND })
+        ));
+        assert!(matches!(
+            to_element(&Mixed(1, "a".to_string())),
+            Err(NbtSerError::MixedList { expected : tag::INT, found : tag::STRING })
+        ));
+    }
+
+    #[test]
+    fn missing_values() {
+        assert!(matches!(to_network(Vec::new(), &vec![Some(1), None]), Err(NbtSerError::MissingValue)));
+        assert!(matches!(to_network(Vec::new(), &vec![(), ()]), Err(NbtSerError::MissingValue)));
+        assert!(matches!(to_network(Vec::new(), &Enum::Newtype(None)), Err(NbtSerError::MissingValue)));
+        assert!(matches!(to_element(&vec![Some(1), None]), Err(NbtSerError::MissingValue)));
+        assert!(matches!(to_element(&Enum::Newtype(None)), Err(NbtSerError::MissingValue)));
+        assert!(matches!(to_file(Vec::new(), NbtCompression::None, "", &None::<i32>), Err(NbtSerError::MissingValue)));
+        let mut buf = Vec::new();
+        to_network(&mut buf, &None::<i32>).unwrap();
+        assert_eq!(buf, [tag::END]);
+    }
+
+
+    /// A map written with separate keys and values, holding a sequence of unknown length.
+    struct Unsized;
+    impl Ser for Unsized {
+        fn serialize<S>(&self, serer : S) -> Result<S::Ok, S::Error>
+        where
+            S : serde::Serializer
+        {
+            use serde::ser::SerializeMap as _;
+            let mut map = serer.serialize_map(None)?;
+            map.serialize_key("list")?;
+            map.serialize_value(&[1, 2])?;
+            map.serialize_key("seq")?;
+            map.serialize_value(&UnsizedSeq)?;
+            map.end()
+        }
+    }
+    struct UnsizedSeq;
+    impl Ser for UnsizedSeq {
+        fn serialize<S>(&self, serer : S) -> Result<S::Ok, S::Error>
+        where
+            S : serde::Serializer
+        { serde::ser::SerializeSeq::end(serer.serialize_seq(None)?) }
+    }
+    struct HugeTuple;
+    impl Ser for HugeTuple {
+        fn serialize<S>(&self, serer : S) -> Result<S::Ok, S::Error>
+        where
+            S : serde::Serializer
+        { serde::ser::SerializeTuple::end(serer.serialize_tuple(1 << 31)?) }
+    }
+    /// A sequence which gives a length of `.0`, but serialises `.1` elements.
+    struct WrongLength(usize, usize,);
+    impl Ser for WrongLength {
+        fn serialize<S>(&self, serer : S) -> Result<S::Ok, S::Error>
+        where
+            S : serde::Serializer
+        {
+            use serde::ser::SerializeSeq as _;
+            let mut seq = serer.serialize_seq(Some(self.0))?;
+            for i in 0..self.1 { seq.serialize_element(&(i as i32))?; }
+            seq.end()
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let map = [(1, 2,)].into_iter().collect::<std::collections::BTreeMap<_, _>>();
+        assert!(matches!(to_network(Vec::new(), &map), Err(NbtSerError::KeyMustBeString)));
+        assert!(matches!(to_element(&map), Err(NbtSerError::KeyMustBeString)));
+        let chars = [('a', 2,)].into_iter().collect::<std::collections::BTreeMap<_, _>>();
+        assert_eq!(to_element(&chars).unwrap().to_string(), "{a:2}");
+        assert!(matches!(to_network(Vec::new(), &Unsized), Err(NbtSerError::UnknownLength)));
+        assert!(matches!(to_network(Vec::new(), &HugeTuple), Err(NbtSerError::ListTooLong(0x8000_0000))));
+        assert!(matches!(to_network(Vec::new(), &WrongLength(2, 1,)), Err(NbtSerError::LengthMismatch(2))));
+        assert!(matches!(to_network(Vec::new(), &WrongLength(2, 3,)), Err(NbtSerError::LengthMismatch(2))));
+        assert!(matches!(to_network(Vec::new(), &WrongLength(1, 0,)), Err(NbtSerError::LengthMismatch(1))));
+        assert!(matches!(to_network(Vec::new(), &WrongLength(0, 1,)), Err(NbtSerError::LengthMismatch(0))));
+        let mut buf = Vec::new();
+        to_network(&mut buf, &WrongLength(2, 2,)).unwrap();
+        assert_eq!(from_network::<_, Vec<i32>>(&*buf).unwrap(), [0, 1]);
+    }
+
+
+}
@@ -1,14 +1,21 @@
 use super::{
     NbtSerError,
+    string_len,
     NbtSeqSerer,
     NbtMapSerer,
-    never::Never,
+    NbtArraySerer,
     tag
 };
-use std::io::{ self, Write };
+use crate::nbt::{
+    BARRAY_TOKEN,
+    IARRAY_TOKEN,
+    LARRAY_TOKEN
+};
+use std::io::Write;
 use cesu8::to_java_cesu8;
 use serde::ser::{
     Serialize as Ser,
+    SerializeStruct as SerStruct,
     Serializer as Serer
 };
 
@@ -30,34 +37,56 @@ where
         Self { writer, tag_write }
     }
 
-    fn handle_tag_write(self : &mut &mut Self, tag : u8) -> io::Result<()> { match (&self.tag_write) {
-        TagWrite::None => Ok(()),
+    fn handle_tag_write(self : &mut &mut Self, tag : u8) -> Result<(), NbtSerError> { match (&mut self.tag_write) {
+        TagWrite::Expect(expected) => {
+            if (tag == *expected) { Ok(()) } else { Err(NbtSerError::BadArray) }
+        },
         TagWrite::Tag => {
             self.writer.write_all(&[tag])?;
             Ok(())
         },
-        TagWrite::TagAndU32(v) => {
+        TagWrite::ListHead(len, elem_tag) => {
+            **elem_tag = tag;
             self.writer.write_all(&[tag])?;
-            self.writer.write_all(&v.to_be_bytes())?;
+            self.writer.write_all(&len.to_be_bytes())?;
             Ok(())
         },
-        TagWrite::TagAndString(s) => {
+        TagWrite::ListElement(expected) => {
+            if (tag == *expected) { Ok(()) } else { Err(NbtSerError::MixedList { expected : *expected, found : tag }) }
+        },
+        TagWrite::TagAndString(s) | TagWrite::Entry(s) => {
             self.writer.write_all(&[tag])?;
             let jstring = to_java_cesu8(s);
-            self.writer.write_all(&u16::try_from(jstring.len()).unwrap().to_be_bytes())?;
+            self.writer.write_all(&string_len(&jstring)?.to_be_bytes())?;
             self.writer.write_all(&jstring)?;
             Ok(())
         }
     } }
 
+    /// Handles `None` and `()`, which are left out of compounds and written as an empty root.
+    fn handle_no_value(mut self) -> Result<(), NbtSerError> { match (&self.tag_write) {
+        TagWrite::Entry(_)  => Ok(()),
+        TagWrite::Tag       => (&mut self).handle_tag_write(tag::END),
+        TagWrite::Expect(_) => Err(NbtSerError::BadArray),
+        _                   => Err(NbtSerError::MissingValue)
+    } }
+
 }
 
 
 pub(super) enum TagWrite<'l> {
-    None,
+    /// Writes nothing, but fails if the value does not have the given array element tag.
+    Expect(u8),
+    /// Writes the tag of the value.
     Tag,
-    TagAndU32(u32),
-    TagAndString(&'l str)
+    /// Writes the tag of the first element of a list and the list's length, and stores the tag.
+    ListHead(u32, &'l mut u8),
+    /// Writes nothing, but fails if the value does not have the tag of the list's first element.
+    ListElement(u8),
+    /// Writes the tag of the value and a name.
+    TagAndString(&'l str),
+    /// Writes the tag of the value and a compound key, or nothing if the value is `None` or `()`.
+    Entry(&'l str)
 }
 
 
@@ -70,11 +99,11 @@ where
 
     type SerializeSeq           = NbtSeqSerer<'l, W>;
     type SerializeTuple         = NbtSeqSerer<'l, W>;
-    type SerializeTupleStruct   = Never;
-    type SerializeTupleVariant  = Never;
+    type SerializeTupleStruct   = NbtSeqSerer<'l, W>;
+    type SerializeTupleVariant  = NbtSeqSerer<'l, W>;
     type SerializeMap           = NbtMapSerer<'l, W>;
     type SerializeStruct        = NbtMapSerer<'l, W>;
-    type SerializeStructVariant = Never;
+    type SerializeStructVariant = NbtMapSerer<'l, W>;
 
     #[inline]
     fn serialize_bool(self, v : bool) -> Result<Self::Ok, Self::Error> {
@@ -136,17 +165,18 @@ where
     fn serialize_str(mut self, v : &str) -> Result<Self::Ok, Self::Error> {
         (&mut self).handle_tag_write(tag::STRING)?;
         let jstring = to_java_cesu8(v);
-        self.writer.write_all(&u16::try_from(jstring.len()).unwrap().to_be_bytes())?;
+        self.writer.write_all(&string_len(&jstring)?.to_be_bytes())?;
         self.writer.write_all(&jstring)?;
         Ok(())
     }
-    fn serialize_bytes(self, _ : &[u8]) -> Result<Self::Ok, Self::Error> {
-        todo!() // TODO: BArray
+    fn serialize_bytes(mut self, v : &[u8]) -> Result<Self::Ok, Self::Error> {
+        (&mut self).handle_tag_write(tag::BARRAY)?;
+        NbtArraySerer::new(&mut*self.writer, tag::BYTE).serialize_bytes(v)
     }
 
     #[inline]
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        self.handle_no_value()
     }
     #[inline]
     fn serialize_some<T>(self, v : &T) -> Result<Self::Ok, Self::Error>
@@ -154,13 +184,14 @@ where
         T : ?Sized + Ser
     { v.serialize(self) }
 
-    fn serialize_unit(mut self) -> Result<Self::Ok, Self::Error> {
-        (&mut self).handle_tag_write(tag::END)?;
-        Ok(())
+    #[inline]
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.handle_no_value()
     }
 
-    fn serialize_unit_struct(self, _name : &'static str) -> Result<Self::Ok, Self::Error> {
-        unreachable!("unit structs are not supported by the NBT serialiser");
+    #[inline]
+    fn serialize_unit_struct(self, name : &'static str) -> Result<Self::Ok, Self::Error> {
+        SerStruct::end(self.serialize_struct(name, 0)?)
     }
 
     #[inline]
@@ -174,55 +205,68 @@ where
     }
 
     fn serialize_newtype_struct<T>(
-        self,
-        _name  : &'static str,
-        _value : &T,
+        mut self,
+        name  : &'static str,
+        value : &T,
     ) -> Result<Self::Ok, Self::Error>
     where
         T : ?Sized + Ser
     {
-        todo!()
+        let (array_tag, elem_tag,) = match (name) {
+            BARRAY_TOKEN => (tag::BARRAY, tag::BYTE,),
+            IARRAY_TOKEN => (tag::IARRAY, tag::INT,),
+            LARRAY_TOKEN => (tag::LARRAY, tag::LONG,),
+            _            => { return value.serialize(self); }
+        };
+        (&mut self).handle_tag_write(array_tag)?;
+        value.serialize(NbtArraySerer::new(&mut*self.writer, elem_tag))
     }
 
     fn serialize_newtype_variant<T>(
-        self,
+        mut self,
         _name          : &'static str,
         _variant_index : u32,
-        _variant       : &'static str,
-        _value         : &T,
+        variant        : &'static str,
+        value          : &T,
     ) -> Result<Self::Ok, Self::Error>
     where
         T : ?Sized + Ser
     {
-        unreachable!("newtype variants are not supported by the NBT serialiser");
+        (&mut self).handle_tag_write(tag::COMPOUND)?;
+        value.serialize(NbtRootSerer::new(&mut*self.writer, TagWrite::TagAndString(variant)))?;
+        self.writer.write_all(&[tag::END])?;
+        Ok(())
     }
 
     fn serialize_seq(self, len : Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-        let Some(len) = len else { panic!("NBT sequence serialiser requires length to be known in advance"); };
+        let Some(len) = len else { return Err(NbtSerError::UnknownLength); };
         self.serialize_tuple(len)
     }
 
     fn serialize_tuple(mut self, len : usize) -> Result<Self::SerializeTuple, Self::Error> {
-        (&mut self).handle_tag_write(tag::LIST)?; // TODO: BArray, IArray, LArray
-        Ok(NbtSeqSerer::new(&mut*self.writer, len.try_into().unwrap()))
+        let len = i32::try_from(len).map_err(|_| NbtSerError::ListTooLong(len))?;
+        (&mut self).handle_tag_write(tag::LIST)?;
+        Ok(NbtSeqSerer::new(&mut*self.writer, len.cast_unsigned()))
     }
 
+    #[inline]
     fn serialize_tuple_struct(
         self,
         _name : &'static str,
-        _len  : usize,
+        len   : usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
-        unreachable!("tuple structs are not supported by the NBT serialiser");
+        self.serialize_tuple(len)
     }
 
     fn serialize_tuple_variant(
-        self,
+        mut self,
         _name          : &'static str,
         _variant_index : u32,
-        _variant       : &'static str,
-        _len           : usize,
+        variant        : &'static str,
+        len            : usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        unreachable!("tuple variants are not supported by the NBT serialiser");
+        (&mut self).handle_tag_write(tag::COMPOUND)?;
+        Ok(NbtRootSerer::new(self.writer, TagWrite::TagAndString(variant)).serialize_tuple(len)?.in_variant())
     }
 
     fn serialize_map(mut self, _ : Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
@@ -240,13 +284,14 @@ where
     }
 
     fn serialize_struct_variant(
-        self,
-        _name          : &'static str,
+        mut self,
+        name           : &'static str,
         _variant_index : u32,
-        _variant       : &'static str,
-        _len           : usize,
+        variant        : &'static str,
+        len            : usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        unreachable!("struct variants are not supported by the NBT serialiser");
+        (&mut self).handle_tag_write(tag::COMPOUND)?;
+        Ok(NbtRootSerer::new(self.writer, TagWrite::TagAndString(variant)).serialize_struct(name, len)?.in_variant())
     }
 
 }
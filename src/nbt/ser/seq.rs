@@ -7,7 +7,9 @@ use std::io::Write;
 use serde::ser::{
     Serialize as Ser,
     SerializeSeq as SerSeq,
-    SerializeTuple as SerTuple
+    SerializeTuple as SerTuple,
+    SerializeTupleStruct as SerTupleStruct,
+    SerializeTupleVariant as SerTupleVariant
 };
 
 
@@ -15,17 +17,30 @@ pub(super) struct NbtSeqSerer<'l, W>
 where
     W : Write
 {
-    writer : &'l mut W,
-    len    : u32,
-    first  : bool
+    writer     : &'l mut W,
+    len        : u32,
+    remaining  : u32,
+    first      : bool,
+    elem_tag   : u8,
+    in_variant : bool
 }
 
 impl<'l, W> NbtSeqSerer<'l, W>
 where
     W : Write
-{ pub(super) fn new(writer : &'l mut W, len : u32) -> Self { Self {
-    writer, len, first : true
-} } }
+{
+
+    pub(super) fn new(writer : &'l mut W, len : u32) -> Self { Self {
+        writer, len, remaining : len, first : true, elem_tag : tag::END, in_variant : false
+    } }
+
+    /// Also closes the compound wrapping an enum variant when ended.
+    pub(super) fn in_variant(mut self) -> Self {
+        self.in_variant = true;
+        self
+    }
+
+}
 
 
 impl<'l, W> SerSeq for NbtSeqSerer<'l, W>
@@ -60,18 +75,67 @@ where
     where
         T : ?Sized + Ser
     {
-        let after_tag = if (self.first) { TagWrite::TagAndU32(self.len) } else { TagWrite::None };
+        // The length has already been written, so the number of elements has to match it.
+        self.remaining = self.remaining.checked_sub(1).ok_or(NbtSerError::LengthMismatch(self.len as usize))?;
+        // NBT lists can only hold elements of one type, given by the first element.
+        let after_tag = if (self.first) { TagWrite::ListHead(self.len, &mut self.elem_tag) } else { TagWrite::ListElement(self.elem_tag) };
         self.first = false;
         value.serialize(NbtRootSerer::new(&mut*self.writer, after_tag))?;
         Ok(())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
+        if (self.remaining != 0) { return Err(NbtSerError::LengthMismatch(self.len as usize)); }
         if (self.first) {
             self.writer.write_all(&[tag::END])?;
             self.writer.write_all(&0u32.to_be_bytes())?;
         }
+        if (self.in_variant) {
+            self.writer.write_all(&[tag::END])?;
+        }
         Ok(())
     }
 
 }
+
+
+impl<'l, W> SerTupleStruct for NbtSeqSerer<'l, W>
+where
+    W : Write
+{
+    type Ok    = ();
+    type Error = NbtSerError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, value : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    { SerTuple::serialize_element(self, value) }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerTuple::end(self)
+    }
+
+}
+
+
+impl<'l, W> SerTupleVariant for NbtSeqSerer<'l, W>
+where
+    W : Write
+{
+    type Ok    = ();
+    type Error = NbtSerError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, value : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    { SerTuple::serialize_element(self, value) }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerTuple::end(self)
+    }
+
+}
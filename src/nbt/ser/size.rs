@@ -0,0 +1,27 @@
+use std::io::{ self, Write };
+
+
+/// Counts the bytes written to it, discarding them.
+#[derive(Default)]
+pub(super) struct NbtSizeCounter {
+    pub(super) size : usize
+}
+
+impl Write for NbtSizeCounter {
+
+    #[inline]
+    fn write(&mut self, buf : &[u8]) -> io::Result<usize> {
+        self.size += buf.len();
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf : &[u8]) -> io::Result<()> {
+        self.size += buf.len();
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+
+}
@@ -0,0 +1,149 @@
+use super::{
+    NbtElement,
+    NbtSerError,
+    NbtDeserError,
+    NbtLimits,
+    to_network,
+    from_network_with_limits
+};
+use core::{
+    error::Error as StdError,
+    fmt::{ self, Display, Formatter }
+};
+use serde::{
+    Serialize as Ser,
+    Deserialize as Deser
+};
+
+
+mod parse;
+pub(in crate::nbt) use parse::{ parse_compound_prefix, parse_quoted_prefix };
+mod print;
+pub(in crate::nbt) use print::write_quoted;
+
+
+/// Serialise a value as SNBT.
+pub fn to_snbt<T>(value : &T) -> Result<String, NbtSerError>
+where
+    T : Ser
+{
+    let mut buf = Vec::new();
+    to_network(&mut buf, value)?;
+    let element = from_network_with_limits::<_, NbtElement>(&*buf, NbtLimits::UNLIMITED).map_err(|err| NbtSerError::Custom(err.to_string()))?;
+    Ok(element.to_string())
+}
+
+/// Deserialise SNBT as a value.
+pub fn from_snbt<T>(snbt : &str) -> Result<T, NbtDeserError>
+where
+    for<'l> T : Deser<'l>
+{
+    let     element = NbtElement::from_snbt(snbt)?;
+    let mut buf     = Vec::new();
+    to_network(&mut buf, &element).map_err(|err| NbtDeserError::Custom(err.to_string()))?;
+    from_network_with_limits(&*buf, NbtLimits::UNLIMITED)
+}
+
+
+/// Returned by [`NbtElement::from_snbt`] when the input is not valid SNBT.
+#[derive(Debug)]
+pub struct SnbtParseError {
+    /// The byte offset in the input at which the error was found.
+    pub offset : usize,
+    /// What went wrong.
+    pub kind   : SnbtParseErrorKind
+}
+
+/// The kind of an [`SnbtParseError`].
+#[derive(Debug)]
+pub enum SnbtParseErrorKind {
+    /// The input ended in the middle of an element.
+    UnexpectedEnd,
+    /// An unexpected character was found.
+    UnexpectedChar(char),
+    /// An invalid escape sequence was found in a quoted string.
+    BadEscape,
+    /// A number is out of range for its type.
+    NumberOutOfRange,
+    /// An array contains an element of the wrong type.
+    BadArrayElement,
+    /// The input continues after the end of the element.
+    TrailingChars,
+    /// Lists and compounds are nested more than 512 deep.
+    TooDeep
+}
+
+impl Display for SnbtParseError {
+    #[inline]
+    fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.kind, self.offset)
+    }
+}
+impl StdError for SnbtParseError { }
+
+impl Display for SnbtParseErrorKind {
+    fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result { match (self) {
+        Self::UnexpectedEnd      => write!(f, "unexpected end of input"),
+        Self::UnexpectedChar(ch) => write!(f, "unexpected character {ch:?}"),
+        Self::BadEscape          => write!(f, "bad escape sequence"),
+        Self::NumberOutOfRange   => write!(f, "number out of range"),
+        Self::BadArrayElement    => write!(f, "bad array element"),
+        Self::TrailingChars      => write!(f, "trailing characters"),
+        Self::TooDeep            => write!(f, "nested too deeply")
+    } }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::NbtPath;
+
+    #[test]
+    fn print_parse_round_trip() {
+        let snbt    = "{a:1b,b:[I;1,2],c:[L;3L],d:[B;4B],e:[1s,2s],f:\"x\\\"y\",g:{h:1.5f,i:-2.0d},j:[],k:5L,\"l m\":'q\"'}";
+        let element = NbtElement::from_snbt(snbt).unwrap();
+        assert_eq!(element.to_string(), "{a:1b,b:[I;1,2],c:[L;3L],d:[B;4B],e:[1s,2s],f:'x\"y',g:{h:1.5f,i:-2.0d},j:[],k:5L,\"l m\":'q\"'}");
+        assert_eq!(element.to_string().parse::<NbtElement>().unwrap(), element);
+        assert_eq!(format!("{element:#}").parse::<NbtElement>().unwrap(), element);
+    }
+
+    #[test]
+    fn non_finite_floats() {
+        for element in [
+            NbtElement::Float(f32::INFINITY), NbtElement::Float(f32::NEG_INFINITY),
+            NbtElement::Double(f64::INFINITY), NbtElement::Double(f64::NEG_INFINITY)
+        ] {
+            assert_eq!(element.to_string().parse::<NbtElement>().unwrap(), element);
+        }
+        assert_eq!(NbtElement::Float(f32::NAN).to_string(), "NaNf");
+        assert!(matches!(NbtElement::from_snbt("NaNd").unwrap(), NbtElement::Double(v) if v.is_nan()));
+        assert_eq!(NbtElement::Float(0.1).to_string(), "0.1f");
+    }
+
+    #[test]
+    fn depth_limit() {
+        let nested = |depth : usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(NbtElement::from_snbt(&nested(512)).is_ok());
+        let err = NbtElement::from_snbt(&nested(100_000)).unwrap_err();
+        assert!(matches!(err.kind, SnbtParseErrorKind::TooDeep));
+        let err = NbtElement::from_snbt(&"{a:".repeat(100_000)).unwrap_err();
+        assert!(matches!(err.kind, SnbtParseErrorKind::TooDeep));
+        assert!(format!("a{}", "{b:".repeat(100_000)).parse::<NbtPath>().is_err());
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Value {
+            name  : String,
+            count : i32,
+            tags  : Vec<String>
+        }
+        let value = Value { name : "a".into(), count : 2, tags : vec!["x".into()] };
+        let snbt  = to_snbt(&value).unwrap();
+        assert_eq!(snbt, "{name:\"a\",count:2,tags:[\"x\"]}");
+        assert_eq!(from_snbt::<Value>(&snbt).unwrap(), value);
+    }
+
+}
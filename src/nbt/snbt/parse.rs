@@ -0,0 +1,395 @@
+use super::{
+    SnbtParseError,
+    SnbtParseErrorKind
+};
+use crate::nbt::{
+    NbtElement,
+    NbtCompound,
+    NbtLimits
+};
+use core::str::FromStr;
+use std::borrow::Cow;
+
+
+impl<'l> NbtElement<'l> {
+
+    /// Parses an SNBT string, such as `{key:1b,list:[I;1,2]}`.
+    ///
+    /// Strings which contain no escape sequences borrow from the input.
+    pub fn from_snbt(snbt : &'l str) -> Result<Self, SnbtParseError> {
+        let mut parser  = SnbtParser { src : snbt, pos : 0, depth : 0 };
+        let     element = parser.parse_element()?;
+        parser.skip_whitespace();
+        if (parser.pos < snbt.len()) {
+            return Err(parser.error(SnbtParseErrorKind::TrailingChars));
+        }
+        Ok(element)
+    }
+
+}
+
+impl FromStr for NbtElement<'static> {
+    type Err = SnbtParseError;
+    #[inline]
+    fn from_str(s : &str) -> Result<Self, Self::Err> {
+        NbtElement::from_snbt(s).map(NbtElement::into_owned)
+    }
+}
+
+
+/// Parses an SNBT compound at the start of `src`, returning it and the length of its SNBT.
+///
+/// `src` must start with `{`.
+pub(in crate::nbt) fn parse_compound_prefix(src : &str) -> Result<(NbtCompound<'_>, usize,), SnbtParseError> {
+    let mut parser   = SnbtParser { src, pos : 0, depth : 0 };
+    let     compound = parser.parse_compound()?;
+    Ok((compound, parser.pos,))
+}
+
+/// Parses a quoted SNBT string at the start of `src`, returning it and the length of its SNBT.
+///
+/// `src` must start with `"` or `'`.
+pub(in crate::nbt) fn parse_quoted_prefix(src : &str) -> Result<(Cow<'_, str>, usize,), SnbtParseError> {
+    let mut parser = SnbtParser { src, pos : 0, depth : 0 };
+    let     quote  = parser.peek().ok_or_else(|| parser.error(SnbtParseErrorKind::UnexpectedEnd))?;
+    let     string = parser.parse_quoted(quote)?;
+    Ok((string, parser.pos,))
+}
+
+
+/// Returns `true` if the character may appear in an unquoted SNBT string.
+pub(super) fn is_unquoted_char(ch : char) -> bool {
+    ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.' | '+')
+}
+
+
+/// The maximum nesting depth of lists and compounds, matching [`NbtLimits::NETWORK`].
+const MAX_DEPTH : usize = NbtLimits::NETWORK.max_depth;
+
+
+struct SnbtParser<'l> {
+    src   : &'l str,
+    pos   : usize,
+    depth : usize
+}
+
+impl<'l> SnbtParser<'l> {
+
+    #[inline]
+    fn error(&self, kind : SnbtParseErrorKind) -> SnbtParseError {
+        SnbtParseError { offset : self.pos, kind }
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn unexpected(&self) -> SnbtParseError { match (self.peek()) {
+        Some(ch) => self.error(SnbtParseErrorKind::UnexpectedChar(ch)),
+        None     => self.error(SnbtParseErrorKind::UnexpectedEnd)
+    } }
+
+    /// Enters a list or compound, failing if it is nested too deeply.
+    fn push_depth(&mut self) -> Result<(), SnbtParseError> {
+        if (self.depth >= MAX_DEPTH) { return Err(self.error(SnbtParseErrorKind::TooDeep)); }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(ch) = self.peek() && ch.is_whitespace() {
+            self.pos += ch.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, expected : char) -> Result<(), SnbtParseError> {
+        self.skip_whitespace();
+        if (self.peek() == Some(expected)) {
+            self.pos += expected.len_utf8();
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    /// Consumes a separating comma, returning `true` if the closing character was reached instead.
+    ///
+    /// A trailing comma before the closing character is allowed.
+    fn separator(&mut self, close : char) -> Result<bool, SnbtParseError> {
+        self.skip_whitespace();
+        match (self.peek()) {
+            Some(',') => {
+                self.pos += 1;
+                self.skip_whitespace();
+                if (self.peek() == Some(close)) {
+                    self.pos += 1;
+                    Ok(true)
+                } else { Ok(false) }
+            },
+            Some(ch) if (ch == close) => {
+                self.pos += 1;
+                Ok(true)
+            },
+            _ => Err(self.unexpected())
+        }
+    }
+
+    fn parse_element(&mut self) -> Result<NbtElement<'l>, SnbtParseError> {
+        self.skip_whitespace();
+        match (self.peek()) {
+            Some('{')                  => Ok(NbtElement::Compound(self.parse_compound()?)),
+            Some('[')                  => self.parse_list(),
+            Some(quote @ ('"' | '\'')) => Ok(NbtElement::String(self.parse_quoted(quote)?)),
+            _                          => {
+                let start = self.pos;
+                let token = self.parse_unquoted()?;
+                parse_literal(token).map_err(|kind| SnbtParseError { offset : start, kind })
+            }
+        }
+    }
+
+    fn parse_unquoted(&mut self) -> Result<&'l str, SnbtParseError> {
+        let start = self.pos;
+        while let Some(ch) = self.peek() && is_unquoted_char(ch) {
+            self.pos += 1;
+        }
+        if (self.pos == start) { return Err(self.unexpected()); }
+        Ok(&self.src[start..self.pos])
+    }
+
+    fn parse_quoted(&mut self, quote : char) -> Result<Cow<'l, str>, SnbtParseError> {
+        self.pos += 1;
+        let     src   = self.src;
+        let     start = self.pos;
+        let mut owned = None::<String>;
+        loop {
+            let Some(ch) = self.peek()
+                else { return Err(self.error(SnbtParseErrorKind::UnexpectedEnd)); };
+            if (ch == quote) {
+                let string = match (owned) {
+                    Some(owned) => Cow::Owned(owned),
+                    None        => Cow::Borrowed(&src[start..self.pos])
+                };
+                self.pos += 1;
+                return Ok(string);
+            }
+            if (ch == '\\') {
+                let string = owned.get_or_insert_with(|| src[start..self.pos].to_string());
+                string.push(self.parse_escape()?);
+            } else {
+                if let Some(string) = &mut owned { string.push(ch); }
+                self.pos += ch.len_utf8();
+            }
+        }
+    }
+
+    fn parse_escape(&mut self) -> Result<char, SnbtParseError> {
+        let start = self.pos;
+        self.pos += 1;
+        let Some(ch) = self.peek()
+            else { return Err(self.error(SnbtParseErrorKind::UnexpectedEnd)); };
+        self.pos += ch.len_utf8();
+        let digits = match (ch) {
+            '\\' | '\'' | '"' => { return Ok(ch); },
+            'n'               => { return Ok('\n'); },
+            't'               => { return Ok('\t'); },
+            'r'               => { return Ok('\r'); },
+            'b'               => { return Ok('\u{8}'); },
+            'f'               => { return Ok('\u{C}'); },
+            's'               => { return Ok(' '); },
+            'x'               => 2,
+            'u'               => 4,
+            'U'               => 8,
+            _                 => { return Err(SnbtParseError { offset : start, kind : SnbtParseErrorKind::BadEscape }); }
+        };
+        self.src.get(self.pos..(self.pos + digits))
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|hex| char::from_u32(u32::from_str_radix(hex, 16).ok()?))
+            .inspect(|_| { self.pos += digits; })
+            .ok_or(SnbtParseError { offset : start, kind : SnbtParseErrorKind::BadEscape })
+    }
+
+    fn parse_compound(&mut self) -> Result<NbtCompound<'l>, SnbtParseError> {
+        self.push_depth()?;
+        self.pos += 1;
+        let mut compound = NbtCompound::new();
+        self.skip_whitespace();
+        if (self.peek() == Some('}')) {
+            self.pos += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                let key = match (self.peek()) {
+                    Some(quote @ ('"' | '\'')) => self.parse_quoted(quote)?,
+                    _                          => Cow::Borrowed(self.parse_unquoted()?)
+                };
+                self.expect(':')?;
+                let element = self.parse_element()?;
+                compound.insert(key, element);
+                if (self.separator('}')?) { break; }
+            }
+        }
+        self.depth -= 1;
+        Ok(compound)
+    }
+
+    fn parse_list(&mut self) -> Result<NbtElement<'l>, SnbtParseError> {
+        self.push_depth()?;
+        self.pos += 1;
+        let array_tag = match (self.src.as_bytes().get(self.pos..(self.pos + 2))) {
+            Some([tag @ (b'B' | b'I' | b'L'), b';']) => {
+                self.pos += 2;
+                Some(*tag)
+            },
+            _ => None
+        };
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if (self.peek() == Some(']')) {
+            self.pos += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                let start = self.pos;
+                elements.push((start, self.parse_element()?,));
+                if (self.separator(']')?) { break; }
+            }
+        }
+        self.depth -= 1;
+        let elements = elements.into_iter();
+        let bad      = |offset| SnbtParseError { offset, kind : SnbtParseErrorKind::BadArrayElement };
+        Ok(match (array_tag) {
+            Some(b'B') => NbtElement::BArray(Cow::Owned(elements.map(|(offset, element,)| match (element) {
+                NbtElement::Byte(v) => Ok(v),
+                NbtElement::Int(v)  => i8::try_from(v).map_err(|_| bad(offset)),
+                _                   => Err(bad(offset))
+            }).collect::<Result<_, _>>()?)),
+            Some(b'I') => NbtElement::IArray(Cow::Owned(elements.map(|(offset, element,)| match (element) {
+                NbtElement::Byte(v)  => Ok(v as i32),
+                NbtElement::Short(v) => Ok(v as i32),
+                NbtElement::Int(v)   => Ok(v),
+                _                    => Err(bad(offset))
+            }).collect::<Result<_, _>>()?)),
+            Some(_) => NbtElement::LArray(Cow::Owned(elements.map(|(offset, element,)| match (element) {
+                NbtElement::Byte(v)  => Ok(v as i64),
+                NbtElement::Short(v) => Ok(v as i64),
+                NbtElement::Int(v)   => Ok(v as i64),
+                NbtElement::Long(v)  => Ok(v),
+                _                    => Err(bad(offset))
+            }).collect::<Result<_, _>>()?)),
+            None => NbtElement::List(Cow::Owned(elements.map(|(_, element,)| element).collect()))
+        })
+    }
+
+}
+
+
+/// Interprets an unquoted token as a boolean, number, or string.
+fn parse_literal(token : &str) -> Result<NbtElement<'_>, SnbtParseErrorKind> {
+    match (token) {
+        "true"  => return Ok(NbtElement::Byte(1)),
+        "false" => return Ok(NbtElement::Byte(0)),
+        _       => { }
+    }
+    if let Some(element) = parse_integer(token) { return element; }
+    if let Some(element) = parse_float(token) { return element; }
+    Ok(NbtElement::String(Cow::Borrowed(token)))
+}
+
+/// Interprets an unquoted token as an integer, returning `None` if it does not look like one.
+///
+/// Accepts a sign, a `0x` or `0b` radix prefix, `_` digit separators, and an optional
+///  `s` (signed) or `u` (unsigned) marker before a `b`, `s`, `i` or `l` type suffix.
+fn parse_integer<'l>(token : &str) -> Option<Result<NbtElement<'l>, SnbtParseErrorKind>> {
+    let (negative, rest,) = match (token.as_bytes().first()?) {
+        b'-' => (true, &token[1..],),
+        b'+' => (false, &token[1..],),
+        _    => (false, token,)
+    };
+    let (radix, rest,) = if let Some(rest) = rest.strip_prefix("0x").or_else(|| rest.strip_prefix("0X")) {
+        (16, rest,)
+    } else if let Some(rest) = rest.strip_prefix("0b").or_else(|| rest.strip_prefix("0B"))
+        && rest.starts_with(['0', '1'])
+    {
+        (2, rest,)
+    } else { (10, rest,) };
+
+    // `b` is a hex digit, so hex bytes need a signedness marker.
+    let byte_suffix = (radix != 16) || {
+        let lower = rest.to_ascii_lowercase();
+        lower.ends_with("sb") || lower.ends_with("ub")
+    };
+    let (bits, rest,) = match (rest.as_bytes().last()?.to_ascii_lowercase()) {
+        b'b' if (byte_suffix) => (8, &rest[..(rest.len() - 1)],),
+        b's'                  => (16, &rest[..(rest.len() - 1)],),
+        b'i'                  => (32, &rest[..(rest.len() - 1)],),
+        b'l'                  => (64, &rest[..(rest.len() - 1)],),
+        _                     => (0, rest,)
+    };
+    let (signed, rest,) = match (rest.as_bytes().last().map(u8::to_ascii_lowercase)) {
+        Some(b's') if (bits != 0) => (Some(true), &rest[..(rest.len() - 1)],),
+        Some(b'u') if (bits != 0) => (Some(false), &rest[..(rest.len() - 1)],),
+        _                         => (None, rest,)
+    };
+    let bits = if (bits == 0) { 32 } else { bits };
+
+    let digits = rest.replace('_', "");
+    if (digits.is_empty() || ! digits.chars().all(|ch| ch.is_digit(radix))) { return None; }
+
+    let Ok(magnitude) = u64::from_str_radix(&digits, radix)
+        else { return Some(Err(SnbtParseErrorKind::NumberOutOfRange)); };
+    let value       = if (negative) { -(magnitude as i128) } else { magnitude as i128 };
+    let signed_ok   = (-(1i128 << (bits - 1))..(1i128 << (bits - 1))).contains(&value);
+    let unsigned_ok = (! negative) && (magnitude as i128) < (1i128 << bits);
+    let raw = match (signed) {
+        Some(true)  if (signed_ok)                    => value as i64,
+        Some(false) if (unsigned_ok)                  => magnitude as i64,
+        None        if (signed_ok)                    => value as i64,
+        None        if (radix != 10 && unsigned_ok)   => magnitude as i64,
+        _                                             => { return Some(Err(SnbtParseErrorKind::NumberOutOfRange)); }
+    };
+    Some(Ok(match (bits) {
+        8  => NbtElement::Byte(raw as i8),
+        16 => NbtElement::Short(raw as i16),
+        32 => NbtElement::Int(raw as i32),
+        _  => NbtElement::Long(raw)
+    }))
+}
+
+/// Interprets an unquoted token as a float or double, returning `None` if it does not look like one.
+///
+/// Also accepts the `NaN`, `Infinity` and `-Infinity` forms that vanilla prints non-finite values as, with a type suffix.
+fn parse_float<'l>(token : &str) -> Option<Result<NbtElement<'l>, SnbtParseErrorKind>> {
+    let non_finite = |name : &str| match (name) {
+        "NaN"                     => Some(f64::NAN),
+        "Infinity" | "+Infinity" => Some(f64::INFINITY),
+        "-Infinity"               => Some(f64::NEG_INFINITY),
+        _                         => None
+    };
+    if let Some(name) = token.strip_suffix(['f', 'F']) && let Some(value) = non_finite(name) {
+        return Some(Ok(NbtElement::Float(value as f32)));
+    }
+    if let Some(name) = token.strip_suffix(['d', 'D']) && let Some(value) = non_finite(name) {
+        return Some(Ok(NbtElement::Double(value)));
+    }
+    let (is_float, rest,) = match (token.as_bytes().last()?.to_ascii_lowercase()) {
+        b'f' => (true, &token[..(token.len() - 1)],),
+        b'd' => (false, &token[..(token.len() - 1)],),
+        _ if (token.contains(['.', 'e', 'E'])) => (false, token,),
+        _    => { return None; }
+    };
+    let digits = rest.replace('_', "");
+    if (! (digits.bytes().any(|b| b.is_ascii_digit())
+        && digits.bytes().all(|b| b.is_ascii_digit() || matches!(b, b'+' | b'-' | b'.' | b'e' | b'E'))
+    )) { return None; }
+    let value = digits.parse::<f64>().ok()?;
+    if (is_float) {
+        let value = value as f32;
+        if (value.is_infinite()) { return Some(Err(SnbtParseErrorKind::NumberOutOfRange)); }
+        Some(Ok(NbtElement::Float(value)))
+    } else {
+        if (value.is_infinite()) { return Some(Err(SnbtParseErrorKind::NumberOutOfRange)); }
+        Some(Ok(NbtElement::Double(value)))
+    }
+}
@@ -0,0 +1,136 @@
+use super::parse::is_unquoted_char;
+use crate::nbt::{
+    NbtElement,
+    NbtCompound
+};
+use core::fmt::{ self, Display, Formatter, Write };
+
+
+/// Writes this element as SNBT.
+///
+/// The alternate flag (`{:#}`) spreads compounds and lists over multiple indented lines.
+impl Display for NbtElement<'_> {
+    #[inline]
+    fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result {
+        let indent = f.alternate().then_some(0);
+        write_element(f, self, indent)
+    }
+}
+
+/// Writes this compound as SNBT.
+///
+/// The alternate flag (`{:#}`) spreads the compound over multiple indented lines.
+impl Display for NbtCompound<'_> {
+    #[inline]
+    fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result {
+        let indent = f.alternate().then_some(0);
+        write_compound(f, self, indent)
+    }
+}
+
+
+fn write_element(f : &mut Formatter<'_>, element : &NbtElement<'_>, indent : Option<usize>) -> fmt::Result { match (element) {
+    NbtElement::Byte(v)     => write!(f, "{v}b"),
+    NbtElement::Short(v)    => write!(f, "{v}s"),
+    NbtElement::Int(v)      => write!(f, "{v}"),
+    NbtElement::Long(v)     => write!(f, "{v}L"),
+    NbtElement::Float(v)    => { write_float(f, *v)?; f.write_char('f') },
+    NbtElement::Double(v)   => { write_float(f, *v)?; f.write_char('d') },
+    NbtElement::BArray(v)   => write_array(f, "B", v.iter().map(|v| format!("{v}B")), indent),
+    NbtElement::String(v)   => write_quoted(f, v),
+    NbtElement::List(v)     => write_list(f, v, indent),
+    NbtElement::Compound(v) => write_compound(f, v, indent),
+    NbtElement::IArray(v)   => write_array(f, "I", v.iter(), indent),
+    NbtElement::LArray(v)   => write_array(f, "L", v.iter().map(|v| format!("{v}L")), indent)
+} }
+
+/// Writes a float without its type suffix, with non-finite values in the forms that vanilla prints.
+fn write_float<F>(f : &mut Formatter<'_>, v : F) -> fmt::Result
+where
+    F : Copy + fmt::Debug + Into<f64>
+{
+    let v64 = v.into();
+    if (v64.is_nan()) {
+        f.write_str("NaN")
+    } else if (v64.is_infinite()) {
+        f.write_str(if (v64 > 0.0) { "Infinity" } else { "-Infinity" })
+    } else {
+        write!(f, "{v:?}")
+    }
+}
+
+fn write_array<I>(f : &mut Formatter<'_>, prefix : &str, values : I, indent : Option<usize>) -> fmt::Result
+where
+    I        : Iterator,
+    I::Item : Display
+{
+    write!(f, "[{prefix};")?;
+    for (i, value,) in values.enumerate() {
+        if (i > 0) { f.write_char(',')?; }
+        if (indent.is_some()) { f.write_char(' ')?; }
+        write!(f, "{value}")?;
+    }
+    f.write_char(']')
+}
+
+fn write_list(f : &mut Formatter<'_>, elements : &[NbtElement<'_>], indent : Option<usize>) -> fmt::Result {
+    let nested = elements.iter().any(|element| matches!(element, NbtElement::List(_) | NbtElement::Compound(_)));
+    let indent = indent.filter(|_| nested);
+    f.write_char('[')?;
+    for (i, element,) in elements.iter().enumerate() {
+        if (i > 0) { f.write_char(',')?; }
+        match (indent) {
+            Some(indent) => write_newline(f, indent + 1)?,
+            None if (i > 0 && f.alternate()) => f.write_char(' ')?,
+            None => { }
+        }
+        write_element(f, element, indent.map(|indent| indent + 1))?;
+    }
+    if let Some(indent) = indent && (! elements.is_empty()) {
+        write_newline(f, indent)?;
+    }
+    f.write_char(']')
+}
+
+fn write_compound(f : &mut Formatter<'_>, compound : &NbtCompound<'_>, indent : Option<usize>) -> fmt::Result {
+    f.write_char('{')?;
+    for (i, (key, element,),) in compound.iter().enumerate() {
+        if (i > 0) { f.write_char(',')?; }
+        if let Some(indent) = indent { write_newline(f, indent + 1)?; }
+        if (! key.is_empty() && key.chars().all(is_unquoted_char)) {
+            f.write_str(key)?;
+        } else {
+            write_quoted(f, key)?;
+        }
+        f.write_char(':')?;
+        if (indent.is_some()) { f.write_char(' ')?; }
+        write_element(f, element, indent.map(|indent| indent + 1))?;
+    }
+    if let Some(indent) = indent && (! compound.is_empty()) {
+        write_newline(f, indent)?;
+    }
+    f.write_char('}')
+}
+
+fn write_newline(f : &mut Formatter<'_>, indent : usize) -> fmt::Result {
+    f.write_char('\n')?;
+    for _ in 0..indent { f.write_str("    ")?; }
+    Ok(())
+}
+
+pub(in crate::nbt) fn write_quoted(f : &mut Formatter<'_>, s : &str) -> fmt::Result {
+    let quote = if (s.contains('"') && ! s.contains('\'')) { '\'' } else { '"' };
+    f.write_char(quote)?;
+    for ch in s.chars() { match (ch) {
+        '\\'                  => f.write_str("\\\\")?,
+        '\n'                  => f.write_str("\\n")?,
+        '\t'                  => f.write_str("\\t")?,
+        '\r'                  => f.write_str("\\r")?,
+        '\u{8}'               => f.write_str("\\b")?,
+        '\u{C}'               => f.write_str("\\f")?,
+        _ if (ch == quote)    => { f.write_char('\\')?; f.write_char(ch)?; },
+        _ if (ch.is_control()) => write!(f, "\\x{:0>2X}", ch as u32)?,
+        _                     => f.write_char(ch)?
+    } }
+    f.write_char(quote)
+}
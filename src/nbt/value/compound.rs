@@ -0,0 +1,188 @@
+use super::NbtElement;
+use core::mem;
+use std::borrow::Cow;
+
+
+/// An NBT compound.
+///
+/// Entries are kept in insertion order.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct NbtCompound<'l> {
+    /// Named elements in this compound.
+    pub entries : Cow<'l, [(Cow<'l, str>, NbtElement<'l>,)]>
+}
+
+
+impl<'l> NbtCompound<'l> {
+
+    /// Create a new empty [`NbtCompound`].
+    #[inline]
+    pub const fn new() -> Self {
+        Self { entries : Cow::Borrowed(&[]) }
+    }
+
+    /// Returns the number of entries in this compound.
+    #[inline]
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    /// Returns `true` if this compound has no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    /// Returns an iterator over the entries in this compound.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &NbtElement<'l>,)> {
+        self.entries.iter().map(|(key, element,)| (&**key, element,))
+    }
+
+    /// Returns `true` if this compound has an entry with the given key.
+    #[inline]
+    pub fn contains_key(&self, key : &str) -> bool {
+        self.entries.iter().any(|(k, _,)| k == key)
+    }
+
+    /// Returns a reference to the element with the given key.
+    pub fn get(&self, key : &str) -> Option<&NbtElement<'l>> {
+        self.entries.iter().find(|(k, _,)| k == key).map(|(_, element,)| element)
+    }
+
+    /// Returns a mutable reference to the element with the given key.
+    ///
+    /// Clones the entries if they are not already owned.
+    pub fn get_mut(&mut self, key : &str) -> Option<&mut NbtElement<'l>> {
+        let index = self.entries.iter().position(|(k, _,)| k == key)?;
+        Some(&mut self.entries.to_mut()[index].1)
+    }
+
+    /// Inserts an element into this compound, returning the element previously stored with the same key.
+    ///
+    /// Clones the entries if they are not already owned.
+    pub fn insert<K, V>(&mut self, key : K, element : V) -> Option<NbtElement<'l>>
+    where
+        K : Into<Cow<'l, str>>,
+        V : Into<NbtElement<'l>>
+    {
+        let key     = key.into();
+        let element = element.into();
+        let entries = self.entries.to_mut();
+        if let Some((_, existing,)) = entries.iter_mut().find(|(k, _,)| *k == key) {
+            Some(mem::replace(existing, element))
+        } else {
+            entries.push((key, element,));
+            None
+        }
+    }
+
+    /// Removes the element with the given key from this compound, returning it.
+    ///
+    /// Clones the entries if they are not already owned.
+    pub fn remove(&mut self, key : &str) -> Option<NbtElement<'l>> {
+        let index = self.entries.iter().position(|(k, _,)| k == key)?;
+        Some(self.entries.to_mut().remove(index).1)
+    }
+
+    /// Merges `patch` into this compound, like `/data merge`.
+    ///
+    /// Entries of `patch` replace the entries of this compound with the same key,
+    ///  except that compounds present in both are merged recursively.
+    pub fn merge(&mut self, patch : &NbtCompound<'l>) {
+        for (key, element,) in patch.entries.iter() {
+            if let NbtElement::Compound(patch) = element && let Some(NbtElement::Compound(existing)) = self.get_mut(key) {
+                existing.merge(patch);
+            } else {
+                self.insert(key.clone(), element.clone());
+            }
+        }
+    }
+
+    /// Returns a copy of this compound which does not borrow any data.
+    pub fn into_owned(self) -> NbtCompound<'static> {
+        NbtCompound { entries : Cow::Owned(self.entries.into_owned().into_iter()
+            .map(|(key, element,)| (Cow::Owned(key.into_owned()), element.into_owned(),))
+            .collect()
+        ) }
+    }
+
+}
+
+impl<'l> NbtCompound<'l> {
+
+    /// Creates a compound from decoded entries.
+    ///
+    /// Like vanilla, the last of several entries with the same key wins. Like [`NbtCompound::insert`], it is
+    ///  kept at the position of the first. Duplicates are found by sorting, so that decoding is not quadratic.
+    pub(in crate::nbt) fn from_entries(mut entries : Vec<(Cow<'l, str>, NbtElement<'l>,)>) -> Self {
+        let mut order = (0..entries.len()).collect::<Vec<_>>();
+        // Stable, so entries with the same key stay in input order.
+        order.sort_by(|&a, &b| entries[a].0.cmp(&entries[b].0));
+        let mut removed = vec![false; entries.len()];
+        let mut moves   = Vec::new();
+        for run in order.chunk_by(|&a, &b| entries[a].0 == entries[b].0) {
+            if let [first, .., last] = *run {
+                moves.push((first, last,));
+                for &index in &run[1..] { removed[index] = true; }
+            }
+        }
+        for (first, last,) in moves {
+            entries.swap(first, last);
+        }
+        let mut removed = removed.into_iter();
+        entries.retain(|_| ! removed.next().unwrap_or(false));
+        Self { entries : Cow::Owned(entries) }
+    }
+
+    /// Returns `true` if this compound has a single entry with an empty key.
+    ///
+    /// Vanilla uses compounds of this shape to wrap elements in lists of mixed element types.
+    #[inline]
+    pub(in crate::nbt) fn is_wrapper(&self) -> bool {
+        matches!(&*self.entries, [(key, _,)] if key.is_empty())
+    }
+
+    /// Returns the wrapped element if this compound is a wrapper, or this compound otherwise.
+    pub(in crate::nbt) fn into_wrapped(self) -> Result<NbtElement<'l>, Self> {
+        if (! self.is_wrapper()) { return Err(self); }
+        let mut entries = self.entries.into_owned();
+        entries.pop().map(|(_, element,)| element).ok_or_else(Self::new)
+    }
+
+}
+
+
+impl<'l, K, V> FromIterator<(K, V,)> for NbtCompound<'l>
+where
+    K : Into<Cow<'l, str>>,
+    V : Into<NbtElement<'l>>
+{
+    fn from_iter<I>(iter : I) -> Self
+    where
+        I : IntoIterator<Item = (K, V,)>
+    {
+        let mut compound = Self::new();
+        for (key, element,) in iter {
+            compound.insert(key, element);
+        }
+        compound
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn el(snbt : &str) -> NbtElement<'static> { NbtElement::from_snbt(snbt).unwrap().into_owned() }
+
+    #[test]
+    fn merge() {
+        let mut element = el(r#"{a:1,b:{c:1,d:{e:1,f:1}},list:[1,2,3],g:{h:1},s:"x"}"#);
+        element.merge(&el(r#"{a:2,b:{d:{f:2,i:2},j:2},list:[4],g:5,new:{k:1}}"#));
+        assert_eq!(element, el(r#"{a:2,b:{c:1,d:{e:1,f:2,i:2},j:2},list:[4],g:5,s:"x",new:{k:1}}"#));
+        // Anything other than a compound replaces the element.
+        element.merge(&el("[1]"));
+        assert_eq!(element, el("[1]"));
+        element.merge(&el("{a:1}"));
+        assert_eq!(element, el("{a:1}"));
+    }
+
+}
@@ -0,0 +1,472 @@
+use super::{
+    NbtElement,
+    NbtCompound
+};
+use crate::nbt::{
+    NbtDeserError,
+    IARRAY_TOKEN,
+    LARRAY_TOKEN,
+    ELEMENT_TOKEN
+};
+use core::slice;
+use std::borrow::Cow;
+use serde::de::{
+    DeserializeSeed,
+    Deserializer as Deserer,
+    EnumAccess,
+    Error as DeserError,
+    IntoDeserializer,
+    MapAccess,
+    Unexpected,
+    VariantAccess,
+    Visitor,
+    value::{ BorrowedStrDeserializer, SeqDeserializer }
+};
+
+
+impl<'l> NbtElement<'l> {
+
+    /// Describes this element for type errors.
+    fn unexpected(&self) -> Unexpected<'_> { match (self) {
+        Self::Byte(v)     => Unexpected::Signed(*v as i64),
+        Self::Short(v)    => Unexpected::Signed(*v as i64),
+        Self::Int(v)      => Unexpected::Signed(*v as i64),
+        Self::Long(v)     => Unexpected::Signed(*v),
+        Self::Float(v)    => Unexpected::Float(*v as f64),
+        Self::Double(v)   => Unexpected::Float(*v),
+        Self::BArray(_)   => Unexpected::Other("NBT byte array"),
+        Self::String(v)   => Unexpected::Str(v),
+        Self::List(_)     => Unexpected::Seq,
+        Self::Compound(_) => Unexpected::Map,
+        Self::IArray(_)   => Unexpected::Other("NBT int array"),
+        Self::LArray(_)   => Unexpected::Other("NBT long array")
+    } }
+
+    /// Returns the value of this element if it is a byte, short, int, or long.
+    #[inline]
+    fn integer(&self) -> Option<i64> { match (self) {
+        Self::Byte(v)  => Some(*v as i64),
+        Self::Short(v) => Some(*v as i64),
+        Self::Int(v)   => Some(*v as i64),
+        Self::Long(v)  => Some(*v),
+        _              => None
+    } }
+
+    /// Reads an integer of type `T` from any integer element holding a value in range.
+    fn integer_as<'de, T, V>(&self, visitor : &V) -> Result<T, NbtDeserError>
+    where
+        T : TryFrom<i64>,
+        V : Visitor<'de>
+    {
+        let v = self.integer().ok_or_else(|| NbtDeserError::invalid_type(self.unexpected(), visitor))?;
+        T::try_from(v).map_err(|_| NbtDeserError::invalid_value(Unexpected::Signed(v), visitor))
+    }
+
+    /// Reads a float from any number element.
+    fn float<'de, V>(&self, visitor : &V) -> Result<f64, NbtDeserError>
+    where
+        V : Visitor<'de>
+    { match (self) {
+        Self::Float(v)  => Ok(*v as f64),
+        Self::Double(v) => Ok(*v),
+        _               => self.integer().map(|v| v as f64).ok_or_else(|| NbtDeserError::invalid_type(self.unexpected(), visitor))
+    } }
+
+}
+
+
+impl<'de, 'l> IntoDeserializer<'de, NbtDeserError> for &'de NbtElement<'l> {
+    type Deserializer = Self;
+
+    #[inline]
+    fn into_deserializer(self) -> Self::Deserializer { self }
+}
+
+impl<'de, 'l> Deserer<'de> for &'de NbtElement<'l> {
+    type Error = NbtDeserError;
+
+    fn deserialize_any<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { match (self) {
+        NbtElement::Byte(v)     => visitor.visit_i8(*v),
+        NbtElement::Short(v)    => visitor.visit_i16(*v),
+        NbtElement::Int(v)      => visitor.visit_i32(*v),
+        NbtElement::Long(v)     => visitor.visit_i64(*v),
+        NbtElement::Float(v)    => visitor.visit_f32(*v),
+        NbtElement::Double(v)   => visitor.visit_f64(*v),
+        NbtElement::BArray(v)   => visitor.visit_borrowed_bytes(barray_bytes(v)),
+        NbtElement::String(v)   => visitor.visit_borrowed_str(v),
+        NbtElement::List(v)     => visit_seq(v.iter(), visitor),
+        NbtElement::Compound(v) => visitor.visit_map(NbtCompoundMapAccess::new(v)),
+        NbtElement::IArray(v)   => visit_seq(v.iter().copied(), visitor),
+        NbtElement::LArray(v)   => visit_seq(v.iter().copied(), visitor)
+    } }
+
+    fn deserialize_bool<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    {
+        let v = self.integer().ok_or_else(|| NbtDeserError::invalid_type(self.unexpected(), &visitor))?;
+        visitor.visit_bool(v != 0)
+    }
+
+    #[inline]
+    fn deserialize_i8<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    {
+        let v = self.integer_as(&visitor)?;
+        visitor.visit_i8(v)
+    }
+
+    #[inline]
+    fn deserialize_i16<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    {
+        let v = self.integer_as(&visitor)?;
+        visitor.visit_i16(v)
+    }
+
+    #[inline]
+    fn deserialize_i32<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    {
+        let v = self.integer_as(&visitor)?;
+        visitor.visit_i32(v)
+    }
+
+    #[inline]
+    fn deserialize_i64<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    {
+        let v = self.integer_as(&visitor)?;
+        visitor.visit_i64(v)
+    }
+
+    // Unsigned integers are serialised as the signed element of the same width,
+    //  so that element is read back as a bit-cast.
+
+    #[inline]
+    fn deserialize_u8<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { match (self) {
+        NbtElement::Byte(v) => visitor.visit_u8(v.cast_unsigned()),
+        _                   => { let v = self.integer_as(&visitor)?; visitor.visit_u8(v) }
+    } }
+
+    #[inline]
+    fn deserialize_u16<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { match (self) {
+        NbtElement::Short(v) => visitor.visit_u16(v.cast_unsigned()),
+        _                    => { let v = self.integer_as(&visitor)?; visitor.visit_u16(v) }
+    } }
+
+    #[inline]
+    fn deserialize_u32<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { match (self) {
+        NbtElement::Int(v) => visitor.visit_u32(v.cast_unsigned()),
+        _                  => { let v = self.integer_as(&visitor)?; visitor.visit_u32(v) }
+    } }
+
+    #[inline]
+    fn deserialize_u64<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { match (self) {
+        NbtElement::Long(v) => visitor.visit_u64(v.cast_unsigned()),
+        _                   => { let v = self.integer_as(&visitor)?; visitor.visit_u64(v) }
+    } }
+
+    #[inline]
+    fn deserialize_f32<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    {
+        let v = self.float(&visitor)? as f32;
+        visitor.visit_f32(v)
+    }
+
+    #[inline]
+    fn deserialize_f64<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    {
+        let v = self.float(&visitor)?;
+        visitor.visit_f64(v)
+    }
+
+    fn deserialize_char<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    {
+        if let NbtElement::String(v) = self {
+            let mut chars = v.chars();
+            if let (Some(ch), None,) = (chars.next(), chars.next(),) {
+                return visitor.visit_char(ch);
+            }
+        }
+        Err(NbtDeserError::invalid_type(self.unexpected(), &visitor))
+    }
+
+    fn deserialize_str<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { match (self) {
+        NbtElement::String(v) => visitor.visit_borrowed_str(v),
+        _                     => Err(NbtDeserError::invalid_type(self.unexpected(), &visitor))
+    } }
+
+    #[inline]
+    fn deserialize_string<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { self.deserialize_str(visitor) }
+
+    fn deserialize_bytes<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { match (self) {
+        NbtElement::BArray(v) => visitor.visit_borrowed_bytes(barray_bytes(v)),
+        _                     => self.deserialize_seq(visitor)
+    } }
+
+    #[inline]
+    fn deserialize_byte_buf<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { self.deserialize_bytes(visitor) }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { visitor.visit_some(self) }
+
+    #[inline]
+    fn deserialize_unit<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { visitor.visit_unit() }
+
+    #[inline]
+    fn deserialize_unit_struct<V>(self, _name : &'static str, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { visitor.visit_unit() }
+
+    fn deserialize_newtype_struct<V>(self, name : &'static str, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { match (self) {
+        // Int and long arrays are only presented as marker maps to `NbtElement`, matching the NBT deserialisers.
+        NbtElement::IArray(v) if (name == ELEMENT_TOKEN) => visitor.visit_map(NbtArrayMapAccess { token : Some(IARRAY_TOKEN), values : v }),
+        NbtElement::LArray(v) if (name == ELEMENT_TOKEN) => visitor.visit_map(NbtArrayMapAccess { token : Some(LARRAY_TOKEN), values : v }),
+        _ if (name == ELEMENT_TOKEN)                     => self.deserialize_any(visitor),
+        _                                                => visitor.visit_newtype_struct(self)
+    } }
+
+    fn deserialize_seq<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { match (self) {
+        NbtElement::List(v)   => visit_seq(v.iter(), visitor),
+        NbtElement::BArray(v) => visit_seq(v.iter().copied(), visitor),
+        NbtElement::IArray(v) => visit_seq(v.iter().copied(), visitor),
+        NbtElement::LArray(v) => visit_seq(v.iter().copied(), visitor),
+        _                     => Err(NbtDeserError::invalid_type(self.unexpected(), &visitor))
+    } }
+
+    #[inline]
+    fn deserialize_tuple<V>(self, _len : usize, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { self.deserialize_seq(visitor) }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(self, _name : &'static str, _len : usize, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { self.deserialize_seq(visitor) }
+
+    fn deserialize_map<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { match (self) {
+        NbtElement::Compound(v) => visitor.visit_map(NbtCompoundMapAccess::new(v)),
+        _                       => Err(NbtDeserError::invalid_type(self.unexpected(), &visitor))
+    } }
+
+    #[inline]
+    fn deserialize_struct<V>(self, _name : &'static str, _fields : &'static [&'static str], visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { self.deserialize_map(visitor) }
+
+    fn deserialize_enum<V>(self, _name : &'static str, _variants : &'static [&'static str], visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { match (self) {
+        NbtElement::String(v) => visitor.visit_enum(BorrowedStrDeserializer::<NbtDeserError>::new(v)),
+        NbtElement::Compound(v) => {
+            let [(variant, value,)] = &*v.entries
+                else { return Err(NbtDeserError::BadVariant); };
+            visitor.visit_enum(NbtElementVariantAccess { variant, value })
+        },
+        _ => Err(NbtDeserError::invalid_type(self.unexpected(), &visitor))
+    } }
+
+    #[inline]
+    fn deserialize_identifier<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { self.deserialize_str(visitor) }
+
+    #[inline]
+    fn deserialize_ignored_any<V>(self, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { visitor.visit_unit() }
+
+}
+
+
+/// Views the elements of a byte array as bytes.
+#[inline]
+fn barray_bytes(v : &[i8]) -> &[u8] {
+    // SAFETY: `i8` and `u8` have the same size and alignment.
+    unsafe { slice::from_raw_parts(v.as_ptr().cast::<u8>(), v.len()) }
+}
+
+fn visit_seq<'de, I, V>(iter : I, visitor : V) -> Result<V::Value, NbtDeserError>
+where
+    I : Iterator,
+    I::Item : IntoDeserializer<'de, NbtDeserError>,
+    V : Visitor<'de>
+{
+    let mut seq   = SeqDeserializer::new(iter);
+    let     value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+
+/// Presents the entries of a compound, borrowing its keys.
+struct NbtCompoundMapAccess<'de, 'l> {
+    entries : slice::Iter<'de, (Cow<'l, str>, NbtElement<'l>,)>,
+    value   : Option<&'de NbtElement<'l>>
+}
+
+impl<'de, 'l> NbtCompoundMapAccess<'de, 'l> { fn new(compound : &'de NbtCompound<'l>) -> Self { Self {
+    entries : compound.entries.iter(), value : None
+} } }
+
+impl<'de, 'l> MapAccess<'de> for NbtCompoundMapAccess<'de, 'l> {
+    type Error = NbtDeserError;
+
+    fn next_key_seed<K>(&mut self, seed : K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K : DeserializeSeed<'de>
+    {
+        let Some((key, value,)) = self.entries.next()
+            else { return Ok(None); };
+        self.value = Some(value);
+        seed.deserialize(BorrowedStrDeserializer::<NbtDeserError>::new(key)).map(Some)
+    }
+
+    #[inline]
+    fn next_value_seed<V>(&mut self, seed : V) -> Result<V::Value, Self::Error>
+    where
+        V : DeserializeSeed<'de>
+    {
+        let value = self.value.take().ok_or_else(|| NbtDeserError::custom("NBT compound value requested before its key"))?;
+        seed.deserialize(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> { Some(self.entries.len()) }
+
+}
+
+
+/// Presents an int or long array to [`NbtElement`] as a single entry map,
+///  keyed by the array's marker token, matching the NBT deserialisers.
+struct NbtArrayMapAccess<'de, T> {
+    token  : Option<&'static str>,
+    values : &'de [T]
+}
+
+impl<'de, T> MapAccess<'de> for NbtArrayMapAccess<'de, T>
+where
+    T : Copy + IntoDeserializer<'de, NbtDeserError>
+{
+    type Error = NbtDeserError;
+
+    #[inline]
+    fn next_key_seed<K>(&mut self, seed : K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K : DeserializeSeed<'de>
+    { self.token.take().map(|token| seed.deserialize(BorrowedStrDeserializer::<NbtDeserError>::new(token))).transpose() }
+
+    #[inline]
+    fn next_value_seed<V>(&mut self, seed : V) -> Result<V::Value, Self::Error>
+    where
+        V : DeserializeSeed<'de>
+    { seed.deserialize(SeqDeserializer::new(self.values.iter().copied())) }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> { Some(if (self.token.is_some()) { 1 } else { 0 }) }
+
+}
+
+
+/// Presents a single entry compound as an enum variant.
+struct NbtElementVariantAccess<'de, 'l> {
+    variant : &'de str,
+    value   : &'de NbtElement<'l>
+}
+
+impl<'de, 'l> EnumAccess<'de> for NbtElementVariantAccess<'de, 'l> {
+    type Error   = NbtDeserError;
+    type Variant = Self;
+
+    #[inline]
+    fn variant_seed<V>(self, seed : V) -> Result<(V::Value, Self::Variant,), Self::Error>
+    where
+        V : DeserializeSeed<'de>
+    { Ok((seed.deserialize(BorrowedStrDeserializer::<NbtDeserError>::new(self.variant))?, self,)) }
+}
+
+impl<'de, 'l> VariantAccess<'de> for NbtElementVariantAccess<'de, 'l> {
+    type Error = NbtDeserError;
+
+    #[inline]
+    fn unit_variant(self) -> Result<(), Self::Error> { Ok(()) }
+
+    #[inline]
+    fn newtype_variant_seed<T>(self, seed : T) -> Result<T::Value, Self::Error>
+    where
+        T : DeserializeSeed<'de>
+    { seed.deserialize(self.value) }
+
+    #[inline]
+    fn tuple_variant<V>(self, _len : usize, visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { self.value.deserialize_seq(visitor) }
+
+    #[inline]
+    fn struct_variant<V>(self, _fields : &'static [&'static str], visitor : V) -> Result<V::Value, Self::Error>
+    where
+        V : Visitor<'de>
+    { self.value.deserialize_map(visitor) }
+
+}
@@ -0,0 +1,136 @@
+use super::NbtElement;
+use crate::nbt::{
+    NbtPath,
+    NbtPathNode
+};
+use core::fmt::{ self, Display, Formatter };
+
+
+/// A difference between two NBT elements, found by [`NbtElement::diff`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct NbtDiff<'k, 'l> {
+    /// The path to the element which differs.
+    pub path   : NbtPath,
+    /// How the element differs.
+    pub change : NbtChange<'k, 'l>
+}
+
+/// How an element differs in an [`NbtDiff`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum NbtChange<'k, 'l> {
+    /// The element is only present in the new tree.
+    Added(&'k NbtElement<'l>),
+    /// The element is only present in the old tree.
+    Removed(&'k NbtElement<'l>),
+    /// The element is present in both trees, with differing values.
+    Changed {
+        /// The element in the old tree.
+        from : &'k NbtElement<'l>,
+        /// The element in the new tree.
+        to   : &'k NbtElement<'l>
+    }
+}
+
+
+impl<'l> NbtElement<'l> {
+
+    /// Returns the differences between this element and `new`, outermost first.
+    ///
+    /// Compounds are compared entry by entry, and lists element by element.
+    /// Extra list elements at the end of either list are reported as added or removed.
+    /// Any other elements, including arrays, are compared as a whole.
+    pub fn diff<'k>(&'k self, new : &'k NbtElement<'l>) -> Vec<NbtDiff<'k, 'l>> {
+        let mut diffs = Vec::new();
+        diff(&mut Vec::new(), self, new, &mut diffs);
+        diffs
+    }
+
+}
+
+fn diff<'k, 'l>(path : &mut Vec<NbtPathNode>, old : &'k NbtElement<'l>, new : &'k NbtElement<'l>, diffs : &mut Vec<NbtDiff<'k, 'l>>) {
+    match (old, new,) {
+        (NbtElement::Compound(old), NbtElement::Compound(new),) => {
+            for (key, old_element,) in old.iter() {
+                path.push(NbtPathNode::Key(key.to_string()));
+                match (new.get(key)) {
+                    Some(new_element) => diff(path, old_element, new_element, diffs),
+                    None              => record(path, NbtChange::Removed(old_element), diffs)
+                }
+                path.pop();
+            }
+            for (key, new_element,) in new.iter() {
+                if (! old.contains_key(key)) {
+                    path.push(NbtPathNode::Key(key.to_string()));
+                    record(path, NbtChange::Added(new_element), diffs);
+                    path.pop();
+                }
+            }
+        },
+        (NbtElement::List(old), NbtElement::List(new),) => {
+            for i in 0..old.len().max(new.len()) {
+                path.push(NbtPathNode::Index(i as i32));
+                match (old.get(i), new.get(i),) {
+                    (Some(old_element), Some(new_element),) => diff(path, old_element, new_element, diffs),
+                    (Some(old_element), None,)              => record(path, NbtChange::Removed(old_element), diffs),
+                    (None, Some(new_element),)              => record(path, NbtChange::Added(new_element), diffs),
+                    (None, None,)                           => { }
+                }
+                path.pop();
+            }
+        },
+        _ => if (old != new) { record(path, NbtChange::Changed { from : old, to : new }, diffs); }
+    }
+}
+
+#[inline]
+fn record<'k, 'l>(path : &[NbtPathNode], change : NbtChange<'k, 'l>, diffs : &mut Vec<NbtDiff<'k, 'l>>) {
+    diffs.push(NbtDiff { path : NbtPath { nodes : path.to_vec() }, change });
+}
+
+
+impl Display for NbtDiff<'_, '_> {
+    fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result {
+        let path = &self.path;
+        match (self.change) {
+            NbtChange::Added(element)       => write!(f, "+ {path}: {element}"),
+            NbtChange::Removed(element)     => write!(f, "- {path}: {element}"),
+            NbtChange::Changed { from, to } => write!(f, "~ {path}: {from} -> {to}")
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn el(snbt : &str) -> NbtElement<'static> { NbtElement::from_snbt(snbt).unwrap().into_owned() }
+
+    #[test]
+    fn diff() {
+        let old = el(r#"{same:1,changed:1b,removed:"x",nested:{a:1,b:2},list:[1,2,3],short:[1],arr:[I;1,2]}"#);
+        let new = el(r#"{same:1,changed:2b,nested:{a:1,b:3,c:4},list:[1,5],short:[1,2],arr:[I;1,3],added:[]}"#);
+        let diffs = old.diff(&new).iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(diffs, [
+            "~ changed: 1b -> 2b",
+            "- removed: \"x\"",
+            "~ nested.b: 2 -> 3",
+            "+ nested.c: 4",
+            "~ list[1]: 2 -> 5",
+            "- list[2]: 3",
+            "+ short[1]: 2",
+            "~ arr: [I;1,2] -> [I;1,3]",
+            "+ added: []"
+        ]);
+        let diffs = old.diff(&new);
+        assert_eq!(diffs[1].path, "removed".parse().unwrap());
+        assert_eq!(diffs[1].change, NbtChange::Removed(&el(r#""x""#)));
+        assert_eq!(diffs[3].change, NbtChange::Added(&el("4")));
+        assert_eq!(diffs[4].path, "list[1]".parse().unwrap());
+        assert_eq!(diffs[4].change, NbtChange::Changed { from : &el("2"), to : &el("5") });
+        assert!(old.diff(&old).is_empty());
+        // Elements of different types are changed as a whole.
+        assert_eq!(el("{a:[1]}").diff(&el("{a:{}}")).iter().map(ToString::to_string).collect::<Vec<_>>(), ["~ a: [1] -> {}"]);
+    }
+
+}
@@ -1,20 +1,76 @@
 use crate::Minecraft;
-use super::tag;
-use std::borrow::Cow;
-use netzer::prelude::*;
-use cesu8::{
-    to_java_cesu8,
-    from_java_cesu8
+use super::{
+    NbtSerError,
+    NbtDeserError,
+    NbtErrorSegment,
+    NbtLimits,
+    NbtAccounter,
+    string_len,
+    tag
 };
+use std::{
+    borrow::Cow,
+    io
+};
+use netzer::prelude::*;
 use serde::{
     Serialize as Ser,
     Deserialize as Deser
 };
+use cesu8::{
+    Cesu8DecodingError,
+    to_java_cesu8,
+    from_java_cesu8
+};
+
+
+mod compound;
+pub use compound::*;
+
+mod ser;
+
+mod serer;
+use serer::NbtElementSerer;
+
+mod deserer;
+
+mod diff;
+pub use diff::*;
+
+
+/// Serialise a value as an [`NbtElement`].
+///
+/// Produces the same element as decoding the output of [`to_network`](super::to_network),
+///  and fails where it fails:
+///  - `bool`s become bytes holding `0` or `1`.
+///  - Unsigned integers become the signed element of the same width, keeping their bits.
+///  - Sequences become lists. Their elements must all have the same type, except for the elements of
+///    [`NbtElement::List`]s, which are wrapped in compounds as they are when encoded.
+///  - Unit enum variants become strings, and other variants become single entry compounds keyed by the variant name.
+///  - `None` and `()` entries of compounds are skipped. Anywhere else they are an error, including at the root.
+///
+/// Deserialising an [`NbtElement`] from another format follows the same rules.
+pub fn to_element<T>(value : &T) -> Result<NbtElement<'static>, NbtSerError>
+where
+    T : ?Sized + Ser
+{ value.serialize(NbtElementSerer)?.ok_or(NbtSerError::MissingValue) }
+
+/// Deserialise a value from an [`NbtElement`], borrowing strings and byte arrays where possible.
+///
+/// Accepts everything that [`from_network`](super::from_network) accepts after encoding the element, and more:
+///  - `bool`s are read from any integer element, and are `true` if it is non-zero.
+///  - Integers are read from any integer element holding a value in range.
+///    Unsigned integers are also read from the signed element of the same width as a bit-cast, mirroring [`to_element`].
+///  - Floats are read from any number element.
+///  - Sequences are read from lists and arrays. Self-describing visitors see int and long arrays as they would from the NBT deserialisers.
+pub fn from_element<'de, 'l, T>(element : &'de NbtElement<'l>) -> Result<T, NbtDeserError>
+where
+    T : Deser<'de>
+{ T::deserialize(element) }
 
 
 /// An NBT element.
-#[derive(Clone, Debug, Ser, Deser)]
-#[serde(untagged)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum NbtElement<'l> {
     /// Byte
     Byte(i8),
@@ -28,59 +84,226 @@ pub enum NbtElement<'l> {
     Float(f32),
     /// Double
     Double(f64),
-    // /// Byte array
-    // BArray(NbtBArray<'l>),
+    /// Byte array
+    BArray(Cow<'l, [i8]>),
     /// String
     String(Cow<'l, str>),
-    // /// List
-    // List(NbtList<'l>),
-    // /// Compound
-    // Compound(Cow<'l, [(Cow<'l, str>, NbtElement<'l>,)]>),
-    // /// Int array
-    // IArray(NbtIArray<'l>),
-    // /// Long array
-    // LArray(NbtLArray<'l>)
+    /// List
+    ///
+    /// Lists containing elements of differing types are written as lists of compounds,
+    ///  with each element wrapped in a compound under an empty key, matching vanilla.
+    List(Cow<'l, [NbtElement<'l>]>),
+    /// Compound
+    Compound(NbtCompound<'l>),
+    /// Int array
+    IArray(Cow<'l, [i32]>),
+    /// Long array
+    LArray(Cow<'l, [i64]>)
+}
+
+
+impl NbtElement<'_> {
+
+    /// Returns the NBT tag of this element.
+    #[inline]
+    pub(in crate::nbt) const fn tag(&self) -> u8 { match (self) {
+        Self::Byte(_)     => tag::BYTE,
+        Self::Short(_)    => tag::SHORT,
+        Self::Int(_)      => tag::INT,
+        Self::Long(_)     => tag::LONG,
+        Self::Float(_)    => tag::FLOAT,
+        Self::Double(_)   => tag::DOUBLE,
+        Self::BArray(_)   => tag::BARRAY,
+        Self::String(_)   => tag::STRING,
+        Self::List(_)     => tag::LIST,
+        Self::Compound(_) => tag::COMPOUND,
+        Self::IArray(_)   => tag::IARRAY,
+        Self::LArray(_)   => tag::LARRAY
+    } }
+
+    /// Returns a copy of this element which does not borrow any data.
+    pub fn into_owned(self) -> NbtElement<'static> { match (self) {
+        Self::Byte(v)     => NbtElement::Byte(v),
+        Self::Short(v)    => NbtElement::Short(v),
+        Self::Int(v)      => NbtElement::Int(v),
+        Self::Long(v)     => NbtElement::Long(v),
+        Self::Float(v)    => NbtElement::Float(v),
+        Self::Double(v)   => NbtElement::Double(v),
+        Self::BArray(v)   => NbtElement::BArray(Cow::Owned(v.into_owned())),
+        Self::String(v)   => NbtElement::String(Cow::Owned(v.into_owned())),
+        Self::List(v)     => NbtElement::List(Cow::Owned(v.into_owned().into_iter().map(NbtElement::into_owned).collect())),
+        Self::Compound(v) => NbtElement::Compound(v.into_owned()),
+        Self::IArray(v)   => NbtElement::IArray(Cow::Owned(v.into_owned())),
+        Self::LArray(v)   => NbtElement::LArray(Cow::Owned(v.into_owned()))
+    } }
+
+}
+
+impl<'l> NbtElement<'l> {
+
+    /// Merges `patch` into this element, like `/data merge`.
+    ///
+    /// Compounds are merged recursively with [`NbtCompound::merge`]. Any other element is replaced by `patch`.
+    pub fn merge(&mut self, patch : &NbtElement<'l>) {
+        if let (Self::Compound(compound), Self::Compound(patch),) = (&mut *self, patch) {
+            compound.merge(patch);
+        } else {
+            *self = patch.clone();
+        }
+    }
+
+}
+
+impl<'l> NbtElement<'l> {
+
+    /// Returns the tag that the elements of a list are written with.
+    ///
+    /// Lists of differing element types are written as lists of compounds.
+    pub(in crate::nbt) fn list_tag(elements : &[NbtElement<'l>]) -> u8 {
+        let Some(first) = elements.first()
+            else { return tag::END; };
+        let tag = first.tag();
+        if (elements.iter().all(|element| element.tag() == tag)) { tag } else { tag::COMPOUND }
+    }
+
+    /// Wraps an element of a mixed list in a compound under an empty key.
+    ///
+    /// Compounds are left as-is, unless they would be mistaken for a wrapper.
+    pub(in crate::nbt) fn wrap_mixed<'k>(element : &'k NbtElement<'l>) -> Cow<'k, NbtCompound<'l>> {
+        if let Self::Compound(compound) = element && (! compound.is_wrapper()) {
+            Cow::Borrowed(compound)
+        } else {
+            Cow::Owned(NbtCompound { entries : Cow::Owned(vec![(Cow::Borrowed(""), element.clone(),)]) })
+        }
+    }
+
+    /// Unwraps an element of a compound list that was wrapped by [`NbtElement::wrap_mixed`].
+    #[inline]
+    pub(in crate::nbt) fn unwrap_mixed(compound : NbtCompound<'l>) -> Self {
+        compound.into_wrapped().unwrap_or_else(Self::Compound)
+    }
+
+}
+
+
+impl<'l> From<NbtCompound<'l>> for NbtElement<'l> {
+    #[inline]
+    fn from(value : NbtCompound<'l>) -> Self { Self::Compound(value) }
+}
+impl From<i8> for NbtElement<'_> {
+    #[inline]
+    fn from(value : i8) -> Self { Self::Byte(value) }
+}
+impl From<bool> for NbtElement<'_> {
+    #[inline]
+    fn from(value : bool) -> Self { Self::Byte(value as i8) }
+}
+impl From<i16> for NbtElement<'_> {
+    #[inline]
+    fn from(value : i16) -> Self { Self::Short(value) }
+}
+impl From<i32> for NbtElement<'_> {
+    #[inline]
+    fn from(value : i32) -> Self { Self::Int(value) }
+}
+impl From<i64> for NbtElement<'_> {
+    #[inline]
+    fn from(value : i64) -> Self { Self::Long(value) }
+}
+impl From<f32> for NbtElement<'_> {
+    #[inline]
+    fn from(value : f32) -> Self { Self::Float(value) }
+}
+impl From<f64> for NbtElement<'_> {
+    #[inline]
+    fn from(value : f64) -> Self { Self::Double(value) }
+}
+impl<'l> From<&'l str> for NbtElement<'l> {
+    #[inline]
+    fn from(value : &'l str) -> Self { Self::String(Cow::Borrowed(value)) }
 }
+impl From<String> for NbtElement<'_> {
+    #[inline]
+    fn from(value : String) -> Self { Self::String(Cow::Owned(value)) }
+}
+
 
 impl NetEncode<Minecraft> for NbtElement<'_> {
     async fn encode<W : netzer::AsyncWrite>(&self, mut w : W) -> netzer::Result {
+        w.write_all(&[self.tag()]).await?;
+        self.encode_payload(&mut w).await
+    }
+}
+impl NbtElement<'_> {
+    async fn encode_payload<W : netzer::AsyncWrite>(&self, w : &mut W) -> netzer::Result {
         match (self) {
             NbtElement::Byte(v) => {
-                w.write_all(&[tag::BYTE]).await?;
                 w.write_all(&v.to_be_bytes()).await?;
             },
             NbtElement::Short(v) => {
-                w.write_all(&[tag::SHORT]).await?;
                 w.write_all(&v.to_be_bytes()).await?;
             },
             NbtElement::Int(v) => {
-                w.write_all(&[tag::INT]).await?;
                 w.write_all(&v.to_be_bytes()).await?;
             },
             NbtElement::Long(v) => {
-                w.write_all(&[tag::LONG]).await?;
                 w.write_all(&v.to_be_bytes()).await?;
             },
             NbtElement::Float(v) => {
-                w.write_all(&[tag::FLOAT]).await?;
                 w.write_all(&v.to_be_bytes()).await?;
             },
             NbtElement::Double(v) => {
-                w.write_all(&[tag::DOUBLE]).await?;
                 w.write_all(&v.to_be_bytes()).await?;
             },
+            NbtElement::BArray(v) => {
+                Self::encode_len(v.len(), &mut *w).await?;
+                w.write_all(&v.iter().flat_map(|x| x.to_be_bytes()).collect::<Vec<_>>()).await?;
+            },
             NbtElement::String(v) => {
-                w.write_all(&[tag::STRING]).await?;
                 Self::encode_string(v, w).await?;
             },
+            NbtElement::List(v) => {
+                let tag = Self::list_tag(v);
+                w.write_all(&[tag]).await?;
+                Self::encode_len(v.len(), &mut *w).await?;
+                for element in &**v {
+                    if (tag == tag::COMPOUND) {
+                        Box::pin(Self::encode_compound(&Self::wrap_mixed(element), w)).await?;
+                    } else {
+                        Box::pin(element.encode_payload(w)).await?;
+                    }
+                }
+            },
+            NbtElement::Compound(v) => {
+                Self::encode_compound(v, w).await?;
+            },
+            NbtElement::IArray(v) => {
+                Self::encode_len(v.len(), &mut *w).await?;
+                w.write_all(&v.iter().flat_map(|x| x.to_be_bytes()).collect::<Vec<_>>()).await?;
+            },
+            NbtElement::LArray(v) => {
+                Self::encode_len(v.len(), &mut *w).await?;
+                w.write_all(&v.iter().flat_map(|x| x.to_be_bytes()).collect::<Vec<_>>()).await?;
+            }
         }
         Ok(())
     }
-}
-impl NbtElement<'_> {
+    async fn encode_compound<W : netzer::AsyncWrite>(v : &NbtCompound<'_>, w : &mut W) -> netzer::Result {
+        for (key, element,) in v.iter() {
+            w.write_all(&[element.tag()]).await?;
+            Self::encode_string(key, &mut *w).await?;
+            Box::pin(element.encode_payload(w)).await?;
+        }
+        w.write_all(&[tag::END]).await?;
+        Ok(())
+    }
+    async fn encode_len<W : netzer::AsyncWrite>(len : usize, mut w : W) -> netzer::Result {
+        w.write_all(&i32::try_from(len)?.to_be_bytes()).await?;
+        Ok(())
+    }
     async fn encode_string<W : netzer::AsyncWrite>(v : &str, mut w : W) -> netzer::Result {
         let jstring = to_java_cesu8(v);
-        w.write_all(&u16::try_from(jstring.len()).unwrap().to_be_bytes()).await?;
+        w.write_all(&string_len(&jstring)?.to_be_bytes()).await?;
         w.write_all(&jstring).await?;
         Ok(())
     }
@@ -88,36 +311,117 @@ impl NbtElement<'_> {
 
 
 impl NetDecode<Minecraft> for NbtElement<'_> {
-    async fn decode<R : netzer::AsyncRead>(mut r : R) -> netzer::Result<Self> {
-        let mut tag = [0u8; 1];
-        r.read_exact(&mut tag).await?;
-        let tag = tag[0];
-        Ok(match (tag) {
-            tag::BYTE   => NbtElement::Byte(Self::decode_primitive(r, i8::from_be_bytes).await?),
-            tag::SHORT  => NbtElement::Short(Self::decode_primitive(r, i16::from_be_bytes).await?),
-            tag::INT    => NbtElement::Int(Self::decode_primitive(r, i32::from_be_bytes).await?),
-            tag::LONG   => NbtElement::Long(Self::decode_primitive(r, i64::from_be_bytes).await?),
-            tag::FLOAT  => NbtElement::Float(Self::decode_primitive(r, f32::from_be_bytes).await?),
-            tag::DOUBLE => NbtElement::Double(Self::decode_primitive(r, f64::from_be_bytes).await?),
-            tag::STRING => NbtElement::String(Cow::Owned(Self::decode_string(r).await?)),
-            _ => { return Err("invalid NBT tag".into()); }
-        })
+    #[inline]
+    async fn decode<R : netzer::AsyncRead>(r : R) -> netzer::Result<Self> {
+        Self::decode_with_limits(r, NbtLimits::NETWORK).await
     }
 }
 impl NbtElement<'_> {
-    async fn decode_primitive<R : netzer::AsyncRead, T, const N : usize, F>(mut r : R, f : F) -> netzer::Result<T>
+
+    /// Decodes a network NBT element, within the given limits.
+    ///
+    /// [`NetDecode::decode`] uses [`NbtLimits::NETWORK`]. Errors are returned as an [`NbtDeserError`]
+    ///  carrying the path and offset at which decoding failed.
+    pub async fn decode_with_limits<R : netzer::AsyncRead>(mut r : R, limits : NbtLimits) -> netzer::Result<Self> {
+        let mut accounter = NbtAccounter::new(limits);
+        let     element   = async {
+            let tag = Self::decode_primitive(&mut r, &mut accounter, u8::from_be_bytes).await?;
+            Self::decode_payload(tag, &mut r, &mut accounter).await
+        }.await;
+        element.map_err(|err| deser_error(err).at(accounter.offset()).into())
+    }
+
+}
+impl NbtElement<'_> {
+    async fn decode_payload<R : netzer::AsyncRead>(tag : u8, r : &mut R, a : &mut NbtAccounter) -> netzer::Result<Self> {
+        Ok(match (tag) {
+            tag::BYTE     => NbtElement::Byte(Self::decode_primitive(r, a, i8::from_be_bytes).await?),
+            tag::SHORT    => NbtElement::Short(Self::decode_primitive(r, a, i16::from_be_bytes).await?),
+            tag::INT      => NbtElement::Int(Self::decode_primitive(r, a, i32::from_be_bytes).await?),
+            tag::LONG     => NbtElement::Long(Self::decode_primitive(r, a, i64::from_be_bytes).await?),
+            tag::FLOAT    => NbtElement::Float(Self::decode_primitive(r, a, f32::from_be_bytes).await?),
+            tag::DOUBLE   => NbtElement::Double(Self::decode_primitive(r, a, f64::from_be_bytes).await?),
+            tag::BARRAY   => {
+                let len = Self::decode_len(&mut *r, a, tag::BYTE).await?;
+                let buf = Self::decode_bytes(r, a, len).await?;
+                NbtElement::BArray(Cow::Owned(buf.iter().map(|&v| v.cast_signed()).collect()))
+            },
+            tag::STRING   => NbtElement::String(Cow::Owned(Self::decode_string(r, a).await?)),
+            tag::LIST     => {
+                let     elem_tag = Self::decode_primitive(&mut *r, a, u8::from_be_bytes).await?;
+                let     len      = Self::decode_len(&mut *r, a, elem_tag).await?;
+                // Bound the preallocation, as the length comes from the input.
+                let mut elements = Vec::with_capacity(len.min(4096));
+                a.push_depth()?;
+                for index in 0..len {
+                    let element = Box::pin(Self::decode_payload(elem_tag, r, a)).await
+                        .map_err(|err| deser_error(err).within(NbtErrorSegment::Index(index), a.offset()))?;
+                    elements.push(match (element) {
+                        NbtElement::Compound(compound) => Self::unwrap_mixed(compound),
+                        element                        => element
+                    });
+                }
+                a.pop_depth();
+                NbtElement::List(Cow::Owned(elements))
+            },
+            tag::COMPOUND => {
+                let mut entries = Vec::new();
+                a.push_depth()?;
+                loop {
+                    let tag = Self::decode_primitive(&mut *r, a, u8::from_be_bytes).await?;
+                    if (tag == tag::END) { break; }
+                    let key     = Self::decode_string(&mut *r, a).await?;
+                    let element = match (Box::pin(Self::decode_payload(tag, r, a)).await) {
+                        Ok(element) => element,
+                        Err(err)    => { return Err(deser_error(err).within(NbtErrorSegment::Key(key), a.offset()).into()); }
+                    };
+                    entries.push((Cow::Owned(key), element,));
+                }
+                a.pop_depth();
+                NbtElement::Compound(NbtCompound::from_entries(entries))
+            },
+            tag::IARRAY   => {
+                let len = Self::decode_len(&mut *r, a, tag::INT).await?;
+                let buf = Self::decode_bytes(r, a, len.checked_mul(4).ok_or("NBT array too long")?).await?;
+                NbtElement::IArray(Cow::Owned(buf.as_chunks::<4>().0.iter().map(|&v| i32::from_be_bytes(v)).collect()))
+            },
+            tag::LARRAY   => {
+                let len = Self::decode_len(&mut *r, a, tag::LONG).await?;
+                let buf = Self::decode_bytes(r, a, len.checked_mul(8).ok_or("NBT array too long")?).await?;
+                NbtElement::LArray(Cow::Owned(buf.as_chunks::<8>().0.iter().map(|&v| i64::from_be_bytes(v)).collect()))
+            },
+            _ => { return Err(NbtDeserError::UnknownTag(tag).into()); }
+        })
+    }
+    async fn decode_primitive<R : netzer::AsyncRead, T, const N : usize, F>(mut r : R, a : &mut NbtAccounter, f : F) -> netzer::Result<T>
     where F : FnOnce([u8; N]) -> T
     {
+        a.account_bytes(N)?;
         let mut buf = [0u8; N];
         r.read_exact(&mut buf).await?;
+        a.advance(N);
         Ok(f(buf))
     }
-    async fn decode_string<R : netzer::AsyncRead>(mut r : R) -> netzer::Result<String> {
-        let mut len = [0u8; 2];
-        r.read_exact(&mut len).await?;
-        let len = u16::from_be_bytes(len) as usize;
+    /// Decodes the length of a list or array, checking that it fits in the remaining budget.
+    async fn decode_len<R : netzer::AsyncRead>(r : R, a : &mut NbtAccounter, elem_tag : u8) -> netzer::Result<usize> {
+        let len = Self::decode_primitive(r, a, i32::from_be_bytes).await?;
+        let len = usize::try_from(len).map_err(|_| NbtDeserError::BadLength(len))?;
+        a.check_list(elem_tag, len)?;
+        Ok(len)
+    }
+    async fn decode_bytes<R : netzer::AsyncRead>(mut r : R, a : &mut NbtAccounter, len : usize) -> netzer::Result<Box<[u8]>> {
+        a.account_bytes(len)?;
+        let mut buf = vec![0u8; len].into_boxed_slice();
+        r.read_exact(&mut buf).await?;
+        a.advance(len);
+        Ok(buf)
+    }
+    async fn decode_string<R : netzer::AsyncRead>(mut r : R, a : &mut NbtAccounter) -> netzer::Result<String> {
+        let len = Self::decode_primitive(&mut r, a, u16::from_be_bytes).await? as usize;
+        a.account_bytes(len)?;
         let mut buf = Box::new_uninit_slice(len);
         r.read_exact(unsafe { buf.assume_init_mut() }).await?;
+        a.advance(len);
         let buf = unsafe { buf.assume_init() };
         Ok(match (from_java_cesu8(&buf)?) {
             Cow::Owned(v)    => v,
@@ -125,3 +429,94 @@ impl NbtElement<'_> {
         })
     }
 }
+
+
+/// Recovers the [`NbtDeserError`] behind an error returned while decoding.
+pub(super) fn deser_error(err : netzer::Error) -> NbtDeserError {
+    let err = match (err.downcast::<NbtDeserError>()) {
+        Ok(err)  => { return *err; },
+        Err(err) => err
+    };
+    let err = match (err.downcast::<io::Error>()) {
+        Ok(err)  => { return NbtDeserError::Io(*err); },
+        Err(err) => err
+    };
+    match (err.downcast::<Cesu8DecodingError>()) {
+        Ok(err)  => NbtDeserError::BadString(*err),
+        Err(err) => NbtDeserError::Custom(err.to_string())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::{ from_network, to_network };
+    use core::{
+        fmt::Debug,
+        pin::pin,
+        task::{ Context, Poll, Waker }
+    };
+    use serde::de::{
+        IntoDeserializer,
+        value::Error as ValueError
+    };
+
+    /// Polls a future which never waits, as reading from slices is never pending.
+    fn ready<F : Future>(future : F) -> F::Output {
+        match (pin!(future).poll(&mut Context::from_waker(Waker::noop()))) {
+            Poll::Ready(output) => output,
+            Poll::Pending       => panic!("future is pending")
+        }
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        // `{a:1b,b:2b,a:3b}`
+        let bytes = [
+            tag::COMPOUND,
+            tag::BYTE, 0, 1, b'a', 1,
+            tag::BYTE, 0, 1, b'b', 2,
+            tag::BYTE, 0, 1, b'a', 3,
+            tag::END
+        ];
+        let expected = [("a", 3i8,), ("b", 2,)].into_iter().collect::<NbtCompound>();
+        let decoded  = ready(NbtElement::decode(&bytes[..])).unwrap();
+        assert_eq!(decoded, NbtElement::Compound(expected.clone()));
+        assert_eq!(from_network::<_, NbtElement>(&bytes[..]).unwrap(), decoded);
+        assert_eq!(from_network::<_, NbtCompound>(&bytes[..]).unwrap(), expected);
+
+        let compound = NbtCompound::from_entries(["a", "b", "a", "c", "b", "a"].into_iter().enumerate()
+            .map(|(i, key,)| (Cow::Borrowed(key), NbtElement::Int(i as i32),))
+            .collect()
+        );
+        assert_eq!(compound, [("a", 5,), ("b", 4,), ("c", 3,)].into_iter().collect());
+    }
+
+    /// Checks that `value` becomes `element` in every direction, and is read back from it.
+    fn unsigned<T>(value : T, element : NbtElement<'static>)
+    where
+        T : Ser + for<'de> Deser<'de> + IntoDeserializer<'static, ValueError> + Copy + PartialEq + Debug
+    {
+        assert_eq!(to_element(&value).unwrap(), element);
+        assert_eq!(from_element::<T>(&element).unwrap(), value);
+        assert_eq!(NbtElement::deserialize(value.into_deserializer()).unwrap(), element);
+        let mut buf = Vec::new();
+        to_network(&mut buf, &value).unwrap();
+        assert_eq!(from_network::<_, NbtElement>(&*buf).unwrap(), element);
+        assert_eq!(from_network::<_, T>(&*buf).unwrap(), value);
+    }
+
+    #[test]
+    fn unsigned_round_trip() {
+        unsigned(0u8, NbtElement::Byte(0));
+        unsigned(200u8, NbtElement::Byte(-56));
+        unsigned(u8::MAX, NbtElement::Byte(-1));
+        unsigned(u16::MAX, NbtElement::Short(-1));
+        unsigned(1u16 << 15, NbtElement::Short(i16::MIN));
+        unsigned(u32::MAX, NbtElement::Int(-1));
+        unsigned(1u32 << 31, NbtElement::Int(i32::MIN));
+        unsigned(u64::MAX, NbtElement::Long(-1));
+    }
+
+}
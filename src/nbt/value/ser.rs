@@ -0,0 +1,390 @@
+use super::{
+    NbtElement,
+    NbtCompound
+};
+use crate::nbt::{
+    NbtIArray,
+    NbtLArray,
+    IARRAY_TOKEN,
+    LARRAY_TOKEN,
+    ELEMENT_TOKEN,
+    tag
+};
+use core::{
+    fmt::{ self, Formatter },
+    marker::PhantomData,
+    slice
+};
+use std::borrow::Cow;
+use serde::{
+    Serialize as Ser,
+    Serializer as Serer,
+    Deserialize as Deser,
+    Deserializer as Deserer,
+    de::{
+        Error as DeserError,
+        DeserializeSeed as DeserSeed,
+        MapAccess,
+        SeqAccess,
+        Visitor
+    }
+};
+
+
+impl Ser for NbtElement<'_> {
+    fn serialize<S>(&self, serer : S) -> Result<S::Ok, S::Error>
+    where
+        S : Serer
+    { match (self) {
+        Self::Byte(v)     => serer.serialize_i8(*v),
+        Self::Short(v)    => serer.serialize_i16(*v),
+        Self::Int(v)      => serer.serialize_i32(*v),
+        Self::Long(v)     => serer.serialize_i64(*v),
+        Self::Float(v)    => serer.serialize_f32(*v),
+        Self::Double(v)   => serer.serialize_f64(*v),
+        // SAFETY: `i8` and `u8` have the same size and alignment.
+        Self::BArray(v)   => serer.serialize_bytes(unsafe { slice::from_raw_parts(v.as_ptr().cast::<u8>(), v.len()) }),
+        Self::String(v)   => serer.serialize_str(v),
+        Self::List(v)     => {
+            if (Self::list_tag(v) == tag::COMPOUND && v.iter().any(|element| element.tag() != tag::COMPOUND)) {
+                serer.collect_seq(v.iter().map(Self::wrap_mixed))
+            } else {
+                serer.collect_seq(v.iter())
+            }
+        },
+        Self::Compound(v) => v.serialize(serer),
+        Self::IArray(v)   => NbtIArray(&**v).serialize(serer),
+        Self::LArray(v)   => NbtLArray(&**v).serialize(serer)
+    } }
+}
+
+impl Ser for NbtCompound<'_> {
+    #[inline]
+    fn serialize<S>(&self, serer : S) -> Result<S::Ok, S::Error>
+    where
+        S : Serer
+    { serer.collect_map(self.iter()) }
+}
+
+
+impl<'de, 'l> Deser<'de> for NbtElement<'l> {
+    /// Requested as a marked newtype struct, so that the NBT deserialisers present int and long arrays as marker maps.
+    ///
+    /// Strings and byte arrays are always copied, so that owned elements can be deserialised from any input.
+    ///  See [`NbtElement::deserialize_borrowed`] to borrow them instead.
+    #[inline]
+    fn deserialize<D>(deserer : D) -> Result<Self, D::Error>
+    where
+        D : Deserer<'de>
+    { NbtElementVisitor::<Owned>::new().deserialize(deserer) }
+}
+
+impl<'de, 'l> Deser<'de> for NbtCompound<'l> {
+    /// Strings and byte arrays are always copied. See [`NbtCompound::deserialize_borrowed`] to borrow them instead.
+    #[inline]
+    fn deserialize<D>(deserer : D) -> Result<Self, D::Error>
+    where
+        D : Deserer<'de>
+    { deserialize_compound::<Owned, D>(deserer) }
+}
+
+impl<'l> NbtElement<'l> {
+
+    /// Deserialises an element, borrowing strings and byte arrays from the input where the deserialiser allows it.
+    ///
+    /// Use with `#[serde(borrow, deserialize_with = "NbtElement::deserialize_borrowed")]`.
+    #[inline]
+    pub fn deserialize_borrowed<'de, D>(deserer : D) -> Result<Self, D::Error>
+    where
+        'de : 'l,
+        D   : Deserer<'de>
+    { NbtElementVisitor::<Borrowed>::new().deserialize(deserer) }
+
+}
+
+impl<'l> NbtCompound<'l> {
+
+    /// Deserialises a compound, borrowing keys, strings and byte arrays from the input where the deserialiser allows it.
+    ///
+    /// Use with `#[serde(borrow, deserialize_with = "NbtCompound::deserialize_borrowed")]`.
+    #[inline]
+    pub fn deserialize_borrowed<'de, D>(deserer : D) -> Result<Self, D::Error>
+    where
+        'de : 'l,
+        D   : Deserer<'de>
+    { deserialize_compound::<Borrowed, D>(deserer) }
+
+}
+
+fn deserialize_compound<'de, 'l, M, D>(deserer : D) -> Result<NbtCompound<'l>, D::Error>
+where
+    M : CowMode<'de, 'l>,
+    D : Deserer<'de>
+{ deserer.deserialize_map(NbtElementVisitor::<M>::new()).and_then(|element| match (element) {
+    NbtElement::Compound(compound) => Ok(compound),
+    _                              => Err(D::Error::custom("expected NBT compound"))
+}) }
+
+
+/// How strings and byte arrays given to an [`NbtElementVisitor`] are stored.
+trait CowMode<'de, 'l> {
+    fn str(v : &'de str) -> Cow<'l, str>;
+    fn bytes(v : &'de [u8]) -> Cow<'l, [i8]>;
+}
+
+/// Copies borrowed strings and byte arrays.
+struct Owned;
+impl<'de, 'l> CowMode<'de, 'l> for Owned {
+    #[inline]
+    fn str(v : &'de str) -> Cow<'l, str> { Cow::Owned(v.to_string()) }
+    #[inline]
+    fn bytes(v : &'de [u8]) -> Cow<'l, [i8]> { Cow::Owned(v.iter().map(|b| b.cast_signed()).collect()) }
+}
+
+/// Keeps borrowed strings and byte arrays borrowed.
+struct Borrowed;
+impl<'de : 'l, 'l> CowMode<'de, 'l> for Borrowed {
+    #[inline]
+    fn str(v : &'de str) -> Cow<'l, str> { Cow::Borrowed(v) }
+    // SAFETY: `i8` and `u8` have the same size and alignment.
+    #[inline]
+    fn bytes(v : &'de [u8]) -> Cow<'l, [i8]> { Cow::Borrowed(unsafe { slice::from_raw_parts(v.as_ptr().cast::<i8>(), v.len()) }) }
+}
+
+
+struct NbtElementVisitor<'l, M> {
+    _marker : PhantomData<(&'l (), M,)>
+}
+
+impl<M> NbtElementVisitor<'_, M> {
+    #[inline]
+    fn new() -> Self { Self { _marker : PhantomData } }
+}
+
+impl<'de, 'l, M> DeserSeed<'de> for NbtElementVisitor<'l, M>
+where
+    M : CowMode<'de, 'l>
+{
+    type Value = NbtElement<'l>;
+
+    #[inline]
+    fn deserialize<D>(self, deserer : D) -> Result<Self::Value, D::Error>
+    where
+        D : Deserer<'de>
+    { deserer.deserialize_newtype_struct(ELEMENT_TOKEN, self) }
+}
+
+impl<'de, 'l, M> Visitor<'de> for NbtElementVisitor<'l, M>
+where
+    M : CowMode<'de, 'l>
+{
+    type Value = NbtElement<'l>;
+
+    #[inline]
+    fn expecting(&self, f : &mut Formatter) -> fmt::Result {
+        write!(f, "an NBT element")
+    }
+
+    #[inline]
+    fn visit_bool<E>(self, v : bool) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::Byte(v as i8)) }
+
+    #[inline]
+    fn visit_i8<E>(self, v : i8) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::Byte(v)) }
+
+    #[inline]
+    fn visit_i16<E>(self, v : i16) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::Short(v)) }
+
+    #[inline]
+    fn visit_i32<E>(self, v : i32) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::Int(v)) }
+
+    #[inline]
+    fn visit_i64<E>(self, v : i64) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::Long(v)) }
+
+    // Unsigned integers become the signed element of the same width, keeping their bits, as `to_element` does.
+    #[inline]
+    fn visit_u8<E>(self, v : u8) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::Byte(v.cast_signed())) }
+
+    #[inline]
+    fn visit_u16<E>(self, v : u16) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::Short(v.cast_signed())) }
+
+    #[inline]
+    fn visit_u32<E>(self, v : u32) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::Int(v.cast_signed())) }
+
+    #[inline]
+    fn visit_u64<E>(self, v : u64) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::Long(v.cast_signed())) }
+
+    #[inline]
+    fn visit_f32<E>(self, v : f32) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::Float(v)) }
+
+    #[inline]
+    fn visit_f64<E>(self, v : f64) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::Double(v)) }
+
+    #[inline]
+    fn visit_char<E>(self, v : char) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::String(Cow::Owned(v.to_string()))) }
+
+    #[inline]
+    fn visit_str<E>(self, v : &str) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::String(Cow::Owned(v.to_string()))) }
+
+    #[inline]
+    fn visit_borrowed_str<E>(self, v : &'de str) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::String(M::str(v))) }
+
+    #[inline]
+    fn visit_string<E>(self, v : String) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::String(Cow::Owned(v))) }
+
+    #[inline]
+    fn visit_bytes<E>(self, v : &[u8]) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::BArray(Cow::Owned(v.iter().map(|b| b.cast_signed()).collect()))) }
+
+    #[inline]
+    fn visit_borrowed_bytes<E>(self, v : &'de [u8]) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::BArray(M::bytes(v))) }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, v : Vec<u8>) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(NbtElement::BArray(Cow::Owned(v.into_iter().map(u8::cast_signed).collect()))) }
+
+    /// Other deserialisers see the marked newtype struct requested by [`NbtElement::deserialize`].
+    #[inline]
+    fn visit_newtype_struct<D>(self, deserer : D) -> Result<Self::Value, D::Error>
+    where
+        D : Deserer<'de>
+    { deserer.deserialize_any(self) }
+
+    fn visit_seq<A>(self, mut seq : A) -> Result<Self::Value, A::Error>
+    where
+        A : SeqAccess<'de>
+    {
+        // Bound the preallocation, as the length comes from the input.
+        let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(element) = seq.next_element_seed(Self::new())? {
+            elements.push(match (element) {
+                NbtElement::Compound(compound) => NbtElement::unwrap_mixed(compound),
+                element                        => element
+            });
+        }
+        Ok(NbtElement::List(Cow::Owned(elements)))
+    }
+
+    fn visit_map<A>(self, mut map : A) -> Result<Self::Value, A::Error>
+    where
+        A : MapAccess<'de>
+    {
+        let Some(key) = map.next_key_seed(NbtKeyVisitor::<M>::new())? else { return Ok(NbtElement::Compound(NbtCompound::new())); };
+        let mut entries = Vec::new();
+        match (&*key) {
+            IARRAY_TOKEN => { return Ok(NbtElement::IArray(Cow::Owned(map.next_value::<Vec<i32>>()?))); },
+            LARRAY_TOKEN => { return Ok(NbtElement::LArray(Cow::Owned(map.next_value::<Vec<i64>>()?))); },
+            _            => { entries.push((key, map.next_value_seed(Self::new())?,)); }
+        }
+        while let Some(entry) = map.next_entry_seed(NbtKeyVisitor::<M>::new(), Self::new())? {
+            entries.push(entry);
+        }
+        Ok(NbtElement::Compound(NbtCompound::from_entries(entries)))
+    }
+
+}
+
+
+struct NbtKeyVisitor<'l, M> {
+    _marker : PhantomData<(&'l (), M,)>
+}
+
+impl<M> NbtKeyVisitor<'_, M> {
+    #[inline]
+    fn new() -> Self { Self { _marker : PhantomData } }
+}
+
+impl<'de, 'l, M> DeserSeed<'de> for NbtKeyVisitor<'l, M>
+where
+    M : CowMode<'de, 'l>
+{
+    type Value = Cow<'l, str>;
+
+    #[inline]
+    fn deserialize<D>(self, deserer : D) -> Result<Self::Value, D::Error>
+    where
+        D : Deserer<'de>
+    { deserer.deserialize_str(self) }
+}
+
+impl<'de, 'l, M> Visitor<'de> for NbtKeyVisitor<'l, M>
+where
+    M : CowMode<'de, 'l>
+{
+    type Value = Cow<'l, str>;
+
+    #[inline]
+    fn expecting(&self, f : &mut Formatter) -> fmt::Result {
+        write!(f, "an NBT compound key")
+    }
+
+    #[inline]
+    fn visit_str<E>(self, v : &str) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(Cow::Owned(v.to_string())) }
+
+    #[inline]
+    fn visit_borrowed_str<E>(self, v : &'de str) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(M::str(v)) }
+
+    #[inline]
+    fn visit_string<E>(self, v : String) -> Result<Self::Value, E>
+    where
+        E : DeserError
+    { Ok(Cow::Owned(v)) }
+
+}
@@ -0,0 +1,380 @@
+use super::{
+    NbtElement,
+    NbtCompound
+};
+use crate::nbt::{
+    NbtSerError,
+    BARRAY_TOKEN,
+    IARRAY_TOKEN,
+    LARRAY_TOKEN
+};
+use std::borrow::Cow;
+use serde::ser::{
+    Error as SerError,
+    Serialize as Ser,
+    Serializer as Serer,
+    SerializeSeq as SerSeq,
+    SerializeTuple as SerTuple,
+    SerializeTupleStruct as SerTupleStruct,
+    SerializeTupleVariant as SerTupleVariant,
+    SerializeMap as SerMap,
+    SerializeStruct as SerStruct,
+    SerializeStructVariant as SerStructVariant
+};
+
+
+/// Serialises a value as an [`NbtElement`].
+///
+/// `None` and `()` produce no element, and are only allowed as compound entries.
+pub(super) struct NbtElementSerer;
+
+impl NbtElementSerer {
+
+    /// Serialises a value which must produce an element.
+    fn element<T>(value : &T) -> Result<NbtElement<'static>, NbtSerError>
+    where
+        T : ?Sized + Ser
+    { value.serialize(Self)?.ok_or(NbtSerError::MissingValue) }
+
+}
+
+impl Serer for NbtElementSerer {
+    type Ok    = Option<NbtElement<'static>>;
+    type Error = NbtSerError;
+
+    type SerializeSeq           = NbtElementSeqSerer;
+    type SerializeTuple         = NbtElementSeqSerer;
+    type SerializeTupleStruct   = NbtElementSeqSerer;
+    type SerializeTupleVariant  = NbtElementSeqSerer;
+    type SerializeMap           = NbtElementMapSerer;
+    type SerializeStruct        = NbtElementMapSerer;
+    type SerializeStructVariant = NbtElementMapSerer;
+
+    #[inline]
+    fn serialize_bool(self, v : bool) -> Result<Self::Ok, Self::Error> { Ok(Some(NbtElement::Byte(v as i8))) }
+    #[inline]
+    fn serialize_i8(self, v : i8) -> Result<Self::Ok, Self::Error> { Ok(Some(NbtElement::Byte(v))) }
+    #[inline]
+    fn serialize_i16(self, v : i16) -> Result<Self::Ok, Self::Error> { Ok(Some(NbtElement::Short(v))) }
+    #[inline]
+    fn serialize_i32(self, v : i32) -> Result<Self::Ok, Self::Error> { Ok(Some(NbtElement::Int(v))) }
+    #[inline]
+    fn serialize_i64(self, v : i64) -> Result<Self::Ok, Self::Error> { Ok(Some(NbtElement::Long(v))) }
+    #[inline]
+    fn serialize_u8(self, v : u8) -> Result<Self::Ok, Self::Error> { Ok(Some(NbtElement::Byte(v.cast_signed()))) }
+    #[inline]
+    fn serialize_u16(self, v : u16) -> Result<Self::Ok, Self::Error> { Ok(Some(NbtElement::Short(v.cast_signed()))) }
+    #[inline]
+    fn serialize_u32(self, v : u32) -> Result<Self::Ok, Self::Error> { Ok(Some(NbtElement::Int(v.cast_signed()))) }
+    #[inline]
+    fn serialize_u64(self, v : u64) -> Result<Self::Ok, Self::Error> { Ok(Some(NbtElement::Long(v.cast_signed()))) }
+    #[inline]
+    fn serialize_f32(self, v : f32) -> Result<Self::Ok, Self::Error> { Ok(Some(NbtElement::Float(v))) }
+    #[inline]
+    fn serialize_f64(self, v : f64) -> Result<Self::Ok, Self::Error> { Ok(Some(NbtElement::Double(v))) }
+
+    #[inline]
+    fn serialize_char(self, v : char) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(NbtElement::String(Cow::Owned(v.to_string()))))
+    }
+    #[inline]
+    fn serialize_str(self, v : &str) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(NbtElement::String(Cow::Owned(v.to_string()))))
+    }
+    #[inline]
+    fn serialize_bytes(self, v : &[u8]) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(NbtElement::BArray(Cow::Owned(v.iter().map(|b| b.cast_signed()).collect()))))
+    }
+
+    #[inline]
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> { Ok(None) }
+    #[inline]
+    fn serialize_some<T>(self, v : &T) -> Result<Self::Ok, Self::Error>
+    where
+        T : ?Sized + Ser
+    { v.serialize(self) }
+
+    #[inline]
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> { Ok(None) }
+
+    #[inline]
+    fn serialize_unit_struct(self, _name : &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(NbtElement::Compound(NbtCompound::new())))
+    }
+
+    #[inline]
+    fn serialize_unit_variant(
+        self,
+        _name          : &'static str,
+        _variant_index : u32,
+        variant        : &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name  : &'static str,
+        value : &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T : ?Sized + Ser
+    {
+        if (! matches!(name, BARRAY_TOKEN | IARRAY_TOKEN | LARRAY_TOKEN)) {
+            return value.serialize(self);
+        }
+        let element = Self::element(value)?;
+        Ok(Some(match (name, element,) {
+            (BARRAY_TOKEN, NbtElement::BArray(v),) => NbtElement::BArray(v),
+            (IARRAY_TOKEN, NbtElement::IArray(v),) => NbtElement::IArray(v),
+            (LARRAY_TOKEN, NbtElement::LArray(v),) => NbtElement::LArray(v),
+            (BARRAY_TOKEN, NbtElement::List(v),) => NbtElement::BArray(Cow::Owned(
+                v.iter().map(|element| if let NbtElement::Byte(v) = element { Ok(*v) } else { Err(NbtSerError::BadArray) }).collect::<Result<_, _>>()?
+            )),
+            (IARRAY_TOKEN, NbtElement::List(v),) => NbtElement::IArray(Cow::Owned(
+                v.iter().map(|element| if let NbtElement::Int(v) = element { Ok(*v) } else { Err(NbtSerError::BadArray) }).collect::<Result<_, _>>()?
+            )),
+            (LARRAY_TOKEN, NbtElement::List(v),) => NbtElement::LArray(Cow::Owned(
+                v.iter().map(|element| if let NbtElement::Long(v) = element { Ok(*v) } else { Err(NbtSerError::BadArray) }).collect::<Result<_, _>>()?
+            )),
+            _ => { return Err(NbtSerError::BadArray); }
+        }))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name          : &'static str,
+        _variant_index : u32,
+        variant        : &'static str,
+        value          : &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T : ?Sized + Ser
+    {
+        Ok(Some(in_variant(Some(variant), Self::element(value)?)))
+    }
+
+    #[inline]
+    fn serialize_seq(self, len : Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(NbtElementSeqSerer { elements : Vec::with_capacity(len.unwrap_or(0)), elem_tag : None, variant : None })
+    }
+
+    #[inline]
+    fn serialize_tuple(self, len : usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    #[inline]
+    fn serialize_tuple_struct(
+        self,
+        _name : &'static str,
+        len   : usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    #[inline]
+    fn serialize_tuple_variant(
+        self,
+        _name          : &'static str,
+        _variant_index : u32,
+        variant        : &'static str,
+        len            : usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(NbtElementSeqSerer { elements : Vec::with_capacity(len), elem_tag : None, variant : Some(variant) })
+    }
+
+    #[inline]
+    fn serialize_map(self, _ : Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(NbtElementMapSerer { compound : NbtCompound::new(), key : None, variant : None })
+    }
+
+    #[inline]
+    fn serialize_struct(
+        self,
+        _name : &'static str,
+        _len  : usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(None)
+    }
+
+    #[inline]
+    fn serialize_struct_variant(
+        self,
+        _name          : &'static str,
+        _variant_index : u32,
+        variant        : &'static str,
+        _len           : usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(NbtElementMapSerer { compound : NbtCompound::new(), key : None, variant : Some(variant) })
+    }
+
+}
+
+
+/// Wraps an element in a compound under the name of its enum variant, if any.
+fn in_variant(variant : Option<&'static str>, element : NbtElement<'static>) -> NbtElement<'static> {
+    let Some(variant) = variant
+        else { return element; };
+    let mut compound = NbtCompound::new();
+    compound.insert(variant, element);
+    NbtElement::Compound(compound)
+}
+
+
+pub(super) struct NbtElementSeqSerer {
+    elements : Vec<NbtElement<'static>>,
+    /// The tag of the first element, which all other elements must share.
+    elem_tag : Option<u8>,
+    variant  : Option<&'static str>
+}
+
+impl SerSeq for NbtElementSeqSerer {
+    type Ok    = Option<NbtElement<'static>>;
+    type Error = NbtSerError;
+
+    fn serialize_element<T>(&mut self, value : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    {
+        let element  = NbtElementSerer::element(value)?;
+        let expected = *self.elem_tag.get_or_insert(element.tag());
+        if (element.tag() != expected) {
+            return Err(NbtSerError::MixedList { expected, found : element.tag() });
+        }
+        // Elements of mixed lists may already be wrapped, such as when serialising an `NbtElement`.
+        self.elements.push(match (element) {
+            NbtElement::Compound(compound) => NbtElement::unwrap_mixed(compound),
+            element                        => element
+        });
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(in_variant(self.variant, NbtElement::List(Cow::Owned(self.elements)))))
+    }
+}
+
+impl SerTuple for NbtElementSeqSerer {
+    type Ok    = Option<NbtElement<'static>>;
+    type Error = NbtSerError;
+
+    #[inline]
+    fn serialize_element<T>(&mut self, value : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    { SerSeq::serialize_element(self, value) }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> { SerSeq::end(self) }
+}
+
+impl SerTupleStruct for NbtElementSeqSerer {
+    type Ok    = Option<NbtElement<'static>>;
+    type Error = NbtSerError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, value : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    { SerSeq::serialize_element(self, value) }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> { SerSeq::end(self) }
+}
+
+impl SerTupleVariant for NbtElementSeqSerer {
+    type Ok    = Option<NbtElement<'static>>;
+    type Error = NbtSerError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, value : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    { SerSeq::serialize_element(self, value) }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> { SerSeq::end(self) }
+}
+
+
+pub(super) struct NbtElementMapSerer {
+    compound : NbtCompound<'static>,
+    key      : Option<String>,
+    variant  : Option<&'static str>
+}
+
+impl NbtElementMapSerer {
+
+    fn insert<T>(&mut self, key : String, value : &T) -> Result<(), NbtSerError>
+    where
+        T : ?Sized + Ser
+    {
+        if let Some(element) = value.serialize(NbtElementSerer)? {
+            self.compound.insert(key, element);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn finish(self) -> NbtElement<'static> {
+        in_variant(self.variant, NbtElement::Compound(self.compound))
+    }
+
+}
+
+impl SerMap for NbtElementMapSerer {
+    type Ok    = Option<NbtElement<'static>>;
+    type Error = NbtSerError;
+
+    fn serialize_key<T>(&mut self, key : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    {
+        let NbtElement::String(key) = NbtElementSerer::element(key)?
+            else { return Err(NbtSerError::KeyMustBeString); };
+        self.key = Some(key.into_owned());
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    {
+        let Some(key) = self.key.take()
+            else { return Err(NbtSerError::custom("NBT compound value serialised before its key")); };
+        self.insert(key, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> { Ok(Some(self.finish())) }
+}
+
+impl SerStruct for NbtElementMapSerer {
+    type Ok    = Option<NbtElement<'static>>;
+    type Error = NbtSerError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, key : &'static str, value : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    { self.insert(key.to_string(), value) }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> { Ok(Some(self.finish())) }
+}
+
+impl SerStructVariant for NbtElementMapSerer {
+    type Ok    = Option<NbtElement<'static>>;
+    type Error = NbtSerError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, key : &'static str, value : &T) -> Result<(), Self::Error>
+    where
+        T : ?Sized + Ser
+    { self.insert(key.to_string(), value) }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> { Ok(Some(self.finish())) }
+}
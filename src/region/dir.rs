@@ -0,0 +1,105 @@
+use super::{
+    RegionPos,
+    RegionFile,
+    RegionError
+};
+use crate::{
+    chunk_pos::ChunkPos,
+    nbt::NbtCompression
+};
+use std::{
+    collections::{ HashMap, hash_map::Entry },
+    fs,
+    io,
+    path::{ Path, PathBuf }
+};
+use serde::{
+    Serialize as Ser,
+    Deserialize as Deser
+};
+
+
+/// A directory of Anvil region files, such as a dimension's `region` directory.
+///
+/// Region files are opened as chunks in them are accessed, and kept open until this is dropped.
+pub struct RegionDir {
+    dir     : PathBuf,
+    regions : HashMap<RegionPos, RegionFile>
+}
+
+impl RegionDir {
+
+    /// Creates a [`RegionDir`] reading and writing region files in the given directory.
+    ///
+    /// Region files are only opened for writing once a chunk in them is written or removed,
+    ///  so chunks can be read from worlds which are read only.
+    pub fn new<P>(dir : P) -> Self
+    where
+        P : Into<PathBuf>
+    { Self { dir : dir.into(), regions : HashMap::new() } }
+
+    /// Returns the path of this directory.
+    #[inline]
+    pub fn path(&self) -> &Path { &self.dir }
+
+    /// Returns the positions of all regions which have a file in this directory.
+    pub fn regions(&self) -> io::Result<Vec<RegionPos>> {
+        let mut regions = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            if let Some(pos) = entry?.file_name().to_str().and_then(RegionPos::from_file_name) {
+                regions.push(pos);
+            }
+        }
+        Ok(regions)
+    }
+
+    /// Returns the open region file for the given region, opening it if needed.
+    ///
+    /// Region files are opened for reading only unless `write` is `true`, in which case
+    ///  the file is reopened for writing if needed, and created if it does not exist.
+    /// Returns `None` if the region has no file and `write` is `false`.
+    pub fn region(&mut self, pos : RegionPos, write : bool) -> Result<Option<&mut RegionFile>, RegionError> {
+        if (write) { return self.writable_region(pos).map(Some); }
+        if (! self.regions.contains_key(&pos)) {
+            if (! self.dir.join(pos.file_name()).exists()) { return Ok(None); }
+            self.regions.insert(pos, RegionFile::open_read(&self.dir, pos)?);
+        }
+        Ok(self.regions.get_mut(&pos))
+    }
+
+    /// Returns the open region file for the given region, reopening it for writing or creating it if needed.
+    fn writable_region(&mut self, pos : RegionPos) -> Result<&mut RegionFile, RegionError> {
+        match (self.regions.entry(pos)) {
+            Entry::Occupied(mut entry) => {
+                if (! entry.get().is_writable()) { entry.insert(RegionFile::open(&self.dir, pos)?); }
+                Ok(entry.into_mut())
+            },
+            Entry::Vacant(entry) => Ok(entry.insert(RegionFile::open(&self.dir, pos)?))
+        }
+    }
+
+    /// Reads and deserialises the NBT of the given chunk.
+    pub fn read_chunk<T>(&mut self, pos : ChunkPos) -> Result<Option<T>, RegionError>
+    where
+        for<'l> T : Deser<'l>
+    {
+        let Some(region) = self.region(pos.region(), false)? else { return Ok(None); };
+        region.read_chunk(pos)
+    }
+
+    /// Serialises and writes the NBT of the given chunk, creating its region file if needed.
+    pub fn write_chunk<T>(&mut self, pos : ChunkPos, compression : NbtCompression, value : &T) -> Result<(), RegionError>
+    where
+        T : Ser
+    {
+        self.writable_region(pos.region())?.write_chunk(pos, compression, value)
+    }
+
+    /// Removes the given chunk, returning `true` if it was present.
+    pub fn remove_chunk(&mut self, pos : ChunkPos) -> Result<bool, RegionError> {
+        let Some(region) = self.region(pos.region(), false)? else { return Ok(false); };
+        if (! region.contains(pos)?) { return Ok(false); }
+        self.writable_region(pos.region())?.remove_chunk(pos)
+    }
+
+}
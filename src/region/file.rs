@@ -0,0 +1,326 @@
+use super::{
+    RegionPos,
+    RegionError
+};
+use crate::{
+    chunk_pos::ChunkPos,
+    nbt::{ self, NbtCompression }
+};
+use std::{
+    fs::{ self, File },
+    io::{ Read, Write, Seek, SeekFrom },
+    path::{ Path, PathBuf },
+    time::{ SystemTime, UNIX_EPOCH }
+};
+use serde::{
+    Serialize as Ser,
+    Deserialize as Deser
+};
+
+
+const SECTOR_LEN         : usize = 4096;
+const CHUNKS             : usize = 1024;
+/// Chunks spanning this many sectors or more are stored in external `.mcc` files.
+const MAX_SECTORS        : usize = 256;
+const EXTERNAL_FLAG      : u8    = 0x80;
+const COMPRESSION_GZIP   : u8    = 1;
+const COMPRESSION_ZLIB   : u8    = 2;
+const COMPRESSION_NONE   : u8    = 3;
+const COMPRESSION_LZ4    : u8    = 4;
+const COMPRESSION_CUSTOM : u8    = 127;
+
+
+/// An open Anvil region (`.mca`) file.
+///
+/// Holds the 1024 entry location and timestamp header in memory,
+///  and reads or writes chunk payloads on demand.
+pub struct RegionFile {
+    file       : File,
+    dir        : PathBuf,
+    pos        : RegionPos,
+    writable   : bool,
+    locations  : [u32; CHUNKS],
+    timestamps : [u32; CHUNKS]
+}
+
+impl RegionFile {
+
+    /// Opens the region file for the given region in a directory for reading and writing, creating it if it does not exist.
+    #[inline]
+    pub fn open<P>(dir : P, pos : RegionPos) -> Result<Self, RegionError>
+    where
+        P : AsRef<Path>
+    { Self::open_with(dir.as_ref(), pos, true) }
+
+    /// Opens the existing region file for the given region in a directory for reading only.
+    ///
+    /// Chunks can not be written to or removed from a region file opened this way.
+    #[inline]
+    pub fn open_read<P>(dir : P, pos : RegionPos) -> Result<Self, RegionError>
+    where
+        P : AsRef<Path>
+    { Self::open_with(dir.as_ref(), pos, false) }
+
+    fn open_with(dir : &Path, pos : RegionPos, writable : bool) -> Result<Self, RegionError> {
+        let     dir    = dir.to_path_buf();
+        let mut file   = File::options().read(true).write(writable).create(writable).truncate(false).open(dir.join(pos.file_name()))?;
+        let mut header = vec![0u8; SECTOR_LEN * 2];
+        match (file.metadata()?.len()) {
+            // An empty file is a region with no chunks. Vanilla leaves these behind when it creates a region without saving any chunks.
+            0 => { if (writable) { file.set_len(header.len() as u64)?; } },
+            len if (len < header.len() as u64) => { return Err(RegionError::TruncatedHeader(pos)); },
+            _ => { file.read_exact(&mut header)?; }
+        }
+        let (locations, timestamps,) = header.split_at(SECTOR_LEN);
+        let read_table = |bytes : &[u8]| {
+            let mut table = [0u32; CHUNKS];
+            for (entry, bytes,) in table.iter_mut().zip(bytes.as_chunks::<4>().0) {
+                *entry = u32::from_be_bytes(*bytes);
+            }
+            table
+        };
+        Ok(Self { locations : read_table(locations), timestamps : read_table(timestamps), file, dir, pos, writable })
+    }
+
+    /// Returns the position of this region.
+    #[inline]
+    pub fn pos(&self) -> RegionPos { self.pos }
+
+    /// Returns `true` if this region file was opened for writing.
+    #[inline]
+    pub fn is_writable(&self) -> bool { self.writable }
+
+    /// Returns the positions of all chunks stored in this region.
+    pub fn chunks(&self) -> impl Iterator<Item = ChunkPos> + '_ {
+        let min = self.pos.min_chunk();
+        self.locations.iter().enumerate()
+            .filter(|(_, location,)| **location != 0)
+            .map(move |(index, _,)| ChunkPos { x : min.x + (index % 32) as i32, z : min.z + (index / 32) as i32 })
+    }
+
+    /// Returns `true` if the given chunk is stored in this region.
+    #[inline]
+    pub fn contains(&self, pos : ChunkPos) -> Result<bool, RegionError> {
+        Ok(self.locations[self.index(pos)?] != 0)
+    }
+
+    /// Returns when the given chunk was last written, in seconds since the unix epoch.
+    pub fn timestamp(&self, pos : ChunkPos) -> Result<Option<u32>, RegionError> {
+        let index = self.index(pos)?;
+        Ok((self.locations[index] != 0).then_some(self.timestamps[index]))
+    }
+
+    /// Reads and deserialises the NBT of the given chunk.
+    pub fn read_chunk<T>(&mut self, pos : ChunkPos) -> Result<Option<T>, RegionError>
+    where
+        for<'l> T : Deser<'l>
+    {
+        let Some((compression, data,)) = self.read_chunk_payload(pos)? else { return Ok(None); };
+        let compression = match (compression) {
+            COMPRESSION_GZIP                           => NbtCompression::Gzip,
+            COMPRESSION_ZLIB                           => NbtCompression::Zlib,
+            COMPRESSION_NONE                           => NbtCompression::None,
+            v @ (COMPRESSION_LZ4 | COMPRESSION_CUSTOM) => { return Err(RegionError::UnsupportedCompression(v)); },
+            v                                          => { return Err(RegionError::UnknownCompression(v)); }
+        };
+        Ok(Some(nbt::from_file::<_, T>(&*data, compression)?.1))
+    }
+
+    /// Serialises and writes the NBT of the given chunk, replacing any existing data.
+    ///
+    /// Vanilla compresses chunks using [`NbtCompression::Zlib`].
+    pub fn write_chunk<T>(&mut self, pos : ChunkPos, compression : NbtCompression, value : &T) -> Result<(), RegionError>
+    where
+        T : Ser
+    {
+        let index = self.index(pos)?;
+        if (! self.writable) { return Err(RegionError::ReadOnly); }
+        let mut data = Vec::new();
+        nbt::to_file(&mut data, compression, "", value)?;
+        let compression = match (compression) {
+            NbtCompression::Gzip => COMPRESSION_GZIP,
+            NbtCompression::Zlib => COMPRESSION_ZLIB,
+            NbtCompression::None => COMPRESSION_NONE
+        };
+
+        // The previous data is left in place until the header points at the new data,
+        //  so that it survives if writing fails part way through.
+        let external_path = self.external_path(pos);
+        let external      = (5 + data.len()).div_ceil(SECTOR_LEN) >= MAX_SECTORS;
+        let mut payload   = Vec::with_capacity(5 + data.len());
+        if (external) {
+            let temp_path = external_path.with_extension("mcc.tmp");
+            fs::write(&temp_path, &data)?;
+            fs::rename(&temp_path, &external_path)?;
+            payload.extend_from_slice(&1u32.to_be_bytes());
+            payload.push(compression | EXTERNAL_FLAG);
+        } else {
+            payload.extend_from_slice(&(data.len() as u32 + 1).to_be_bytes());
+            payload.push(compression);
+            payload.extend_from_slice(&data);
+        }
+        let sectors = payload.len().div_ceil(SECTOR_LEN);
+        payload.resize(sectors * SECTOR_LEN, 0);
+
+        let start = self.find_free_sectors(sectors);
+        self.file.seek(SeekFrom::Start((start * SECTOR_LEN) as u64))?;
+        self.file.write_all(&payload)?;
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs() as u32);
+        self.write_header(index, ((start as u32) << 8) | (sectors as u32), timestamp)?;
+        if (! external && external_path.exists()) { fs::remove_file(&external_path)?; }
+        Ok(())
+    }
+
+    /// Removes the given chunk from this region, returning `true` if it was present.
+    pub fn remove_chunk(&mut self, pos : ChunkPos) -> Result<bool, RegionError> {
+        let index = self.index(pos)?;
+        if (self.locations[index] == 0) { return Ok(false); }
+        if (! self.writable) { return Err(RegionError::ReadOnly); }
+        let external_path = self.external_path(pos);
+        if (external_path.exists()) { fs::remove_file(&external_path)?; }
+        self.write_header(index, 0, 0)?;
+        Ok(true)
+    }
+
+}
+
+impl RegionFile {
+
+    fn index(&self, pos : ChunkPos) -> Result<usize, RegionError> {
+        if (! self.pos.contains(pos)) { return Err(RegionError::NotInRegion(pos)); }
+        Ok((pos.x.rem_euclid(32) + pos.z.rem_euclid(32) * 32) as usize)
+    }
+
+    fn external_path(&self, pos : ChunkPos) -> PathBuf {
+        self.dir.join(format!("c.{}.{}.mcc", pos.x, pos.z))
+    }
+
+    /// Reads the compression scheme and compressed payload of the given chunk.
+    fn read_chunk_payload(&mut self, pos : ChunkPos) -> Result<Option<(u8, Vec<u8>,)>, RegionError> {
+        let index    = self.index(pos)?;
+        let location = self.locations[index];
+        if (location == 0) { return Ok(None); }
+        let (start, sectors,) = ((location >> 8) as usize, (location & 0xFF) as usize,);
+        if (start < 2 || sectors == 0) { return Err(RegionError::BadChunk(pos)); }
+
+        self.file.seek(SeekFrom::Start((start * SECTOR_LEN) as u64))?;
+        let mut header = [0u8; 5];
+        self.file.read_exact(&mut header)?;
+        let len         = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let compression = header[4];
+        if (len == 0 || len + 4 > sectors * SECTOR_LEN) { return Err(RegionError::BadChunk(pos)); }
+
+        if (compression & EXTERNAL_FLAG != 0) {
+            Ok(Some((compression & ! EXTERNAL_FLAG, fs::read(self.external_path(pos))?,)))
+        } else {
+            let mut data = vec![0u8; len - 1];
+            self.file.read_exact(&mut data)?;
+            Ok(Some((compression, data,)))
+        }
+    }
+
+    /// Finds the first run of free sectors long enough to hold a chunk.
+    ///
+    /// The sectors of a chunk being replaced are still in use, and are only freed once the header stops pointing at them.
+    fn find_free_sectors(&self, sectors : usize) -> usize {
+        let mut used = vec![true, true];
+        for location in &self.locations {
+            if (*location == 0) { continue; }
+            let (start, count,) = ((location >> 8) as usize, (location & 0xFF) as usize,);
+            if (used.len() < start + count) { used.resize(start + count, false); }
+            used[start..(start + count)].fill(true);
+        }
+        let mut run = 0;
+        for (sector, used,) in used.iter().enumerate() {
+            if (*used) { run = 0; } else {
+                run += 1;
+                if (run == sectors) { return sector + 1 - sectors; }
+            }
+        }
+        used.len() - run
+    }
+
+    fn write_header(&mut self, index : usize, location : u32, timestamp : u32) -> Result<(), RegionError> {
+        self.locations[index]  = location;
+        self.timestamps[index] = timestamp;
+        self.file.seek(SeekFrom::Start((index * 4) as u64))?;
+        self.file.write_all(&location.to_be_bytes())?;
+        self.file.seek(SeekFrom::Start((SECTOR_LEN + index * 4) as u64))?;
+        self.file.write_all(&timestamp.to_be_bytes())?;
+        Ok(())
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name : &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pipeworkmc-region-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn read_only() {
+        let dir = temp_dir("read-only");
+        let pos = RegionPos { x : 0, z : 0 };
+        assert!(matches!(RegionFile::open_read(&dir, pos), Err(RegionError::Io(_))));
+        RegionFile::open(&dir, pos).unwrap().write_chunk(ChunkPos { x : 1, z : 2 }, NbtCompression::Zlib, &5i32).unwrap();
+
+        let mut region = RegionFile::open_read(&dir, pos).unwrap();
+        assert_eq!(region.read_chunk::<i32>(ChunkPos { x : 1, z : 2 }).unwrap(), Some(5));
+        assert!(matches!(region.write_chunk(ChunkPos { x : 1, z : 2 }, NbtCompression::Zlib, &6i32), Err(RegionError::ReadOnly)));
+        assert!(matches!(region.remove_chunk(ChunkPos { x : 1, z : 2 }), Err(RegionError::ReadOnly)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn truncated_header() {
+        let dir = temp_dir("truncated");
+        let pos = RegionPos { x : -1, z : 3 };
+        fs::write(dir.join(pos.file_name()), []).unwrap();
+        assert_eq!(RegionFile::open_read(&dir, pos).unwrap().chunks().count(), 0);
+        fs::write(dir.join(pos.file_name()), [0u8; SECTOR_LEN + 7]).unwrap();
+        assert!(matches!(RegionFile::open_read(&dir, pos), Err(RegionError::TruncatedHeader(p)) if (p == pos)));
+        assert!(matches!(RegionFile::open(&dir, pos), Err(RegionError::TruncatedHeader(p)) if (p == pos)));
+        assert_eq!(fs::metadata(dir.join(pos.file_name())).unwrap().len(), (SECTOR_LEN + 7) as u64);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn lz4_compression() {
+        let dir = temp_dir("lz4");
+        let pos = RegionPos { x : 0, z : 0 };
+        let mut data = vec![0u8; SECTOR_LEN * 3];
+        data[..4].copy_from_slice(&((2 << 8) | 1u32).to_be_bytes());
+        data[(SECTOR_LEN * 2)..(SECTOR_LEN * 2 + 5)].copy_from_slice(&[0, 0, 0, 2, COMPRESSION_LZ4]);
+        fs::write(dir.join(pos.file_name()), data).unwrap();
+        let mut region = RegionFile::open_read(&dir, pos).unwrap();
+        assert!(matches!(region.read_chunk::<i32>(ChunkPos { x : 0, z : 0 }), Err(RegionError::UnsupportedCompression(COMPRESSION_LZ4))));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rewrite_keeps_old_sectors() {
+        let dir = temp_dir("rewrite");
+        let pos = ChunkPos { x : 0, z : 0 };
+        let mut region = RegionFile::open(&dir, RegionPos { x : 0, z : 0 }).unwrap();
+        region.write_chunk(pos, NbtCompression::None, &1i32).unwrap();
+        let old = region.locations[0];
+        region.write_chunk(pos, NbtCompression::None, &2i32).unwrap();
+        let new = region.locations[0];
+        assert_ne!(old >> 8, new >> 8);
+        assert_eq!(region.read_chunk::<i32>(pos).unwrap(), Some(2));
+        // The old sectors are free again once the header no longer points at them.
+        region.write_chunk(pos, NbtCompression::None, &3i32).unwrap();
+        assert_eq!(region.locations[0], old);
+        assert_eq!(region.read_chunk::<i32>(pos).unwrap(), Some(3));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+}
@@ -0,0 +1,117 @@
+//! Anvil region files.
+
+
+use crate::{
+    chunk_pos::ChunkPos,
+    nbt::{ NbtSerError, NbtDeserError }
+};
+use core::{
+    error::Error as StdError,
+    fmt::{ self, Display, Formatter }
+};
+use std::io;
+
+
+mod file;
+pub use file::*;
+
+mod dir;
+pub use dir::*;
+
+
+/// The position of a region, spanning 32 by 32 chunks.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct RegionPos {
+    /// X
+    pub x : i32,
+    /// Z
+    pub z : i32
+}
+
+impl RegionPos {
+
+    /// Returns the position of the chunk in the north-west corner of this region.
+    pub const fn min_chunk(self) -> ChunkPos { ChunkPos {
+        x : self.x * 32,
+        z : self.z * 32
+    } }
+
+    /// Returns `true` if the given chunk falls into this region.
+    pub const fn contains(self, chunk : ChunkPos) -> bool {
+        chunk.x.div_euclid(32) == self.x && chunk.z.div_euclid(32) == self.z
+    }
+
+    /// Returns the name of the file storing this region, such as `r.0.-1.mca`.
+    pub fn file_name(self) -> String {
+        format!("r.{}.{}.mca", self.x, self.z)
+    }
+
+    /// Parses the name of a file storing a region.
+    pub fn from_file_name(name : &str) -> Option<Self> {
+        let (x, z,) = name.strip_prefix("r.")?.strip_suffix(".mca")?.split_once('.')?;
+        Some(Self { x : x.parse().ok()?, z : z.parse().ok()? })
+    }
+
+}
+
+
+/// Errors emitted while reading or writing region files.
+#[derive(Debug)]
+pub enum RegionError {
+    /// An io error occured.
+    Io(io::Error),
+    /// A chunk could not be serialised as NBT.
+    Ser(NbtSerError),
+    /// A chunk could not be deserialised from NBT.
+    Deser(NbtDeserError),
+
+    /// A chunk was accessed through a region which does not contain it.
+    NotInRegion(ChunkPos),
+    /// A chunk uses an unknown compression scheme.
+    UnknownCompression(u8),
+    /// A chunk uses a compression scheme which is known but not supported, such as LZ4.
+    UnsupportedCompression(u8),
+    /// A region file is too short to hold its location and timestamp header.
+    TruncatedHeader(RegionPos),
+    /// A chunk was written to or removed from a region file opened for reading only.
+    ReadOnly,
+    /// A chunk's location or length header is corrupt.
+    BadChunk(ChunkPos)
+}
+impl From<io::Error> for RegionError {
+    #[inline]
+    fn from(err : io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+impl From<NbtSerError> for RegionError {
+    #[inline]
+    fn from(err : NbtSerError) -> Self {
+        Self::Ser(err)
+    }
+}
+impl From<NbtDeserError> for RegionError {
+    #[inline]
+    fn from(err : NbtDeserError) -> Self {
+        Self::Deser(err)
+    }
+}
+
+impl Display for RegionError {
+    fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result { match (self) {
+        Self::Io(err)                   => write!(f, "{err}"),
+        Self::Ser(err)                  => write!(f, "{err}"),
+        Self::Deser(err)                => write!(f, "{err}"),
+        Self::NotInRegion(pos)          => write!(f, "chunk {} {} is not in region", pos.x, pos.z),
+        Self::UnknownCompression(v)     => write!(f, "unknown compression 0x{v:0>2X}"),
+        Self::UnsupportedCompression(v) => match (v) {
+            4   => write!(f, "unsupported compression lz4"),
+            127 => write!(f, "unsupported custom compression"),
+            v   => write!(f, "unsupported compression 0x{v:0>2X}")
+        },
+        Self::TruncatedHeader(pos)      => write!(f, "truncated header in region {} {}", pos.x, pos.z),
+        Self::ReadOnly                  => write!(f, "region file is read only"),
+        Self::BadChunk(pos)             => write!(f, "bad chunk {} {}", pos.x, pos.z)
+    } }
+}
+impl StdError for RegionError { }
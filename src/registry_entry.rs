@@ -3,7 +3,7 @@
 
 use crate::{
     ident::Ident,
-    nbt::to_network as to_network_nbt
+    nbt::{ NbtSerError, to_network as to_network_nbt }
 };
 use core::fmt::Debug;
 use std::io::Write;
@@ -30,8 +30,8 @@ where
     /// The ID of the registry.
     const REGISTRY_ID : Ident;
 
-    /// Write this registry entry as network NBT.
-    fn to_network_nbt<W>(&self, writer : W) -> bool
+    /// Write this registry entry as network NBT, returning `false` if there was nothing to write.
+    fn to_network_nbt<W>(&self, writer : W) -> Result<bool, NbtSerError>
     where
         W : Write;
 }
@@ -44,12 +44,12 @@ where
     const REGISTRY_ID : Ident = <T as RegistryEntryType>::REGISTRY_ID;
 
     #[inline]
-    fn to_network_nbt<W>(&self, writer : W) -> bool
+    fn to_network_nbt<W>(&self, writer : W) -> Result<bool, NbtSerError>
     where
         W : Write
     {
-        to_network_nbt(writer, self).unwrap();
-        true
+        to_network_nbt(writer, self)?;
+        Ok(true)
     }
 }
 
@@ -61,12 +61,12 @@ where
     const REGISTRY_ID : Ident = <T as RegistryEntryType>::REGISTRY_ID;
 
     #[inline]
-    fn to_network_nbt<W>(&self, writer : W) -> bool
+    fn to_network_nbt<W>(&self, writer : W) -> Result<bool, NbtSerError>
     where
         W : Write
     {
-        to_network_nbt(writer, self).unwrap();
-        true
+        to_network_nbt(writer, self)?;
+        Ok(true)
     }
 }
 
@@ -77,8 +77,11 @@ where
 {
     const REGISTRY_ID : Ident = <T as RegistryEntryType>::REGISTRY_ID;
 
-    fn to_network_nbt<W>(&self, writer : W) -> bool
+    fn to_network_nbt<W>(&self, writer : W) -> Result<bool, NbtSerError>
     where
         W : Write
-    { self.as_ref().is_some_and(|inner| T::to_network_nbt(inner, writer)) }
+    { match (self) {
+        Some(inner) => T::to_network_nbt(inner, writer),
+        None        => Ok(false)
+    } }
 }
@@ -1,7 +1,7 @@
 use super::RegistryEntryType;
 use crate::{
     ident::Ident,
-    nbt::to_network as to_network_nbt,
+    nbt::{ NbtSerError, to_network as to_network_nbt },
     text::Text
 };
 use std::io::Write;
@@ -43,11 +43,11 @@ pub struct TrimMaterialPaletteOverrides {
 impl RegistryEntryType for TrimMaterialRegistryEntry {
     const REGISTRY_ID : Ident = Ident::new("minecraft:trim_material");
 
-    fn to_network_nbt<W>(&self, writer : W) -> bool
+    fn to_network_nbt<W>(&self, writer : W) -> Result<bool, NbtSerError>
     where
         W : Write
     {
-        to_network_nbt(writer, self).unwrap();
-        true
+        to_network_nbt(writer, self)?;
+        Ok(true)
     }
 }
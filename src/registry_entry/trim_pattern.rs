@@ -1,7 +1,7 @@
 use super::RegistryEntryType;
 use crate::{
     ident::Ident,
-    nbt::to_network as to_network_nbt,
+    nbt::{ NbtSerError, to_network as to_network_nbt },
     text::Text
 };
 use std::io::Write;
@@ -24,11 +24,11 @@ pub struct TrimPatternRegistryEntry {
 impl RegistryEntryType for TrimPatternRegistryEntry {
     const REGISTRY_ID : Ident = Ident::new("minecraft:trim_pattern");
 
-    fn to_network_nbt<W>(&self, writer : W) -> bool
+    fn to_network_nbt<W>(&self, writer : W) -> Result<bool, NbtSerError>
     where
         W : Write
     {
-        to_network_nbt(writer, self).unwrap();
-        true
+        to_network_nbt(writer, self)?;
+        Ok(true)
     }
 }
@@ -1,15 +1,149 @@
 use super::{
     Text,
     TextComponent,
-    TextContent
+    TextContent,
+    TextObject
 };
+use super::legacy::LEGACY_COLOURS;
+use crate::colour::Rgb;
 use core::fmt::{ self,
     Display,
-    Formatter
+    Formatter,
+    Write as _
 };
+use core::sync::atomic::{ AtomicU8, Ordering };
+use std::{
+    env,
+    io::{ self, IsTerminal }
+};
+
+
+/// How [`Text`] is coloured when displayed on a console.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColourMode {
+    /// No escape codes are written.
+    None,
+    /// Colours are reduced to the 16 standard console colours.
+    Ansi16,
+    /// Colours are reduced to the 256 colour palette.
+    Ansi256,
+    /// Colours are written as they are.
+    TrueColour
+}
+
+/// The current [`ColourMode`], or `u8::MAX` if it has not been selected yet.
+static COLOUR_MODE : AtomicU8 = AtomicU8::new(u8::MAX);
+
+impl ColourMode {
+
+    /// Selects a mode from the environment.
+    ///
+    /// No colours are used if `NO_COLOR` is set, if standard output is not a terminal, or if `TERM` is `dumb`.
+    /// Otherwise, `COLORTERM` set to `truecolor` or `24bit` selects [`ColourMode::TrueColour`], and a `TERM`
+    ///  containing `256color` selects [`ColourMode::Ansi256`].
+    ///
+    /// An unset `TERM` disables colours, except on Windows where consoles do not set it. There, Windows Terminal
+    ///  (detected through `WT_SESSION`) selects [`ColourMode::TrueColour`], and other consoles [`ColourMode::Ansi16`].
+    pub fn detect() -> Self {
+        if (env::var_os("NO_COLOR").is_some_and(|v| ! v.is_empty())) { return Self::None; }
+        if (! io::stdout().is_terminal()) { return Self::None; }
+        if let Ok(colour_term) = env::var("COLORTERM")
+            && (colour_term.eq_ignore_ascii_case("truecolor") || colour_term.eq_ignore_ascii_case("24bit"))
+        { return Self::TrueColour; }
+        match (env::var("TERM")) {
+            Ok(term) if (term.is_empty() || term == "dumb") => Self::None,
+            Ok(term) if (term.contains("256color"))         => Self::Ansi256,
+            Ok(_)                                            => Self::Ansi16,
+            Err(_)                                           => Self::detect_without_term()
+        }
+    }
+
+    #[cfg(not(windows))]
+    #[inline]
+    fn detect_without_term() -> Self { Self::None }
+
+    #[cfg(windows)]
+    fn detect_without_term() -> Self {
+        if (env::var_os("WT_SESSION").is_some()) { Self::TrueColour } else { Self::Ansi16 }
+    }
+
+    /// Returns the mode used when displaying [`Text`].
+    ///
+    /// Selected by [`ColourMode::detect`] unless it was set by [`ColourMode::set_current`].
+    pub fn current() -> Self {
+        match (Self::from_u8(COLOUR_MODE.load(Ordering::Relaxed))) {
+            Some(mode) => mode,
+            None       => {
+                let mode = Self::detect();
+                let _ = COLOUR_MODE.compare_exchange(u8::MAX, mode as u8, Ordering::Relaxed, Ordering::Relaxed);
+                Self::from_u8(COLOUR_MODE.load(Ordering::Relaxed)).unwrap_or(mode)
+            }
+        }
+    }
+
+    /// Sets the mode used when displaying [`Text`].
+    #[inline]
+    pub fn set_current(mode : Self) {
+        COLOUR_MODE.store(mode as u8, Ordering::Relaxed);
+    }
+
+    const fn from_u8(v : u8) -> Option<Self> { match (v) {
+        0 => Some(Self::None),
+        1 => Some(Self::Ansi16),
+        2 => Some(Self::Ansi256),
+        3 => Some(Self::TrueColour),
+        _ => None
+    } }
+
+    /// Writes the escape code which sets the foreground colour, or background colour if `background`.
+    fn write_colour(self, f : &mut Formatter<'_>, colour : Rgb, background : bool) -> fmt::Result {
+        match (self) {
+            Self::None       => Ok(()),
+            Self::Ansi16     => write!(f, "{ESC}[{}m", nearest_ansi16(colour) + if (background) { 10 } else { 0 }),
+            Self::Ansi256    => write!(f, "{ESC}[{};5;{}m", if (background) { 48 } else { 38 }, nearest_ansi256(colour)),
+            Self::TrueColour => write!(f, "{ESC}[{};2;{};{};{}m", if (background) { 48 } else { 38 }, colour.r, colour.g, colour.b)
+        }
+    }
+
+}
+
+
+/// The foreground codes of the 16 standard console colours, indexed by the legacy code of the closest vanilla colour.
+const ANSI16_CODES : [u8; 16] = [30, 34, 32, 36, 31, 35, 33, 37, 90, 94, 92, 96, 91, 95, 93, 97];
+
+/// The channel values of the 6x6x6 colour cube in the 256 colour palette.
+const ANSI256_CUBE : [u8; 6] = [0, 95, 135, 175, 215, 255];
 
+fn distance(a : Rgb, b : Rgb) -> u32 {
+    let dr = (a.r as i32) - (b.r as i32);
+    let dg = (a.g as i32) - (b.g as i32);
+    let db = (a.b as i32) - (b.b as i32);
+    (dr * dr + dg * dg + db * db) as u32
+}
 
-// TODO: No colour env/setting.
+/// Returns the foreground code of the standard console colour closest to `colour`.
+///
+/// Console colours are matched by the vanilla colours with the same legacy codes, so vanilla colours map exactly.
+fn nearest_ansi16(colour : Rgb) -> u8 {
+    let index = (0..16).min_by_key(|&i| distance(colour, LEGACY_COLOURS[i])).unwrap_or(15);
+    ANSI16_CODES[index]
+}
+
+/// Returns the index of the colour closest to `colour` in the colour cube or greyscale ramp of the 256 colour palette.
+fn nearest_ansi256(colour : Rgb) -> u8 {
+    let cube_index = |v : u8| (0..6).min_by_key(|&i| (ANSI256_CUBE[i] as i32 - v as i32).unsigned_abs()).unwrap_or(0);
+    let (r, g, b,) = (cube_index(colour.r), cube_index(colour.g), cube_index(colour.b),);
+    let cube       = Rgb::new(ANSI256_CUBE[r], ANSI256_CUBE[g], ANSI256_CUBE[b]);
+    // The greyscale ramp runs from 8 to 238 in steps of 10.
+    let average    = ((colour.r as u32 + colour.g as u32 + colour.b as u32) / 3) as u8;
+    let grey_index = (average.saturating_sub(3) / 10).min(23);
+    let grey       = Rgb::splat(8 + grey_index * 10);
+    if (distance(colour, grey) < distance(colour, cube)) {
+        232 + grey_index
+    } else {
+        16 + (r as u8) * 36 + (g as u8) * 6 + (b as u8)
+    }
+}
 
 
 const ESC : &str = "\x1b";
@@ -17,9 +151,16 @@ const ESC : &str = "\x1b";
 impl Display for Text {
     fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result {
         if (self.components.is_empty()) { return Ok(()); }
+        let mode = ColourMode::current();
+        if (mode == ColourMode::None) {
+            for component in &*self.components {
+                write!(f, "{}", component.content)?;
+            }
+            return Ok(());
+        }
         write!(f, "{ESC}[0m")?;
         for component in &*self.components {
-            component.fmt_unwrapped(f)?;
+            component.fmt_unwrapped(f, mode)?;
             write!(f, "{ESC}[0m")?;
         }
         Ok(())
@@ -29,22 +170,24 @@ impl Display for Text {
 
 impl Display for TextComponent {
     fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result {
+        let mode = ColourMode::current();
+        if (mode == ColourMode::None) { return write!(f, "{}", self.content); }
         write!(f, "{ESC}[0m")?;
-        self.fmt_unwrapped(f)?;
+        self.fmt_unwrapped(f, mode)?;
         write!(f, "{ESC}[0m")?;
         Ok(())
     }
 }
 impl TextComponent {
-    fn fmt_unwrapped(&self, f : &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{ESC}[38;2;{};{};{}m", self.style.colour.r, self.style.colour.g, self.style.colour.b)?;
+    fn fmt_unwrapped(&self, f : &mut Formatter<'_>, mode : ColourMode) -> fmt::Result {
+        mode.write_colour(f, self.style.colour, false)?;
         if (self.style.bold) { write!(f, "{ESC}[1m")?; }
         if (self.style.italic) { write!(f, "{ESC}[3m")?; }
         if (self.style.underline) { write!(f, "{ESC}[4m")?; }
         if (self.style.strike) { write!(f, "{ESC}[9m")?; }
         if (self.style.obfuscate) { write!(f, "{ESC}[5m")?; }
         if let Some(colour) = &self.style.shadow {
-            write!(f, "{ESC}[48;2;{};{};{}m", colour.r, colour.g, colour.b)?;
+            mode.write_colour(f, colour.without_alpha(), true)?;
         }
         write!(f, "{}", self.content)?;
         Ok(())
@@ -55,9 +198,82 @@ impl TextComponent {
 impl Display for TextContent {
     fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result {
         match (self) {
-            TextContent::Literal   { text    } => write!(f, "{text}"),
-            TextContent::Translate { key, .. } => write!(f, "{key}"),
-            TextContent::Keybind   { id      } => write!(f, "{id}")
+            TextContent::Literal   { text          } => write!(f, "{text}"),
+            TextContent::Translate { key, ..       } => write!(f, "{key}"),
+            TextContent::Keybind   { id            } => write!(f, "{id}"),
+            TextContent::Score     { score         } => write!(f, "{}", score.name),
+            TextContent::Selector  { selector, ..  } => write!(f, "{selector}"),
+            TextContent::Nbt       { path, ..      } => write!(f, "{path}"),
+            TextContent::Object(TextObject::Atlas  { sprite, .. }) => write!(f, "{sprite}"),
+            TextContent::Object(TextObject::Player { name, ..   }) => write!(f, "{name}")
+        }
+    }
+}
+
+
+impl Text {
+
+    /// Renders this text as a string with no styling.
+    ///
+    /// Translated and keybind components are written as their key or ID.
+    pub fn to_plain(&self) -> String {
+        let mut out = String::new();
+        for component in &*self.components {
+            let _ = write!(out, "{}", component.content);
         }
+        out
     }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::TextFormatted as _;
+
+    struct Colour(ColourMode, Rgb, bool,);
+    impl Display for Colour {
+        fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result { self.0.write_colour(f, self.1, self.2) }
+    }
+
+    #[test]
+    fn ansi16() {
+        for (colour, code,) in LEGACY_COLOURS.into_iter().zip(ANSI16_CODES) {
+            assert_eq!(nearest_ansi16(colour), code);
+        }
+        assert_eq!(nearest_ansi16(Rgb::new(240, 80, 80)), 91);
+        assert_eq!(nearest_ansi16(Rgb::new(10, 0, 150)), 34);
+    }
+
+    #[test]
+    fn ansi256() {
+        assert_eq!(nearest_ansi256(Rgb::BLACK), 16);
+        assert_eq!(nearest_ansi256(Rgb::WHITE), 231);
+        assert_eq!(nearest_ansi256(Rgb::new(95, 135, 175)), 67);
+        assert_eq!(nearest_ansi256(Rgb::splat(95)), 59);
+        assert_eq!(nearest_ansi256(Rgb::splat(8)), 232);
+        assert_eq!(nearest_ansi256(Rgb::splat(128)), 244);
+        assert_eq!(nearest_ansi256(Rgb::splat(238)), 255);
+        assert_eq!(nearest_ansi256(Rgb::new(100, 100, 110)), 242);
+        // Between black and the first grey, the cube is preferred when both are as close.
+        assert_eq!(nearest_ansi256(Rgb::splat(4)), 16);
+        assert_eq!(nearest_ansi256(Rgb::splat(5)), 232);
+    }
+
+    #[test]
+    fn modes() {
+        assert_eq!(Colour(ColourMode::None, Rgb::RED, false).to_string(), "");
+        assert_eq!(Colour(ColourMode::Ansi16, Rgb::RED, false).to_string(), "\x1b[91m");
+        assert_eq!(Colour(ColourMode::Ansi16, Rgb::RED, true).to_string(), "\x1b[101m");
+        assert_eq!(Colour(ColourMode::Ansi256, Rgb::RED, false).to_string(), "\x1b[38;5;203m");
+        assert_eq!(Colour(ColourMode::Ansi256, Rgb::RED, true).to_string(), "\x1b[48;5;203m");
+        assert_eq!(Colour(ColourMode::TrueColour, Rgb::RED, false).to_string(), "\x1b[38;2;255;85;85m");
+        ColourMode::set_current(ColourMode::None);
+        assert_eq!(ColourMode::current(), ColourMode::None);
+        let text = Text::literal("a").red().bold() + Text::translate("b.c");
+        assert_eq!(text.to_string(), "ab.c");
+        assert_eq!(text.components[0].to_string(), "a");
+    }
+
 }
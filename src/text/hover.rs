@@ -0,0 +1,246 @@
+use super::Text;
+use crate::{
+    ident::Ident,
+    item_stack::ItemStack,
+    uuid::Uuid
+};
+use std::borrow::Cow;
+use serde::{
+    Serialize as Ser,
+    Serializer as Serer,
+    Deserialize as Deser,
+    Deserializer as Deserer,
+    de::Error as DeserError
+};
+use syndebug::SynDebug;
+
+
+/// Something to show on a text component hovered.
+#[derive(Clone, Debug, SynDebug)]
+pub enum HoverEvent {
+    /// Show a text tooltip.
+    ShowText(Text),
+    /// Show the tooltip of an item.
+    ShowItem(ItemStack),
+    /// Show the type, UUID, and name of an entity.
+    ShowEntity {
+        /// The type of entity.
+        kind : Ident,
+        /// The UUID of the entity.
+        uuid : Uuid,
+        /// The name of the entity.
+        name : Option<Text>
+    }
+}
+
+
+impl From<Text> for HoverEvent {
+    #[inline]
+    fn from(text : Text) -> Self { Self::ShowText(text) }
+}
+
+impl From<ItemStack> for HoverEvent {
+    #[inline]
+    fn from(item : ItemStack) -> Self { Self::ShowItem(item) }
+}
+
+
+#[derive(Ser)]
+#[serde(tag = "action")]
+enum HoverEventSer<'l> {
+    #[serde(rename = "show_text")]
+    Text {
+        value : &'l Text
+    },
+    #[serde(rename = "show_item")]
+    Item {
+        #[serde(flatten)]
+        item : &'l ItemStack
+    },
+    #[serde(rename = "show_entity")]
+    Entity {
+        id   : &'l Ident,
+        #[serde(serialize_with = "ser_uuid")]
+        uuid : &'l Uuid,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name : Option<&'l Text>
+    }
+}
+
+impl Ser for HoverEvent {
+    fn serialize<S>(&self, serer : S) -> Result<S::Ok, S::Error>
+    where
+        S : Serer
+    {
+        match (self) {
+            Self::ShowText(text)                  => HoverEventSer::Text { value : text },
+            Self::ShowItem(item)                  => HoverEventSer::Item { item },
+            Self::ShowEntity { kind, uuid, name } => HoverEventSer::Entity { id : kind, uuid, name : name.as_ref() }
+        }.serialize(serer)
+    }
+}
+
+fn ser_uuid<S>(uuid : &&Uuid, serer : S) -> Result<S::Ok, S::Error>
+where
+    S : Serer
+{ serer.collect_str(uuid) }
+
+
+/// A hover event in either the modern layout, or the layout used before 1.21.5, where the shown
+///  value is nested under `contents`.
+#[derive(Deser)]
+#[serde(tag = "action")]
+enum HoverEventDeser {
+    #[serde(rename = "show_text")]
+    Text {
+        #[serde(alias = "contents")]
+        value : Text
+    },
+    #[serde(rename = "show_item")]
+    Item {
+        contents : Option<LegacyItem>,
+        id       : Option<Ident>,
+        count    : Option<u32>
+    },
+    #[serde(rename = "show_entity")]
+    Entity {
+        contents : Option<LegacyEntity>,
+        id       : Option<Ident>,
+        #[serde(deserialize_with = "deser_some_uuid", default)]
+        uuid     : Option<Uuid>,
+        name     : Option<Text>
+    }
+}
+
+#[derive(Deser)]
+#[serde(untagged)]
+enum LegacyItem {
+    Id(Ident),
+    Stack {
+        id    : Ident,
+        count : Option<u32>
+    }
+}
+
+#[derive(Deser)]
+struct LegacyEntity {
+    #[serde(rename = "type")]
+    kind : Ident,
+    #[serde(deserialize_with = "deser_uuid")]
+    id   : Uuid,
+    name : Option<Text>
+}
+
+impl<'de> Deser<'de> for HoverEvent {
+    fn deserialize<D>(deserer : D) -> Result<Self, D::Error>
+    where
+        D : Deserer<'de>
+    {
+        Ok(match (HoverEventDeser::deserialize(deserer)?) {
+            HoverEventDeser::Text { value } => Self::ShowText(value),
+            HoverEventDeser::Item { contents, id, count } => Self::ShowItem(match (contents, id,) {
+                (Some(LegacyItem::Id(id)), _,)              => ItemStack { id, count : 1 },
+                (Some(LegacyItem::Stack { id, count }), _,) => ItemStack { id, count : count.unwrap_or(1) },
+                (None, Some(id),)                           => ItemStack { id, count : count.unwrap_or(1) },
+                (None, None,)                               => { return Err(D::Error::missing_field("id")); }
+            }),
+            HoverEventDeser::Entity { contents, id, uuid, name } => match (contents, id, uuid,) {
+                (Some(LegacyEntity { kind, id, name }), _, _,) => Self::ShowEntity { kind, uuid : id, name },
+                (None, Some(kind), Some(uuid),)               => Self::ShowEntity { kind, uuid, name },
+                (None, None, _,)                              => { return Err(D::Error::missing_field("id")); },
+                (None, _, None,)                              => { return Err(D::Error::missing_field("uuid")); }
+            }
+        })
+    }
+}
+
+/// Reads a UUID from a hyphenated string, or from an array of four integers.
+fn deser_uuid<'de, D>(deserer : D) -> Result<Uuid, D::Error>
+where
+    D : Deserer<'de>
+{
+    #[derive(Deser)]
+    #[serde(untagged)]
+    enum UuidForm<'l> {
+        String(Cow<'l, str>),
+        Ints([i32; 4])
+    }
+    Ok(match (UuidForm::deserialize(deserer)?) {
+        UuidForm::String(uuid) => Uuid::parse_str(&uuid).map_err(D::Error::custom)?,
+        UuidForm::Ints(ints)   => uuid_from_ints(ints)
+    })
+}
+
+#[inline]
+fn deser_some_uuid<'de, D>(deserer : D) -> Result<Option<Uuid>, D::Error>
+where
+    D : Deserer<'de>
+{ deser_uuid(deserer).map(Some) }
+
+/// Converts a UUID from the four integer form used by vanilla.
+pub(super) fn uuid_from_ints(ints : [i32; 4]) -> Uuid {
+    Uuid::from_u128(ints.into_iter().fold(0, |v, int| (v << 32) | (int.cast_unsigned() as u128)))
+}
+
+/// Converts a UUID to the four integer form used by vanilla.
+pub(super) fn uuid_to_ints(uuid : &Uuid) -> [i32; 4] {
+    let v = uuid.as_u128();
+    [(v >> 96) as i32, (v >> 64) as i32, (v >> 32) as i32, v as i32]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nbt::{ NbtElement, to_element, from_element },
+        text::TextFormatted as _
+    };
+
+    const UUID : &str = "01234567-89ab-cdef-fedc-ba9876543210";
+
+    fn hover(hover_event : &str) -> HoverEvent {
+        let text = Text::from_nbt(&format!("{{text:\"x\",hover_event:{hover_event}}}").parse::<NbtElement>().unwrap()).unwrap();
+        text.components[0].style.on_hover.clone().unwrap()
+    }
+
+    #[test]
+    fn modern_round_trip() {
+        let events = [
+            HoverEvent::ShowText(Text::literal("tip")),
+            HoverEvent::ShowItem(ItemStack { id : Ident::new("minecraft:stone"), count : 3 }),
+            HoverEvent::ShowEntity { kind : Ident::new("minecraft:pig"), uuid : Uuid::parse_str(UUID).unwrap(), name : Some(Text::literal("Pig")) },
+            HoverEvent::ShowEntity { kind : Ident::new("minecraft:pig"), uuid : Uuid::parse_str(UUID).unwrap(), name : None }
+        ];
+        for event in events {
+            let text = Text::literal("x").on_hover(event.clone());
+            assert_eq!(format!("{:?}", Text::from_nbt(&text.to_nbt().unwrap()).unwrap()), format!("{text:?}"));
+            assert_eq!(format!("{:?}", from_element::<HoverEvent>(&to_element(&event).unwrap()).unwrap()), format!("{event:?}"));
+        }
+    }
+
+    #[test]
+    fn uuid_forms() {
+        let uuid = Uuid::parse_str(UUID).unwrap();
+        assert_eq!(uuid_from_ints(uuid_to_ints(&uuid)), uuid);
+        assert_eq!(uuid_to_ints(&uuid), [0x01234567, 0x89abcdef_u32.cast_signed(), 0xfedcba98_u32.cast_signed(), 0x76543210]);
+        for id in [format!("{UUID:?}"), "[I;19088743,-1985229329,-19088744,1985229328]".to_string()] {
+            let HoverEvent::ShowEntity { uuid : modern, .. } = hover(&format!("{{action:\"show_entity\",id:\"minecraft:pig\",uuid:{id}}}"))
+                else { panic!("expected show_entity"); };
+            let HoverEvent::ShowEntity { uuid : legacy, .. } = hover(&format!("{{action:\"show_entity\",contents:{{type:\"minecraft:pig\",id:{id}}}}}"))
+                else { panic!("expected show_entity"); };
+            assert_eq!((modern, legacy,), (uuid, uuid,));
+        }
+    }
+
+    #[test]
+    fn legacy_layout() {
+        assert!(matches!(hover(r#"{action:"show_text",contents:"tip"}"#), HoverEvent::ShowText(text) if format!("{text:?}") == format!("{:?}", Text::literal("tip"))));
+        assert!(matches!(hover(r#"{action:"show_item",contents:"minecraft:apple"}"#), HoverEvent::ShowItem(ItemStack { id, count : 1 }) if id == Ident::new("minecraft:apple")));
+        assert!(matches!(hover(r#"{action:"show_item",contents:{id:"minecraft:apple",count:5}}"#), HoverEvent::ShowItem(ItemStack { id, count : 5 }) if id == Ident::new("minecraft:apple")));
+        assert!(matches!(hover(&format!(r#"{{action:"show_entity",contents:{{type:"minecraft:pig",id:"{UUID}",name:"Pig"}}}}"#)),
+            HoverEvent::ShowEntity { kind, name : Some(_), .. } if kind == Ident::new("minecraft:pig")
+        ));
+    }
+
+}
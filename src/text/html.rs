@@ -0,0 +1,124 @@
+use super::{
+    Text,
+    HoverEvent
+};
+use crate::action::Action;
+use core::fmt::Write as _;
+
+
+impl Text {
+
+    /// Renders this text as HTML, with each component in a `<span>` with inline styles.
+    ///
+    /// Components which open an `http` or `https` URL on clicked are wrapped in a link, and text tooltips are
+    ///  written as a plain `title`. Obfuscated components are given the `obfuscated` class, to be styled by the page.
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        for component in &*self.components {
+            let content = component.content.to_string();
+            if (content.is_empty()) { continue; }
+            let style = &component.style;
+            let url   = match (&style.on_click) {
+                Some(Action::OpenUrl { url }) if (is_web_url(url)) => Some(url),
+                _                                                  => None
+            };
+            if let Some(url) = url {
+                out.push_str("<a href=\"");
+                write_escaped(&mut out, url);
+                out.push_str("\" target=\"_blank\" rel=\"noopener noreferrer\">");
+            }
+            let _ = write!(out, "<span style=\"color:#{:06x}", style.colour.to_u32());
+            if (style.bold) { out.push_str(";font-weight:bold"); }
+            if (style.italic) { out.push_str(";font-style:italic"); }
+            match (style.underline, style.strike,) {
+                (true,  true,)  => { out.push_str(";text-decoration:underline line-through"); },
+                (true,  false,) => { out.push_str(";text-decoration:underline"); },
+                (false, true,)  => { out.push_str(";text-decoration:line-through"); },
+                (false, false,) => { }
+            }
+            if let Some(shadow) = &style.shadow && shadow.a != 0 {
+                let _ = write!(out, ";text-shadow:1px 1px rgba({},{},{},{:.3})",
+                    shadow.r, shadow.g, shadow.b, (shadow.a as f32) / 255.0
+                );
+            }
+            out.push('"');
+            if (style.obfuscate) { out.push_str(" class=\"obfuscated\""); }
+            if let Some(HoverEvent::ShowText(tooltip)) = &style.on_hover {
+                out.push_str(" title=\"");
+                write_escaped(&mut out, &tooltip.to_plain());
+                out.push('"');
+            }
+            out.push('>');
+            for line in content.split_inclusive('\n') {
+                match (line.strip_suffix('\n')) {
+                    Some(line) => { write_escaped(&mut out, line); out.push_str("<br>"); },
+                    None       => { write_escaped(&mut out, line); }
+                }
+            }
+            out.push_str("</span>");
+            if (url.is_some()) { out.push_str("</a>"); }
+        }
+        out
+    }
+
+}
+
+
+/// Returns `true` if a URL is safe to link to from a page.
+pub(super) fn is_web_url(url : &str) -> bool {
+    let scheme = url.split_once(':').map_or("", |(scheme, _,)| scheme);
+    scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+}
+
+/// Writes a string with HTML special characters escaped, so that it can be used in text or an attribute value.
+fn write_escaped(out : &mut String, text : &str) {
+    for ch in text.chars() {
+        match (ch) {
+            '&'  => out.push_str("&amp;"),
+            '<'  => out.push_str("&lt;"),
+            '>'  => out.push_str("&gt;"),
+            '"'  => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            ch   => out.push(ch)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::TextFormatted as _;
+
+    #[test]
+    fn escaping() {
+        let text = Text::literal(r#"<b>"a" & 'b'</b>"#).tooltip(r#"<"t">"#);
+        assert_eq!(text.to_html(), concat!(
+            r#"<span style="color:#ffffff" title="&lt;&quot;t&quot;&gt;">"#,
+            "&lt;b&gt;&quot;a&quot; &amp; &#39;b&#39;&lt;/b&gt;</span>"
+        ));
+    }
+
+    #[test]
+    fn line_breaks() {
+        let text = Text::literal("a\nb\n").bold();
+        assert_eq!(text.to_html(), r#"<span style="color:#ffffff;font-weight:bold">a<br>b<br></span>"#);
+    }
+
+    #[test]
+    fn links() {
+        assert!(is_web_url("https://example.com") && is_web_url("HTTP://example.com"));
+        assert!(! is_web_url("javascript:alert(1)"));
+        assert!(! is_web_url("JavaScript:alert(1)"));
+        assert!(! is_web_url("data:text/html,x"));
+        assert!(! is_web_url("//example.com"));
+        let text = Text::literal("a").on_click(Action::OpenUrl { url : "https://example.com/?a=\"b\"".into() });
+        assert_eq!(text.to_html(), concat!(
+            r#"<a href="https://example.com/?a=&quot;b&quot;" target="_blank" rel="noopener noreferrer">"#,
+            r#"<span style="color:#ffffff">a</span></a>"#
+        ));
+        let text = Text::literal("a").on_click(Action::OpenUrl { url : "javascript:alert(1)".into() });
+        assert_eq!(text.to_html(), r#"<span style="color:#ffffff">a</span>"#);
+    }
+
+}
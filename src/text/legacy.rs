@@ -0,0 +1,181 @@
+use super::{
+    Text,
+    TextComponent,
+    TextContent,
+    TextStyle
+};
+use crate::colour::Rgb;
+use core::{
+    fmt::Write as _,
+    mem
+};
+use std::borrow::Cow;
+
+
+/// The vanilla colours, indexed by their legacy code.
+pub(super) const LEGACY_COLOURS : [Rgb; 16] = [
+    Rgb::BLACK, Rgb::DARK_BLUE, Rgb::DARK_GREEN, Rgb::DARK_CYAN,
+    Rgb::DARK_RED, Rgb::PURPLE, Rgb::ORANGE, Rgb::GREY,
+    Rgb::DARK_GREY, Rgb::BLUE, Rgb::GREEN, Rgb::CYAN,
+    Rgb::RED, Rgb::PINK, Rgb::YELLOW, Rgb::WHITE
+];
+
+
+impl Text {
+
+    /// Parses a string containing legacy formatting codes, prefixed by `marker`.
+    ///
+    /// `marker` is usually `§`, or `&` in configuration files.
+    /// Codes are case insensitive: `0`-`f` set a vanilla colour, `k`-`o` enable a format, and `r` resets all styling.
+    /// `x` followed by six prefixed hexadecimal digits (`§x§r§r§g§g§b§b`) sets any colour.
+    /// Like in vanilla, setting a colour also disables all formats.
+    /// Unknown or incomplete codes are kept as literal text.
+    pub fn from_legacy(text : &str, marker : char) -> Self {
+        let mut components = Vec::new();
+        let mut style      = TextStyle::EMPTY;
+        let mut literal    = String::new();
+        let mut chars      = text.chars();
+        while let Some(ch) = chars.next() {
+            if (ch != marker) {
+                literal.push(ch);
+                continue;
+            }
+            let rest = chars.clone();
+            let Some(code) = chars.next()
+                else { literal.push(ch); break; };
+            let mut next_style = style.clone();
+            // `None` for format codes, and `Some(None)` for unknown or incomplete codes.
+            let     colour     = match (code.to_ascii_lowercase()) {
+                'k' => { next_style.obfuscate = true; None },
+                'l' => { next_style.bold      = true; None },
+                'm' => { next_style.strike    = true; None },
+                'n' => { next_style.underline = true; None },
+                'o' => { next_style.italic    = true; None },
+                'r' => { next_style = TextStyle::EMPTY; None },
+                'x' => Some(read_legacy_hex(&mut chars, marker)),
+                code => Some(code.to_digit(16).map(|digit| LEGACY_COLOURS[digit as usize]))
+            };
+            match (colour) {
+                None               => { },
+                Some(Some(colour)) => { next_style = TextStyle { colour, ..TextStyle::EMPTY }; },
+                Some(None)         => {
+                    literal.push(ch);
+                    chars = rest;
+                    continue;
+                }
+            }
+            if (! literal.is_empty()) {
+                components.push(TextComponent {
+                    content : TextContent::Literal { text : Cow::Owned(mem::take(&mut literal)) },
+                    style
+                });
+            }
+            style = next_style;
+        }
+        if (! literal.is_empty()) {
+            components.push(TextComponent { content : TextContent::Literal { text : Cow::Owned(literal) }, style });
+        }
+        Self { components : Cow::Owned(components) }
+    }
+
+    /// Renders this text as a string containing legacy formatting codes, prefixed by `marker`.
+    ///
+    /// Colours which are not vanilla colours are written in the `§x§r§r§g§g§b§b` form.
+    /// Styling which legacy codes can not represent, such as fonts and events, is left out.
+    /// Translated and keybind components are written as their key or ID.
+    pub fn to_legacy(&self, marker : char) -> String {
+        let mut out   = String::new();
+        let mut style = &TextStyle::EMPTY;
+        for component in &*self.components {
+            let next = &component.style;
+            let formats_removed = (style.obfuscate && ! next.obfuscate) || (style.bold && ! next.bold)
+                || (style.strike && ! next.strike) || (style.underline && ! next.underline) || (style.italic && ! next.italic);
+            // Colour codes disable all formats, so every enabled format has to be written again after one.
+            let reset = next.colour != style.colour || formats_removed;
+            if (reset) {
+                if (next.colour == Rgb::WHITE) {
+                    out.push(marker);
+                    out.push('r');
+                } else if let Some(digit) = LEGACY_COLOURS.iter().position(|&colour| colour == next.colour) {
+                    out.push(marker);
+                    out.push(char::from_digit(digit as u32, 16).unwrap());
+                } else {
+                    out.push(marker);
+                    out.push('x');
+                    for digit in format!("{:06x}", next.colour.to_u32()).chars() {
+                        out.push(marker);
+                        out.push(digit);
+                    }
+                }
+            }
+            for (enabled, was_enabled, code,) in [
+                (next.obfuscate, style.obfuscate, 'k',),
+                (next.bold,      style.bold,      'l',),
+                (next.strike,    style.strike,    'm',),
+                (next.underline, style.underline, 'n',),
+                (next.italic,    style.italic,    'o',)
+            ] {
+                if (enabled && (reset || ! was_enabled)) {
+                    out.push(marker);
+                    out.push(code);
+                }
+            }
+            let _ = write!(out, "{}", component.content);
+            style = next;
+        }
+        out
+    }
+
+}
+
+
+/// Reads the six prefixed hexadecimal digits following a `§x` code.
+///
+/// `chars` is only advanced if all of the digits were read.
+fn read_legacy_hex(chars : &mut core::str::Chars<'_>, marker : char) -> Option<Rgb> {
+    let mut rest  = chars.clone();
+    let mut value = 0;
+    for _ in 0..6 {
+        if (rest.next()? != marker) { return None; }
+        value = (value << 4) | rest.next()?.to_digit(16)?;
+    }
+    *chars = rest;
+    Some(Rgb::from_u32(value))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::TextFormatted as _;
+
+    #[test]
+    fn parse() {
+        let text = Text::from_legacy("§cred §Lbold§r plain", '§');
+        assert_eq!(format!("{text:?}"), format!("{:?}", Text::literal("red ").red() + Text::literal("bold").red().bold() + Text::literal(" plain")));
+        // Colours disable formats.
+        let text = Text::from_legacy("&l&ab", '&');
+        assert_eq!(format!("{text:?}"), format!("{:?}", Text::literal("b").green()));
+        let text = Text::from_legacy("&x&1&2&3&a&b&cx", '&');
+        assert_eq!(text.components[0].style.colour, Rgb::from_u32(0x123abc));
+        // Unknown and incomplete codes are kept.
+        assert_eq!(Text::from_legacy("&z&x&1&g end&", '&').to_plain(), "&z&x&g end&");
+        assert_eq!(Text::from_legacy("§r§l", '§').components.len(), 0);
+    }
+
+    #[test]
+    fn render() {
+        assert_eq!(Text::literal("plain").to_legacy('§'), "plain");
+        let text = Text::literal("a").red().bold() + Text::literal("b").red() + Text::literal("c").red().italic();
+        assert_eq!(text.to_legacy('&'), "&c&la&cb&oc");
+        assert_eq!((Text::literal("a").colour(Rgb::from_u32(0x123abc)) + Text::literal("b")).to_legacy('&'), "&x&1&2&3&a&b&ca&rb");
+        // Rendered text parses back to the same components.
+        for text in [
+            Text::literal("a").bold().underline() + Text::literal("b").underline() + Text::literal("c").colour(Rgb::from_u32(0x010203)).obfuscate(),
+            Text::literal("x").strike() + Text::literal("y").dark_grey()
+        ] {
+            assert_eq!(format!("{:?}", Text::from_legacy(&text.to_legacy('§'), '§')), format!("{text:?}"));
+        }
+    }
+
+}
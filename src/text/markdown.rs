@@ -0,0 +1,117 @@
+use super::Text;
+use super::html::is_web_url;
+use crate::action::Action;
+
+
+impl Text {
+
+    /// Renders this text as Discord-flavoured Markdown.
+    ///
+    /// Bold, italic, underlined and crossed out components are marked with `**`, `*`, `__` and `~~`, and obfuscated
+    ///  components are written as spoilers. Components which open an `http` or `https` URL on clicked are written as
+    ///  masked links. Colours, fonts and other events can not be represented, and are left out. Mentions, such as
+    ///  `@everyone` and `<@id>`, are broken up so that they do not notify anyone.
+    pub fn to_markdown(&self) -> String {
+        let mut out  = String::new();
+        // The markers which have been opened and not yet closed, innermost last.
+        let mut open = Vec::new();
+        // Whitespace at the end of the last component, which is written after any markers are closed.
+        let mut trailing = String::new();
+        for component in &*self.components {
+            let content = component.content.to_string();
+            // Markers next to whitespace are not recognised, so whitespace is moved outside of them.
+            let core    = content.trim();
+            if (core.is_empty()) {
+                trailing.push_str(&content);
+                continue;
+            }
+            let leading = &content[..(content.len() - content.trim_start().len())];
+            let style   = &component.style;
+            let wanted  = [
+                (style.bold,      "**",),
+                (style.italic,    "*",),
+                (style.underline, "__",),
+                (style.strike,    "~~",),
+                (style.obfuscate, "||",)
+            ].into_iter().filter_map(|(enabled, marker,)| enabled.then_some(marker)).collect::<Vec<_>>();
+            // Markers are closed in the reverse order that they were opened, so only those outside of
+            //  the first one which is no longer wanted can be kept open.
+            let kept = open.iter().zip(&wanted).take_while(|(a, b,)| a == b).count();
+            while (open.len() > kept) {
+                out.push_str(open.pop().unwrap());
+            }
+            out.push_str(&trailing);
+            out.push_str(leading);
+            trailing.clear();
+            trailing.push_str(&content[(content.trim_end().len())..]);
+            for &marker in &wanted[kept..] {
+                out.push_str(marker);
+                open.push(marker);
+            }
+            match (&style.on_click) {
+                Some(Action::OpenUrl { url }) if (is_web_url(url)) => {
+                    out.push('[');
+                    write_escaped(&mut out, core);
+                    out.push_str("](<");
+                    out.push_str(&url.replace('>', "%3E"));
+                    out.push_str(">)");
+                },
+                _ => { write_escaped(&mut out, core); }
+            }
+        }
+        while let Some(marker) = open.pop() {
+            out.push_str(marker);
+        }
+        out.push_str(&trailing);
+        out
+    }
+
+}
+
+
+/// Writes a string with Markdown special characters escaped.
+///
+/// A zero-width space is written after each `@`, as mentions can not be escaped with a backslash.
+fn write_escaped(out : &mut String, text : &str) {
+    for ch in text.chars() {
+        if (matches!(ch, '\\' | '*' | '_' | '~' | '|' | '`' | '<' | '>' | '#' | '[' | ']' | '(' | ')')) {
+            out.push('\\');
+        }
+        out.push(ch);
+        if (ch == '@') {
+            out.push('\u{200B}');
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::TextFormatted as _;
+
+    fn link(text : &'static str, url : &'static str) -> Text {
+        Text::literal(text).on_click(Action::OpenUrl { url : url.into() })
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(Text::literal(r"*a_b* [c](d) `e` \ #f").to_markdown(), r"\*a\_b\* \[c\]\(d\) \`e\` \\ \#f");
+        assert_eq!(Text::literal("@everyone @here <@123>").to_markdown(), "@\u{200B}everyone @\u{200B}here \\<@\u{200B}123\\>");
+    }
+
+    #[test]
+    fn nesting() {
+        let text = Text::literal("a ").bold() + Text::literal("b").bold().italic() + Text::literal(" c").italic() + Text::literal(" d");
+        assert_eq!(text.to_markdown(), "**a *b*** *c* d");
+        let text = Text::literal("a").strike().obfuscate() + Text::literal("b").strike() + Text::literal("c").underline();
+        assert_eq!(text.to_markdown(), "~~||a||b~~__c__");
+    }
+
+    #[test]
+    fn links() {
+        assert_eq!(link("a]b", "https://example.com/>").to_markdown(), r"[a\]b](<https://example.com/%3E>)");
+        assert_eq!(link("a", "javascript:alert(1)").to_markdown(), "a");
+    }
+
+}
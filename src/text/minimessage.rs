@@ -0,0 +1,779 @@
+use super::{
+    Text,
+    TextComponent,
+    TextContent,
+    TextStyle,
+    TextScore,
+    TextNbtSource,
+    TextObject,
+    HoverEvent
+};
+use crate::{
+    action::Action,
+    colour::{ Rgb, Argb },
+    ident::Ident,
+    item_stack::ItemStack,
+    uuid::Uuid
+};
+use core::fmt::Write as _;
+use std::borrow::Cow;
+
+
+/// The vanilla colours, by their MiniMessage names.
+const NAMED_COLOURS : [(&str, Rgb,); 16] = [
+    ("black",        Rgb::BLACK,),
+    ("dark_blue",    Rgb::DARK_BLUE,),
+    ("dark_green",   Rgb::DARK_GREEN,),
+    ("dark_aqua",    Rgb::DARK_CYAN,),
+    ("dark_red",     Rgb::DARK_RED,),
+    ("dark_purple",  Rgb::PURPLE,),
+    ("gold",         Rgb::ORANGE,),
+    ("gray",         Rgb::GREY,),
+    ("dark_gray",    Rgb::DARK_GREY,),
+    ("blue",         Rgb::BLUE,),
+    ("green",        Rgb::GREEN,),
+    ("aqua",         Rgb::CYAN,),
+    ("red",          Rgb::RED,),
+    ("light_purple", Rgb::PINK,),
+    ("yellow",       Rgb::YELLOW,),
+    ("white",        Rgb::WHITE,)
+];
+
+
+impl Text {
+
+    /// Parses a string of MiniMessage-style markup.
+    ///
+    /// Supported tags are:
+    /// - colours: `<red>`, `<#ff5555>`, `<color:red>` (also `colour` and `c`), and `<shadow:#ff555580>`.
+    /// - decorations: `<bold>`, `<italic>`, `<underlined>`, `<strikethrough>`, and `<obfuscated>`,
+    ///   their short forms `b`, `i`/`em`, `u`, `st`, and `obf`, and negations such as `<!bold>`.
+    /// - `<hover:show_text:'...'>`, with the tooltip parsed as markup, `<hover:show_item:id:count>`, where the count
+    ///   is optional, and `<hover:show_entity:type:uuid:name>`, where the name is optional and parsed as markup.
+    /// - `<click:action:value>`, for `open_url`, `run_command`, `suggest_command`, `copy_to_clipboard`, and `change_page`.
+    /// - `<insert:text>` and `<font:namespace:path>`, where the namespace defaults to `minecraft`.
+    /// - `<gradient:colour:colour:...>`, which colours each character of its contents.
+    /// - `<lang:key:args...>`, `<lang_or:key:fallback:args...>`, and `<key:id>`, with arguments parsed as markup.
+    /// - `<score:name:objective>`, `<selector:selector:separator>`, and `<nbt:block|entity|storage:id:path:separator:interpret>`,
+    ///   where the separator and `interpret` flag are optional.
+    /// - `<sprite:atlas:sprite>`, where the atlas is optional, and `<head:name:hat>`, where the hat flag is optional.
+    /// - `<newline>` (or `<br>`) and `<reset>`.
+    ///
+    /// Tags are closed by `</name>`, which also closes any tags opened after it, or `</>`, which closes the last tag.
+    /// Arguments may be quoted with `'` or `"` to contain `:` or `>`. `\` escapes a `<`, a quote, or itself.
+    /// Like MiniMessage, anything which is not a valid tag is kept as literal text, and unmatched closing tags are ignored.
+    pub fn from_minimessage(markup : &str) -> Self {
+        let mut parser = MiniParser {
+            frames     : vec![MiniFrame {
+                names       : Vec::new(),
+                style       : TextStyle::EMPTY,
+                gradient    : None,
+                start       : 0,
+                coloured_by : None
+            }],
+            components : Vec::new(),
+            literal    : String::new()
+        };
+        let mut rest = markup;
+        while let Some(ch) = rest.chars().next() {
+            rest = &rest[ch.len_utf8()..];
+            if (ch == '\\' && (rest.starts_with('<') || rest.starts_with('\\'))) {
+                parser.literal.push_str(&rest[..1]);
+                rest = &rest[1..];
+                continue;
+            }
+            if (ch == '<') && let Some((tag, after,)) = split_tag(rest) && parser.tag(tag) {
+                rest = after;
+                continue;
+            }
+            parser.literal.push(ch);
+        }
+        parser.close_to(1);
+        Self { components : Cow::Owned(parser.components.into_iter().map(|(component, _,)| component).collect()) }
+    }
+
+    /// Renders this text as MiniMessage-style markup, which [`Text::from_minimessage`] can parse.
+    ///
+    /// Each styled component is wrapped in its own tags. Click actions which can not be written as markup, such
+    ///  as opening dialogs, are left out.
+    pub fn to_minimessage(&self) -> String {
+        let mut out = String::new();
+        for component in &*self.components {
+            component.write_minimessage(&mut out);
+        }
+        out
+    }
+
+}
+
+
+impl TextComponent {
+
+    fn write_minimessage(&self, out : &mut String) {
+        let style     = &self.style;
+        let mut close = Vec::new();
+        if (style.colour != Rgb::WHITE) {
+            match (NAMED_COLOURS.iter().find(|(_, colour,)| *colour == style.colour)) {
+                Some((name, _,)) => { let _ = write!(out, "<{name}>"); close.push(*name); },
+                None             => { let _ = write!(out, "<#{:06x}>", style.colour.to_u32()); close.push("color"); }
+            }
+        }
+        if let Some(shadow) = &style.shadow {
+            let _ = write!(out, "<shadow:#{:02x}{:02x}{:02x}{:02x}>", shadow.r, shadow.g, shadow.b, shadow.a);
+            close.push("shadow");
+        }
+        for (enabled, name,) in [
+            (style.bold,      "bold",),
+            (style.italic,    "italic",),
+            (style.underline, "underlined",),
+            (style.strike,    "strikethrough",),
+            (style.obfuscate, "obfuscated",)
+        ] {
+            if (enabled) {
+                let _ = write!(out, "<{name}>");
+                close.push(name);
+            }
+        }
+        if let Some(font) = &style.font {
+            let _ = write!(out, "<font:{font}>");
+            close.push("font");
+        }
+        if let Some(insertion) = &style.insertion {
+            out.push_str("<insert:");
+            write_quoted(out, insertion);
+            out.push('>');
+            close.push("insert");
+        }
+        let click = match (&style.on_click) {
+            Some(Action::OpenUrl        { url     }) => Some(("open_url",          Cow::Borrowed(&**url),)),
+            Some(Action::RunCommand     { command }) => Some(("run_command",       Cow::Borrowed(&**command),)),
+            Some(Action::SuggestCommand { command }) => Some(("suggest_command",   Cow::Borrowed(&**command),)),
+            Some(Action::SetClipboard   { text    }) => Some(("copy_to_clipboard", Cow::Borrowed(&**text),)),
+            Some(Action::SetBookPage    { page    }) => Some(("change_page",       Cow::Owned(page.to_string()),)),
+            _                                        => None
+        };
+        if let Some((action, value,)) = click {
+            let _ = write!(out, "<click:{action}:");
+            write_quoted(out, &value);
+            out.push('>');
+            close.push("click");
+        }
+        if let Some(on_hover) = &style.on_hover {
+            match (on_hover) {
+                HoverEvent::ShowText(tooltip) => {
+                    out.push_str("<hover:show_text:");
+                    write_quoted(out, &tooltip.to_minimessage());
+                },
+                HoverEvent::ShowItem(item) => {
+                    out.push_str("<hover:show_item:");
+                    write_quoted(out, item.id.as_str());
+                    if (item.count != 1) { let _ = write!(out, ":{}", item.count); }
+                },
+                HoverEvent::ShowEntity { kind, uuid, name } => {
+                    out.push_str("<hover:show_entity:");
+                    write_quoted(out, kind.as_str());
+                    let _ = write!(out, ":{uuid}");
+                    if let Some(name) = name {
+                        out.push(':');
+                        write_quoted(out, &name.to_minimessage());
+                    }
+                }
+            }
+            out.push('>');
+            close.push("hover");
+        }
+        match (&self.content) {
+            TextContent::Literal { text } => {
+                for ch in text.chars() {
+                    if (matches!(ch, '<' | '\\')) { out.push('\\'); }
+                    out.push(ch);
+                }
+            },
+            TextContent::Translate { key, fallback, with } => {
+                match (fallback) {
+                    Some(fallback) => {
+                        out.push_str("<lang_or:");
+                        write_quoted(out, key);
+                        out.push(':');
+                        write_quoted(out, fallback);
+                    },
+                    None => {
+                        out.push_str("<lang:");
+                        write_quoted(out, key);
+                    }
+                }
+                for arg in &**with {
+                    out.push(':');
+                    write_quoted(out, &arg.to_minimessage());
+                }
+                out.push('>');
+            },
+            TextContent::Keybind { id } => {
+                out.push_str("<key:");
+                write_quoted(out, id);
+                out.push('>');
+            },
+            TextContent::Score { score } => {
+                out.push_str("<score:");
+                write_quoted(out, &score.name);
+                out.push(':');
+                write_quoted(out, &score.objective);
+                out.push('>');
+            },
+            TextContent::Selector { selector, separator } => {
+                out.push_str("<selector:");
+                write_quoted(out, selector);
+                if let Some(separator) = separator {
+                    out.push(':');
+                    write_quoted(out, &separator.to_minimessage());
+                }
+                out.push('>');
+            },
+            TextContent::Nbt { path, interpret, separator, source } => {
+                let (source, id,) = match (source) {
+                    TextNbtSource::Block(pos)       => ("block", Cow::Borrowed(&**pos),),
+                    TextNbtSource::Entity(selector) => ("entity", Cow::Borrowed(&**selector),),
+                    TextNbtSource::Storage(id)      => ("storage", Cow::Owned(id.to_string()),)
+                };
+                let _ = write!(out, "<nbt:{source}:");
+                write_quoted(out, &id);
+                out.push(':');
+                write_quoted(out, path);
+                if (separator.is_some() || *interpret) {
+                    out.push(':');
+                    write_quoted(out, &separator.as_ref().map_or_else(String::new, Text::to_minimessage));
+                }
+                if (*interpret) { out.push_str(":interpret"); }
+                out.push('>');
+            },
+            TextContent::Object(TextObject::Atlas { atlas, sprite }) => {
+                out.push_str("<sprite:");
+                if let Some(atlas) = atlas {
+                    write_quoted(out, atlas.as_str());
+                    out.push(':');
+                }
+                write_quoted(out, sprite.as_str());
+                out.push('>');
+            },
+            TextContent::Object(TextObject::Player { name, hat }) => {
+                out.push_str("<head:");
+                write_quoted(out, name);
+                if (! hat) { out.push_str(":false"); }
+                out.push('>');
+            }
+        }
+        for name in close.into_iter().rev() {
+            let _ = write!(out, "</{name}>");
+        }
+    }
+
+}
+
+/// Writes a quoted tag argument.
+fn write_quoted(out : &mut String, value : &str) {
+    out.push('\'');
+    for ch in value.chars() {
+        if (matches!(ch, '\'' | '\\')) { out.push('\\'); }
+        out.push(ch);
+    }
+    out.push('\'');
+}
+
+
+/// A tag which is open while parsing markup.
+struct MiniFrame {
+    /// Names which close this tag.
+    names       : Vec<&'static str>,
+    /// The style of text within this tag.
+    style       : TextStyle,
+    /// The colours of the gradient started by this tag.
+    gradient    : Option<Vec<Rgb>>,
+    /// The index of the first component within this tag.
+    start       : usize,
+    /// The index of the frame of the gradient which colours text within this tag.
+    coloured_by : Option<usize>
+}
+
+struct MiniParser {
+    frames     : Vec<MiniFrame>,
+    /// Parsed components, with the index of the frame of the gradient which colours them.
+    components : Vec<(TextComponent, Option<usize>,)>,
+    /// Literal text which has not yet been pushed as a component.
+    literal    : String
+}
+
+impl MiniParser {
+
+    #[inline]
+    fn frame(&self) -> &MiniFrame { self.frames.last().unwrap() }
+
+    /// Pushes any pending literal text as a component.
+    fn flush(&mut self) {
+        if (self.literal.is_empty()) { return; }
+        let text = Cow::Owned(core::mem::take(&mut self.literal));
+        self.push(TextContent::Literal { text });
+    }
+
+    fn push(&mut self, content : TextContent) {
+        let frame = self.frame();
+        let component = TextComponent { content, style : frame.style.clone() };
+        let coloured_by = frame.coloured_by;
+        self.components.push((component, coloured_by,));
+    }
+
+    /// Opens a tag which modifies the current style.
+    fn open<F>(&mut self, names : Vec<&'static str>, gradient : Option<Vec<Rgb>>, f : F)
+    where
+        F : FnOnce(&mut TextStyle)
+    {
+        self.flush();
+        let frame = self.frame();
+        let mut style = frame.style.clone();
+        f(&mut style);
+        let coloured_by = match (&gradient) {
+            Some(_) => Some(self.frames.len()),
+            None    => frame.coloured_by
+        };
+        self.frames.push(MiniFrame { names, style, gradient, start : self.components.len(), coloured_by });
+    }
+
+    /// Opens a tag which sets the colour of the current style.
+    fn open_colour(&mut self, names : Vec<&'static str>, colour : Rgb) {
+        self.open(names, None, |style| { style.colour = colour; });
+        self.frames.last_mut().unwrap().coloured_by = None;
+    }
+
+    /// Closes every tag from the frame at `index` upwards.
+    fn close_to(&mut self, index : usize) {
+        self.flush();
+        while (self.frames.len() > index) {
+            let frame_index = self.frames.len() - 1;
+            let frame       = self.frames.pop().unwrap();
+            if let Some(colours) = frame.gradient {
+                self.apply_gradient(frame_index, frame.start, &colours);
+            }
+        }
+    }
+
+    /// Colours each character of the components coloured by the gradient at `frame_index`.
+    fn apply_gradient(&mut self, frame_index : usize, start : usize, colours : &[Rgb]) {
+        let coloured = |coloured_by : &Option<usize>| *coloured_by == Some(frame_index);
+        let len = self.components[start..].iter()
+            .filter(|(component, coloured_by,)| coloured(coloured_by) && matches!(component.content, TextContent::Literal { .. }))
+            .map(|(component, _,)| match (&component.content) {
+                TextContent::Literal { text } => text.chars().count(),
+                _                             => 0
+            })
+            .sum::<usize>();
+        let mut i          = 0;
+        let mut components = Vec::with_capacity(self.components.len());
+        for (component, coloured_by,) in self.components.drain(start..) {
+            if (! coloured(&coloured_by)) {
+                components.push((component, coloured_by,));
+                continue;
+            }
+            match (&component.content) {
+                TextContent::Literal { text } => {
+                    for ch in text.chars() {
+                        let style = TextStyle { colour : gradient_at(colours, i, len), ..component.style.clone() };
+                        components.push((TextComponent { content : TextContent::Literal { text : Cow::Owned(ch.to_string()) }, style }, None,));
+                        i += 1;
+                    }
+                },
+                _ => {
+                    let style = TextStyle { colour : gradient_at(colours, i, len), ..component.style.clone() };
+                    components.push((TextComponent { content : component.content, style }, None,));
+                }
+            }
+        }
+        self.components.extend(components);
+    }
+
+    /// Handles the contents of a tag, returning `false` if it is not a valid tag.
+    fn tag(&mut self, tag : &str) -> bool {
+        if let Some(name) = tag.strip_prefix('/') {
+            let name  = name.to_ascii_lowercase();
+            let index = if (name.is_empty()) { (self.frames.len() > 1).then(|| self.frames.len() - 1) }
+                else { self.frames.iter().rposition(|frame| frame.names.iter().any(|n| *n == name)) };
+            // Unmatched closing tags are ignored, like in MiniMessage.
+            if let Some(index) = index && index > 0 {
+                self.close_to(index);
+            }
+            return true;
+        }
+        let (negated, tag,) = match (tag.strip_prefix('!')) {
+            Some(tag) => (true, tag,),
+            None      => (false, tag,)
+        };
+        let Some(args) = split_args(tag)
+            else { return false; };
+        let name = args[0].to_ascii_lowercase();
+        let args = &args[1..];
+        if let Some(names) = decoration(&name) {
+            if (! args.is_empty()) { return false; }
+            self.open(names.to_vec(), None, |style| {
+                let enabled = ! negated;
+                match (names[0]) {
+                    "bold"          => { style.bold      = enabled; },
+                    "italic"        => { style.italic    = enabled; },
+                    "underlined"    => { style.underline = enabled; },
+                    "strikethrough" => { style.strike    = enabled; },
+                    _               => { style.obfuscate = enabled; }
+                }
+            });
+            return true;
+        }
+        if (negated) { return false; }
+        match (name.as_str()) {
+            "reset" => { self.close_to(1); },
+            "newline" | "br" => { self.literal.push('\n'); },
+            "color" | "colour" | "c" => {
+                let [colour] = args
+                    else { return false; };
+                let Some(colour) = parse_colour(colour)
+                    else { return false; };
+                self.open_colour(vec!["color", "colour", "c"], colour);
+            },
+            "shadow" => {
+                let [colour] = args
+                    else { return false; };
+                let Some(shadow) = parse_shadow(colour)
+                    else { return false; };
+                self.open(vec!["shadow"], None, |style| { style.shadow = Some(shadow); });
+            },
+            "hover" => {
+                let [action, value @ ..] = args
+                    else { return false; };
+                if (value.is_empty()) { return false; }
+                let on_hover = match (action.as_str()) {
+                    "show_text" => HoverEvent::ShowText(Text::from_minimessage(&value.join(":"))),
+                    "show_item" => {
+                        let (id, count,) = match (value.split_last().and_then(|(count, id,)| Some((id, count.parse().ok()?,)))) {
+                            Some((id, count,)) if (! id.is_empty()) => (id, count,),
+                            _                                       => (value, 1,)
+                        };
+                        let Some(id) = parse_ident(&id.join(":"))
+                            else { return false; };
+                        HoverEvent::ShowItem(ItemStack { id, count })
+                    },
+                    "show_entity" => {
+                        let Some((i, uuid,)) = value.iter().enumerate().find_map(|(i, uuid,)| Some((i, Uuid::parse_str(uuid).ok()?,)))
+                            else { return false; };
+                        let Some(kind) = parse_ident(&value[..i].join(":"))
+                            else { return false; };
+                        let name = &value[(i + 1)..];
+                        let name = (! name.is_empty()).then(|| Text::from_minimessage(&name.join(":")));
+                        HoverEvent::ShowEntity { kind, uuid, name }
+                    },
+                    _ => { return false; }
+                };
+                self.open(vec!["hover"], None, |style| { style.on_hover = Some(on_hover); });
+            },
+            "click" => {
+                let [action, value @ ..] = args
+                    else { return false; };
+                if (value.is_empty()) { return false; }
+                let value  = Cow::Owned(value.join(":"));
+                let action = match (action.as_str()) {
+                    "open_url"          => Action::OpenUrl { url : value },
+                    "run_command"       => Action::RunCommand { command : value },
+                    "suggest_command"   => Action::SuggestCommand { command : value },
+                    "copy_to_clipboard" => Action::SetClipboard { text : value },
+                    "change_page"       => {
+                        let Ok(page) = value.parse()
+                            else { return false; };
+                        Action::SetBookPage { page }
+                    },
+                    _ => { return false; }
+                };
+                self.open(vec!["click"], None, |style| { style.on_click = Some(action); });
+            },
+            "insert" | "insertion" => {
+                if (args.is_empty()) { return false; }
+                let insertion = Cow::Owned(args.join(":"));
+                self.open(vec!["insert", "insertion"], None, |style| { style.insertion = Some(insertion); });
+            },
+            "font" => {
+                let Some(font) = parse_ident(&args.join(":"))
+                    else { return false; };
+                self.open(vec!["font"], None, |style| { style.font = Some(font); });
+            },
+            "gradient" => {
+                let Some(colours) = args.iter().map(|colour| parse_colour(colour)).collect::<Option<Vec<_>>>()
+                    else { return false; };
+                if (colours.len() < 2) { return false; }
+                self.open(vec!["gradient"], Some(colours), |_| { });
+            },
+            "lang" | "tr" | "translate" | "lang_or" | "tr_or" | "translate_or" => {
+                let (key, fallback, with,) = match (name.ends_with("_or"), args,) {
+                    (false, [key, with @ ..],)           => (key, None, with,),
+                    (true,  [key, fallback, with @ ..],) => (key, Some(fallback), with,),
+                    _                                    => { return false; }
+                };
+                self.flush();
+                self.push(TextContent::Translate {
+                    key      : Cow::Owned(key.clone()),
+                    fallback : fallback.map(|fallback| Cow::Owned(fallback.clone())),
+                    with     : Cow::Owned(with.iter().map(|arg| Text::from_minimessage(arg)).collect())
+                });
+            },
+            "key" => {
+                let [id] = args
+                    else { return false; };
+                self.flush();
+                self.push(TextContent::Keybind { id : Cow::Owned(id.clone()) });
+            },
+            "score" => {
+                let [name, objective] = args
+                    else { return false; };
+                self.flush();
+                self.push(TextContent::Score { score : TextScore {
+                    name      : Cow::Owned(name.clone()),
+                    objective : Cow::Owned(objective.clone())
+                } });
+            },
+            "selector" | "sel" => {
+                let (selector, separator,) = match (args) {
+                    [selector]            => (selector, None,),
+                    [selector, separator] => (selector, Some(Text::from_minimessage(separator)),),
+                    _                     => { return false; }
+                };
+                self.flush();
+                self.push(TextContent::Selector { selector : Cow::Owned(selector.clone()), separator });
+            },
+            "nbt" | "data" => {
+                let [source, id, path, rest @ ..] = args
+                    else { return false; };
+                let (interpret, rest,) = match (rest) {
+                    [rest @ .., flag] if flag == "interpret" => (true, rest,),
+                    rest                                     => (false, rest,)
+                };
+                let separator = match (rest) {
+                    []                                  => None,
+                    [separator] if separator.is_empty() => None,
+                    [separator]                         => Some(Text::from_minimessage(separator)),
+                    _                                   => { return false; }
+                };
+                let source = match (source.as_str()) {
+                    "block"   => TextNbtSource::Block(Cow::Owned(id.clone())),
+                    "entity"  => TextNbtSource::Entity(Cow::Owned(id.clone())),
+                    "storage" => {
+                        let Some(id) = parse_ident(id)
+                            else { return false; };
+                        TextNbtSource::Storage(id)
+                    },
+                    _ => { return false; }
+                };
+                self.flush();
+                self.push(TextContent::Nbt { path : Cow::Owned(path.clone()), interpret, separator, source });
+            },
+            "sprite" => {
+                let (atlas, sprite,) = match (args) {
+                    [sprite]        => (None, sprite,),
+                    [atlas, sprite] => {
+                        let Some(atlas) = parse_ident(atlas)
+                            else { return false; };
+                        (Some(atlas), sprite,)
+                    },
+                    _ => { return false; }
+                };
+                let Some(sprite) = parse_ident(sprite)
+                    else { return false; };
+                self.flush();
+                self.push(TextContent::Object(TextObject::Atlas { atlas, sprite }));
+            },
+            "head" => {
+                let (name, hat,) = match (args) {
+                    [name]      => (name, true,),
+                    [name, hat] => {
+                        let Ok(hat) = hat.parse()
+                            else { return false; };
+                        (name, hat,)
+                    },
+                    _ => { return false; }
+                };
+                self.flush();
+                self.push(TextContent::Object(TextObject::Player { name : Cow::Owned(name.clone()), hat }));
+            },
+            name => {
+                if (! args.is_empty()) { return false; }
+                let Some(colour) = parse_colour(name)
+                    else { return false; };
+                let mut names = vec!["color", "colour", "c"];
+                if let Some((name, _,)) = NAMED_COLOURS.iter().find(|(colour_name, _,)| *colour_name == name) {
+                    names.push(name);
+                }
+                self.open_colour(names, colour);
+            }
+        }
+        true
+    }
+
+}
+
+
+/// Returns the names of a decoration tag, the first of which is its full name.
+fn decoration(name : &str) -> Option<&'static [&'static str]> { Some(match (name) {
+    "bold" | "b"           => &["bold", "b"],
+    "italic" | "i" | "em"  => &["italic", "i", "em"],
+    "underlined" | "u"     => &["underlined", "u"],
+    "strikethrough" | "st" => &["strikethrough", "st"],
+    "obfuscated" | "obf"   => &["obfuscated", "obf"],
+    _                      => { return None; }
+}) }
+
+/// Parses an identifier, where the namespace defaults to `minecraft`.
+fn parse_ident(ident : &str) -> Option<Ident> {
+    match (ident.split_once(':')) {
+        Some((namespace, path,)) => Ident::try_from((namespace, path,)).ok(),
+        None                     => Ident::try_from(("minecraft", ident,)).ok()
+    }
+}
+
+/// Parses a colour name or `#rrggbb` hex colour.
+fn parse_colour(colour : &str) -> Option<Rgb> {
+    let colour = colour.to_ascii_lowercase();
+    if let Some(hex) = colour.strip_prefix('#') {
+        if (hex.len() != 6) { return None; }
+        return u32::from_str_radix(hex, 16).ok().map(Rgb::from_u32);
+    }
+    let colour = match (colour.as_str()) {
+        "dark_grey" => "dark_gray",
+        "grey"      => "gray",
+        colour      => colour
+    };
+    NAMED_COLOURS.iter().find(|(name, _,)| *name == colour).map(|(_, colour,)| *colour)
+}
+
+/// Parses a `#rrggbbaa` hex colour, or an opaque colour name or `#rrggbb` hex colour.
+fn parse_shadow(colour : &str) -> Option<Argb> {
+    if let Some(hex) = colour.strip_prefix('#') && hex.len() == 8 {
+        let v = u32::from_str_radix(hex, 16).ok()?;
+        return Some(Argb::from_u32(v.rotate_right(8)));
+    }
+    parse_colour(colour).map(Rgb::opaque)
+}
+
+/// Returns the colour of character `i` of `len` in a gradient.
+fn gradient_at(colours : &[Rgb], i : usize, len : usize) -> Rgb {
+    if (len <= 1) { return colours[0]; }
+    let position = (i as f32 / (len - 1) as f32) * (colours.len() - 1) as f32;
+    let segment  = (position as usize).min(colours.len() - 2);
+    let t        = position - segment as f32;
+    let (from, to,) = (colours[segment], colours[segment + 1],);
+    let lerp = |a : u8, b : u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Rgb { r : lerp(from.r, to.r), g : lerp(from.g, to.g), b : lerp(from.b, to.b) }
+}
+
+
+/// Splits off the contents of a tag, after its `<`, from the rest of the markup.
+///
+/// Returns `None` if the tag is not closed, or if another tag starts before it is.
+fn split_tag(markup : &str) -> Option<(&str, &str,)> {
+    let mut quote      = None;
+    let mut escaped    = false;
+    let mut arg_start  = true;
+    for (i, ch,) in markup.char_indices() {
+        if (escaped) { escaped = false; continue; }
+        match (quote, ch,) {
+            (Some(_), '\\',)                       => { escaped = true; },
+            (Some(q), ch,) if ch == q              => { quote = None; },
+            (Some(_), _,)                          => { },
+            (None, '\'' | '"',) if arg_start       => { quote = Some(ch); },
+            (None, '>',)                           => { return (i > 0).then(|| (&markup[..i], &markup[(i + 1)..],)); },
+            (None, '<',)                           => { return None; },
+            (None, _,)                             => { }
+        }
+        arg_start = quote.is_none() && ch == ':';
+    }
+    None
+}
+
+/// Splits the contents of a tag into its `:` separated arguments, removing quotes.
+///
+/// Returns `None` if a quoted argument is followed by anything other than a `:`.
+fn split_args(tag : &str) -> Option<Vec<String>> {
+    let mut args  = Vec::new();
+    let mut chars = tag.chars().peekable();
+    loop {
+        let mut arg = String::new();
+        match (chars.peek().copied()) {
+            Some(quote @ ('\'' | '"')) => {
+                chars.next();
+                loop {
+                    match (chars.next()?) {
+                        '\\' => { arg.push(chars.next()?); },
+                        ch if ch == quote => { break; },
+                        ch => { arg.push(ch); }
+                    }
+                }
+                args.push(arg);
+                match (chars.next()) {
+                    None      => { return Some(args); },
+                    Some(':') => { },
+                    Some(_)   => { return None; }
+                }
+            },
+            _ => {
+                loop {
+                    match (chars.next()) {
+                        None      => { args.push(arg); return Some(args); },
+                        Some(':') => { break; },
+                        Some(ch)  => { arg.push(ch); }
+                    }
+                }
+                args.push(arg);
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::TextFormatted as _;
+
+    fn assert_parses(markup : &str, expected : Text) {
+        assert_eq!(format!("{:?}", Text::from_minimessage(markup)), format!("{expected:?}"), "parsing {markup:?}");
+    }
+
+    #[test]
+    fn parse() {
+        assert_parses("<red>a<b>b</red>c", Text::literal("a").red() + Text::literal("b").red().bold() + Text::literal("c"));
+        assert_parses("<#123abc>a</>b", Text::literal("a").colour(Rgb::from_u32(0x123abc)) + Text::literal("b"));
+        assert_parses("<bold>a<!bold>b<reset>c", Text::literal("a").bold() + Text::literal("b") + Text::literal("c"));
+        assert_parses("<insert:'a:b>'>x", Text::literal("x").insertion("a:b>"));
+        assert_parses("<hover:show_text:'<red>tip'>x", Text::literal("x").tooltip(Text::literal("tip").red()));
+        assert_parses("<lang:k:'<bold>a'>", Text::translate_with("k", vec![Text::literal("a").bold()]));
+        // Invalid tags, escapes, and unmatched closing tags are kept as text or ignored.
+        assert_eq!(Text::from_minimessage(r"<nope>\<red>\\</bold>x").to_plain(), r"<nope><red>\x");
+    }
+
+    #[test]
+    fn gradient() {
+        let text = Text::from_minimessage("<gradient:#000000:#ffffff>abc</gradient>d");
+        let colours = text.components.iter().map(|component| component.style.colour).collect::<Vec<_>>();
+        assert_eq!(text.to_plain(), "abcd");
+        assert_eq!(colours.len(), 4);
+        assert_eq!(colours[0], Rgb::from_u32(0x000000));
+        assert_eq!(colours[2], Rgb::from_u32(0xffffff));
+        assert_eq!(colours[3], Rgb::WHITE);
+    }
+
+    #[test]
+    fn render() {
+        assert_eq!(Text::literal("a<\\").to_minimessage(), r"a\<\\");
+        assert_eq!(Text::literal("a").red().bold().to_minimessage(), "<red><bold>a</bold></red>");
+        assert_eq!(Text::literal("a").colour(Rgb::from_u32(0x123abc)).insertion("it's").to_minimessage(), r"<#123abc><insert:'it\'s'>a</insert></color>");
+        // Rendered markup parses back to the same components.
+        for text in [
+            Text::literal("a").red().italic() + Text::literal("b").underline().strike().obfuscate() + Text::literal("c"),
+            Text::literal("x").tooltip(Text::literal("t'ip").green()).insertion("<i>"),
+            Text::translate_with_or("k", vec![Text::literal("a:b").bold()], "fallback") + Text::keybind("key.jump")
+        ] {
+            assert_eq!(format!("{:?}", Text::from_minimessage(&text.to_minimessage())), format!("{text:?}"), "rendering {text:?}");
+        }
+    }
+
+}
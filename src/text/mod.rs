@@ -20,7 +20,19 @@ use syndebug::SynDebug;
 
 
 mod fmt;
+pub use fmt::*;
 mod ser;
+mod nbt;
+mod legacy;
+mod minimessage;
+mod html;
+mod markdown;
+mod resolve;
+pub use resolve::*;
+mod hover;
+pub use hover::*;
+mod translate;
+pub use translate::*;
 
 
 /// A collection of formatted text components.
@@ -61,7 +73,7 @@ pub enum TextContent {
         #[serde(skip_serializing_if = "Option::is_none")]
         fallback : Option<Cow<'static, str>>,
         /// Interpolation replacements.
-        #[serde(skip_serializing_if = "slice_is_empty", default)]
+        #[serde(skip_serializing_if = "slice_is_empty", deserialize_with = "ser::deser_args", default)]
         with     : Cow<'static, [Text]>
     },
     /// A keybind component.
@@ -69,6 +81,82 @@ pub enum TextContent {
         /// ID of the keybind.
         #[serde(rename = "keybind")]
         id : Cow<'static, str>
+    },
+    /// A scoreboard score component, resolved by the server.
+    Score {
+        /// The score to display.
+        score : TextScore
+    },
+    /// An entity selector component, resolved by the server.
+    Selector {
+        /// The selector to match entities with.
+        selector  : Cow<'static, str>,
+        /// Text to place between the names of matched entities.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        separator : Option<Text>
+    },
+    /// An NBT path component, resolved by the server.
+    Nbt {
+        /// The NBT path to display.
+        #[serde(rename = "nbt")]
+        path      : Cow<'static, str>,
+        /// Whether the values found should be parsed as text components.
+        #[serde(skip_serializing_if = "core::ops::Not::not", deserialize_with = "ser::deser_flag", default)]
+        interpret : bool,
+        /// Text to place between the values found.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        separator : Option<Text>,
+        /// Where the NBT is read from.
+        #[serde(flatten)]
+        source    : TextNbtSource
+    },
+    /// An object component.
+    Object(TextObject)
+}
+
+/// The score displayed by a [`TextContent::Score`] component.
+#[derive(Ser, Deser, Clone, Debug, SynDebug)]
+pub struct TextScore {
+    /// The name of the score holder, or a selector matching a single entity.
+    pub name      : Cow<'static, str>,
+    /// The scoreboard objective.
+    pub objective : Cow<'static, str>
+}
+
+/// Where a [`TextContent::Nbt`] component reads NBT from.
+#[derive(Ser, Deser, Clone, Debug, SynDebug)]
+#[serde(rename_all = "snake_case")]
+pub enum TextNbtSource {
+    /// The block entity at a position, such as `~ ~-1 ~`.
+    Block(Cow<'static, str>),
+    /// The entities matched by a selector.
+    Entity(Cow<'static, str>),
+    /// A command storage.
+    Storage(Ident)
+}
+
+/// The object displayed by a [`TextContent::Object`] component.
+#[derive(Ser, Deser, Clone, Debug, SynDebug)]
+#[serde(tag = "object")]
+pub enum TextObject {
+    /// A sprite from a texture atlas.
+    #[serde(rename = "atlas")]
+    Atlas {
+        /// The atlas to take the sprite from, or `minecraft:blocks` if `None`.
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        atlas  : Option<Ident>,
+        /// The sprite to display.
+        sprite : Ident
+    },
+    /// A player's head.
+    #[serde(rename = "player")]
+    Player {
+        /// The name of the player.
+        #[serde(rename = "player")]
+        name : Cow<'static, str>,
+        /// Whether the hat layer of the skin is shown.
+        #[serde(deserialize_with = "ser::deser_flag", default = "ser::default_hat")]
+        hat  : bool
     }
 }
 
@@ -82,19 +170,19 @@ pub struct TextStyle {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub font      : Option<Ident>,
     /// Bolded.
-    #[serde(default)]
+    #[serde(deserialize_with = "ser::deser_flag", default)]
     pub bold      : bool,
     /// Italicised
-    #[serde(default)]
+    #[serde(deserialize_with = "ser::deser_flag", default)]
     pub italic    : bool,
     /// Underlined
-    #[serde(rename = "underlined", default)]
+    #[serde(rename = "underlined", deserialize_with = "ser::deser_flag", default)]
     pub underline : bool,
     /// Crossed out
-    #[serde(rename = "strikethrough", default)]
+    #[serde(rename = "strikethrough", deserialize_with = "ser::deser_flag", default)]
     pub strike    : bool,
     /// Obfuscated
-    #[serde(rename = "obfuscated", default)]
+    #[serde(rename = "obfuscated", deserialize_with = "ser::deser_flag", default)]
     pub obfuscate : bool,
     /// Text shadow colour.
     #[serde(rename = "shadow_color", skip_serializing_if = "Option::is_none")]
@@ -106,8 +194,8 @@ pub struct TextStyle {
     #[serde(rename = "click_event", skip_serializing_if = "Option::is_none")]
     pub on_click  : Option<Action>,
     /// Text to display on component hovered.
-    #[serde(rename = "hover_event", skip_serializing_if = "Option::is_none", serialize_with = "ser::ser_hover_event_tooltip", deserialize_with = "ser::deser_hover_event_tooltip", default)]
-    pub tooltip   : Option<Text>
+    #[serde(rename = "hover_event", skip_serializing_if = "Option::is_none")]
+    pub on_hover  : Option<HoverEvent>
 }
 
 
@@ -248,8 +336,19 @@ impl TextStyle {
         shadow    : None,
         insertion : None,
         on_click  : None,
-        tooltip   : None
+        on_hover  : None
     };
+
+    /// Returns `true` if this style has no styling behaviour.
+    pub fn is_empty(&self) -> bool {
+        self.colour == Rgb::WHITE
+        && self.font.is_none()
+        && (! self.bold) && (! self.italic) && (! self.underline) && (! self.strike) && (! self.obfuscate)
+        && self.shadow.is_none()
+        && self.insertion.is_none()
+        && self.on_click.is_none()
+        && self.on_hover.is_none()
+    }
 }
 impl Default for TextStyle {
     #[inline]
@@ -346,10 +445,14 @@ where
     fn on_click(self, action : Action) -> Text;
     /// Disable actions executed when this text is clicked.
     fn no_on_click(self) -> Text;
+    /// Set what is shown when this text is hovered.
+    fn on_hover<H>(self, event : H) -> Text
+        where H : Into<HoverEvent>;
     /// Set this text's tooltip.
     fn tooltip<S>(self, text : S) -> Text
-        where S : Into<Text>;
-    /// Disable this text's tooltip.
+        where S : Into<Text>
+    { self.on_hover(HoverEvent::ShowText(text.into())) }
+    /// Disable this text's tooltip, or any other hover event.
     fn no_tooltip(self) -> Text;
     /// Disable all styling information on this text.
     fn reset(self) -> Text;
@@ -424,13 +527,13 @@ impl TextFormatted for Text {
     fn no_on_click(self) -> Text {
         self.apply(|component| { component.style.on_click = None; })
     }
-    fn tooltip<S>(self, text : S) -> Text
-    where S : Into<Text> {
-        let text = text.into();
-        self.apply(|component| { component.style.tooltip = Some(text.clone()); })
+    fn on_hover<H>(self, event : H) -> Text
+    where H : Into<HoverEvent> {
+        let event = event.into();
+        self.apply(|component| { component.style.on_hover = Some(event.clone()); })
     }
     fn no_tooltip(self) -> Text {
-        self.apply(|component| { component.style.tooltip = None; })
+        self.apply(|component| { component.style.on_hover = None; })
     }
     fn reset(self) -> Text {
         self.apply(|component| {
@@ -444,7 +547,7 @@ impl TextFormatted for Text {
             component.style.shadow    = None;
             component.style.insertion = None;
             component.style.on_click  = None;
-            component.style.tooltip   = None;
+            component.style.on_hover  = None;
         })
     }
 }
@@ -498,8 +601,8 @@ where
     #[inline]
     fn no_on_click(self) -> Text { Text::from(self).no_on_click() }
     #[inline]
-    fn tooltip<S>(self, text : S) -> Text
-    where S : Into<Text> { Text::from(self).tooltip(text) }
+    fn on_hover<H>(self, event : H) -> Text
+    where H : Into<HoverEvent> { Text::from(self).on_hover(event) }
     #[inline]
     fn no_tooltip(self) -> Text { Text::from(self).no_tooltip() }
     #[inline]
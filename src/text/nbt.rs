@@ -0,0 +1,241 @@
+use super::{
+    Text,
+    TextComponent,
+    TextContent,
+    TextStyle,
+    TextNbtSource,
+    TextObject,
+    HoverEvent,
+    hover::uuid_to_ints
+};
+use crate::{
+    Minecraft,
+    nbt::{
+        NbtElement,
+        NbtCompound,
+        NbtSerError,
+        NbtDeserError,
+        to_element,
+        from_element
+    }
+};
+use std::borrow::Cow;
+use netzer::prelude::*;
+
+
+impl NetEncode<Minecraft> for Text {
+    async fn encode<W : netzer::AsyncWrite>(&self, w : W) -> netzer::Result {
+        <NbtElement as NetEncode<Minecraft>>::encode(&self.to_nbt()?, w).await
+    }
+}
+
+impl NetDecode<Minecraft> for Text {
+    async fn decode<R : netzer::AsyncRead>(r : R) -> netzer::Result<Self> {
+        let element = <NbtElement as NetDecode<Minecraft>>::decode(r).await?;
+        Ok(Self::from_nbt(&element)?)
+    }
+}
+
+
+impl Text {
+
+    /// Converts this text to an NBT element, as sent by vanilla since 1.20.3.
+    ///
+    /// The colour and decorations are always written, so that they are not inherited from where the text is shown,
+    ///  such as the italic purple of item names. Other style fields holding their default values are left out.
+    /// For the same reason, text is only written as a plain string within an unstyled parent, never on its own.
+    pub fn to_nbt(&self) -> Result<NbtElement<'static>, NbtSerError> {
+        Ok(match (&*self.components) {
+            []          => TextComponent::EMPTY.to_nbt(false)?,
+            [component] => component.to_nbt(false)?,
+            components  => {
+                // Wrap the components in an unstyled parent, so that they do not inherit each other's styles.
+                // Unstyled literal children are written as plain strings, inheriting the parent's style.
+                let mut compound = NbtCompound::new();
+                compound.insert("text", "");
+                compound.insert("extra", NbtElement::List(Cow::Owned(components.iter().map(|component| component.to_nbt(true)).collect::<Result<_, _>>()?)));
+                TextStyle::EMPTY.write_nbt(&mut compound)?;
+                NbtElement::Compound(compound)
+            }
+        })
+    }
+
+    /// Parses text from an NBT element, as sent by vanilla since 1.20.3.
+    ///
+    /// Children in `extra` become components of their own, inheriting the style of their parent, as when
+    ///  deserialising [`Text`] from an [`NbtElement`].
+    pub fn from_nbt(element : &NbtElement<'_>) -> Result<Self, NbtDeserError> {
+        from_element(element)
+    }
+
+}
+
+
+impl TextComponent {
+
+    /// Converts this component to an NBT element.
+    ///
+    /// If `in_unstyled_parent` is set, unstyled literal components are written as plain strings.
+    fn to_nbt(&self, in_unstyled_parent : bool) -> Result<NbtElement<'static>, NbtSerError> {
+        if (in_unstyled_parent) && let TextContent::Literal { text } = &self.content && self.style.is_empty() {
+            return Ok(NbtElement::String(text.clone()));
+        }
+        let mut compound = NbtCompound::new();
+        match (&self.content) {
+            TextContent::Literal { text } => { compound.insert("text", NbtElement::String(text.clone())); },
+            TextContent::Translate { key, fallback, with } => {
+                compound.insert("translate", NbtElement::String(key.clone()));
+                if let Some(fallback) = fallback {
+                    compound.insert("fallback", NbtElement::String(fallback.clone()));
+                }
+                if (! with.is_empty()) {
+                    compound.insert("with", NbtElement::List(Cow::Owned(with.iter().map(Text::to_nbt).collect::<Result<_, _>>()?)));
+                }
+            },
+            TextContent::Keybind { id } => { compound.insert("keybind", NbtElement::String(id.clone())); },
+            TextContent::Score { score } => { compound.insert("score", to_element(score)?); },
+            TextContent::Selector { selector, separator } => {
+                compound.insert("selector", NbtElement::String(selector.clone()));
+                if let Some(separator) = separator {
+                    compound.insert("separator", separator.to_nbt()?);
+                }
+            },
+            TextContent::Nbt { path, interpret, separator, source } => {
+                compound.insert("nbt", NbtElement::String(path.clone()));
+                if (*interpret) { compound.insert("interpret", true); }
+                if let Some(separator) = separator {
+                    compound.insert("separator", separator.to_nbt()?);
+                }
+                match (source) {
+                    TextNbtSource::Block(pos)       => { compound.insert("block", NbtElement::String(pos.clone())); },
+                    TextNbtSource::Entity(selector) => { compound.insert("entity", NbtElement::String(selector.clone())); },
+                    TextNbtSource::Storage(id)      => { compound.insert("storage", to_element(id)?); }
+                }
+            },
+            TextContent::Object(TextObject::Atlas { atlas, sprite }) => {
+                compound.insert("object", "atlas");
+                if let Some(atlas) = atlas {
+                    compound.insert("atlas", to_element(atlas)?);
+                }
+                compound.insert("sprite", to_element(sprite)?);
+            },
+            TextContent::Object(TextObject::Player { name, hat }) => {
+                compound.insert("object", "player");
+                compound.insert("player", NbtElement::String(name.clone()));
+                compound.insert("hat", *hat);
+            }
+        }
+        self.style.write_nbt(&mut compound)?;
+        Ok(NbtElement::Compound(compound))
+    }
+
+}
+
+
+impl TextStyle {
+
+    /// Writes the colour and decorations of this style, and the other fields which do not hold their default values, into a compound.
+    fn write_nbt(&self, compound : &mut NbtCompound<'static>) -> Result<(), NbtSerError> {
+        compound.insert("color", format!("#{:06x}", self.colour.to_u32()));
+        if let Some(font) = &self.font {
+            compound.insert("font", to_element(font)?);
+        }
+        compound.insert("bold", self.bold);
+        compound.insert("italic", self.italic);
+        compound.insert("underlined", self.underline);
+        compound.insert("strikethrough", self.strike);
+        compound.insert("obfuscated", self.obfuscate);
+        if let Some(shadow) = &self.shadow {
+            compound.insert("shadow_color", to_element(shadow)?);
+        }
+        if let Some(insertion) = &self.insertion {
+            compound.insert("insertion", NbtElement::String(insertion.clone()));
+        }
+        if let Some(on_click) = &self.on_click {
+            compound.insert("click_event", to_element(on_click)?);
+        }
+        if let Some(on_hover) = &self.on_hover {
+            compound.insert("hover_event", on_hover.to_nbt()?);
+        }
+        Ok(())
+    }
+
+}
+
+
+impl HoverEvent {
+
+    /// Converts this hover event to an NBT compound, in the layout used since 1.21.5.
+    fn to_nbt(&self) -> Result<NbtCompound<'static>, NbtSerError> {
+        let mut compound = NbtCompound::new();
+        match (self) {
+            Self::ShowText(text) => {
+                compound.insert("action", "show_text");
+                compound.insert("value", text.to_nbt()?);
+            },
+            Self::ShowItem(item) => {
+                compound.insert("action", "show_item");
+                compound.insert("id", to_element(&item.id)?);
+                compound.insert("count", item.count.cast_signed());
+            },
+            Self::ShowEntity { kind, uuid, name } => {
+                compound.insert("action", "show_entity");
+                compound.insert("id", to_element(kind)?);
+                compound.insert("uuid", NbtElement::IArray(Cow::Owned(uuid_to_ints(uuid).to_vec())));
+                if let Some(name) = name {
+                    compound.insert("name", name.to_nbt()?);
+                }
+            }
+        }
+        Ok(compound)
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::TextFormatted as _;
+
+    #[test]
+    fn explicit_style() {
+        let text = Text::literal("a").bold() + Text::literal("b");
+        let nbt  = text.to_nbt().unwrap();
+        assert_eq!(nbt.to_string(), concat!(
+            r##"{text:"",extra:[{text:"a",color:"#ffffff",bold:1b,italic:0b,underlined:0b,strikethrough:0b,obfuscated:0b},"b"],"##,
+            r##"color:"#ffffff",bold:0b,italic:0b,underlined:0b,strikethrough:0b,obfuscated:0b}"##
+        ));
+        assert_eq!(format!("{:?}", Text::from_nbt(&nbt).unwrap()), format!("{text:?}"));
+        // A lone literal would inherit the style of where it is shown if written as a plain string.
+        assert_eq!(Text::literal("hi").to_nbt().unwrap().to_string(),
+            r##"{text:"hi",color:"#ffffff",bold:0b,italic:0b,underlined:0b,strikethrough:0b,obfuscated:0b}"##
+        );
+    }
+
+    #[test]
+    fn contents() {
+        use crate::{ ident::Ident, text::{ TextScore, TextObject } };
+        let contents = [
+            (TextContent::Score { score : TextScore { name : "@s".into(), objective : "kills".into() } }, r#"{name:"@s",objective:"kills"}"#, "score",),
+            (TextContent::Selector { selector : "@a".into(), separator : Some(Text::literal(", ")) }, r#""@a""#, "selector",),
+            (TextContent::Nbt { path : "Items[0]".into(), interpret : true, separator : None, source : TextNbtSource::Block("~ ~-1 ~".into()) }, r#""~ ~-1 ~""#, "block",),
+            (TextContent::Nbt { path : "Name".into(), interpret : false, separator : Some(Text::literal("|")), source : TextNbtSource::Entity("@p".into()) }, r#""@p""#, "entity",),
+            (TextContent::Nbt { path : "a.b".into(), interpret : true, separator : None, source : TextNbtSource::Storage(Ident::new("a:b")) }, r#""a:b""#, "storage",),
+            (TextContent::Object(TextObject::Atlas { atlas : Some(Ident::new("minecraft:items")), sprite : Ident::new("minecraft:item/stick") }), r#""atlas""#, "object",),
+            (TextContent::Object(TextObject::Player { name : "Notch".into(), hat : false }), r#""player""#, "object",)
+        ];
+        for (content, expected, key,) in contents {
+            let text = Text::from(content);
+            let NbtElement::Compound(compound) = text.to_nbt().unwrap()
+                else { panic!("expected a compound"); };
+            assert_eq!(compound.get(key).unwrap().to_string(), expected);
+            assert_eq!(format!("{:?}", Text::from_nbt(&NbtElement::Compound(compound)).unwrap()), format!("{text:?}"));
+        }
+        // `interpret` is left out when unset, and `hat` defaults to shown.
+        let text = Text::from_nbt(&r#"[{nbt:"a",entity:"@s"},{object:"player",player:"Notch"}]"#.parse::<NbtElement>().unwrap()).unwrap();
+        assert!(matches!(&text.components[0].content, TextContent::Nbt { interpret : false, separator : None, .. }));
+        assert!(matches!(&text.components[1].content, TextContent::Object(TextObject::Player { hat : true, .. })));
+    }
+
+}
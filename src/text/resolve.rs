@@ -0,0 +1,113 @@
+use super::{
+    Text,
+    TextComponent,
+    TextContent,
+    HoverEvent
+};
+use std::borrow::Cow;
+
+
+/// Substitutes components which are resolved by the server, such as [`TextContent::Score`] and [`TextContent::Selector`],
+///  before text is sent to a client.
+///
+/// Implemented for closures taking a component and returning its replacement.
+pub trait TextResolver {
+
+    /// Returns the text to replace a component with, or `None` to keep it as it is.
+    ///
+    /// Replacement components are used as they are, and are not resolved again.
+    fn resolve(&mut self, component : &TextComponent) -> Option<Text>;
+
+}
+
+impl<F> TextResolver for F
+where
+    F : FnMut(&TextComponent) -> Option<Text>
+{
+    #[inline]
+    fn resolve(&mut self, component : &TextComponent) -> Option<Text> { self(component) }
+}
+
+
+impl Text {
+
+    /// Replaces components using a [`TextResolver`].
+    ///
+    /// Components within translation arguments, separators and hover events are resolved too.
+    pub fn resolve<R>(&self, resolver : &mut R) -> Self
+    where
+        R : TextResolver + ?Sized
+    {
+        let mut components = Vec::with_capacity(self.components.len());
+        for component in &*self.components {
+            match (resolver.resolve(component)) {
+                Some(text) => { components.extend_from_slice(&text.components); },
+                None       => { components.push(component.resolve_inner(resolver)); }
+            }
+        }
+        Self { components : Cow::Owned(components) }
+    }
+
+}
+
+impl TextComponent {
+
+    /// Resolves the texts nested within this component.
+    fn resolve_inner<R>(&self, resolver : &mut R) -> Self
+    where
+        R : TextResolver + ?Sized
+    {
+        let mut component = self.clone();
+        match (&mut component.content) {
+            TextContent::Translate { with, .. } => {
+                *with = Cow::Owned(with.iter().map(|arg| arg.resolve(resolver)).collect());
+            },
+            TextContent::Selector { separator, .. }
+            | TextContent::Nbt { separator, .. } => {
+                if let Some(separator) = separator {
+                    *separator = separator.resolve(resolver);
+                }
+            },
+            TextContent::Literal { .. }
+            | TextContent::Keybind { .. }
+            | TextContent::Score { .. }
+            | TextContent::Object(_) => { }
+        }
+        match (&mut component.style.on_hover) {
+            Some(HoverEvent::ShowText(tooltip))                    => { *tooltip = tooltip.resolve(resolver); },
+            Some(HoverEvent::ShowEntity { name : Some(name), .. }) => { *name = name.resolve(resolver); },
+            _                                                      => { }
+        }
+        component
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::{ TextScore, TextFormatted as _ };
+
+    fn score(name : &'static str) -> Text {
+        TextContent::Score { score : TextScore { name : Cow::Borrowed(name), objective : Cow::Borrowed("kills") } }.into()
+    }
+
+    #[test]
+    fn nested() {
+        let text = score("@s")
+            + Text::translate_with("k", vec![Text::literal("a") + score("@p")])
+            + TextContent::Selector { selector : "@a".into(), separator : Some(score("@r")) }
+            + Text::literal("b").tooltip(score("@e"));
+        let resolved = text.resolve(&mut |component : &TextComponent| match (&component.content) {
+            TextContent::Score { score } => Some(Text::literal(score.name.clone()) + Text::literal("!")),
+            _                            => None
+        });
+        let expected = Text::literal("@s") + Text::literal("!")
+            + Text::translate_with("k", vec![Text::literal("a") + Text::literal("@p") + Text::literal("!")])
+            + TextContent::Selector { selector : "@a".into(), separator : Some(Text::literal("@r") + Text::literal("!")) }
+            + Text::literal("b").tooltip(Text::literal("@e") + Text::literal("!"));
+        assert_eq!(format!("{resolved:?}"), format!("{expected:?}"));
+    }
+
+}
@@ -2,38 +2,202 @@ use super::{
     Text,
     TextComponent,
     TextContent,
-    TextStyle
+    TextStyle,
+    HoverEvent
 };
+use crate::{
+    action::Action,
+    colour::{ Rgb, Argb },
+    ident::Ident
+};
+use core::fmt::{ self, Formatter };
 use std::borrow::Cow;
 use serde::{
     Serialize as Ser,
-    Serializer as Serer,
     Deserialize as Deser,
-    Deserializer as Deserer
+    Deserializer as Deserer,
+    de::{
+        Error as DeserError,
+        Visitor
+    }
 };
 
 
 impl<'de> Deser<'de> for Text {
-    #[inline]
     fn deserialize<D>(deserer : D) -> Result<Self, D::Error>
     where
         D : Deserer<'de>
-    { Ok(TextComponent::deserialize(deserer)?.into()) }
+    {
+        let mut components = Vec::new();
+        SingleableText::deserialize(deserer)?.flatten_into(&TextStyle::EMPTY, &mut components)?;
+        Ok(Self { components : Cow::Owned(components) })
+    }
 }
 
+/// Any of the vanilla forms of text, before styles are inherited.
 #[derive(Deser)]
 #[serde(untagged)]
 pub(super) enum SingleableText {
-    Single(TextComponent),
-    Many(Cow<'static, [TextComponent]>)
+    Literal(Cow<'static, str>),
+    Many(Vec<SingleableText>),
+    Single(Box<InheritingTextComponent>)
+}
+
+#[derive(Deser)]
+pub(super) struct InheritingTextComponent {
+    #[serde(flatten)]
+    content : TextContent,
+    #[serde(flatten)]
+    style   : InheritingTextStyle,
+    #[serde(default)]
+    extra   : Vec<SingleableText>
+}
+
+/// A [`TextStyle`] where missing fields are inherited from the parent component.
+#[derive(Deser)]
+pub(super) struct InheritingTextStyle {
+    #[serde(rename = "color", deserialize_with = "deser_colour", default)]
+    colour    : Option<Rgb>,
+    font      : Option<Ident>,
+    #[serde(deserialize_with = "deser_opt_flag", default)]
+    bold      : Option<bool>,
+    #[serde(deserialize_with = "deser_opt_flag", default)]
+    italic    : Option<bool>,
+    #[serde(rename = "underlined", deserialize_with = "deser_opt_flag", default)]
+    underline : Option<bool>,
+    #[serde(rename = "strikethrough", deserialize_with = "deser_opt_flag", default)]
+    strike    : Option<bool>,
+    #[serde(rename = "obfuscated", deserialize_with = "deser_opt_flag", default)]
+    obfuscate : Option<bool>,
+    #[serde(rename = "shadow_color")]
+    shadow    : Option<Argb>,
+    insertion : Option<Cow<'static, str>>,
+    #[serde(rename = "click_event")]
+    on_click  : Option<Action>,
+    #[serde(rename = "hover_event")]
+    on_hover  : Option<HoverEvent>
+}
+
+#[inline]
+pub(super) const fn default_hat() -> bool { true }
+
+fn deser_colour<'de, D>(deserer : D) -> Result<Option<Rgb>, D::Error>
+where
+    D : Deserer<'de>
+{ Ok(Some(Rgb::from_hex_or_name(deserer)?)) }
+
+/// Deserialises a style flag from a boolean, or from an integer as NBT stores them.
+///
+/// Buffered fields, such as those of flattened structs, can not otherwise be read as booleans from NBT bytes.
+pub(super) fn deser_flag<'de, D>(deserer : D) -> Result<bool, D::Error>
+where
+    D : Deserer<'de>
+{
+    struct FlagVisitor;
+    impl Visitor<'_> for FlagVisitor {
+        type Value = bool;
+        fn expecting(&self, f : &mut Formatter) -> fmt::Result {
+            write!(f, "a boolean")
+        }
+        fn visit_bool<E>(self, v : bool) -> Result<Self::Value, E>
+        where
+            E : DeserError
+        { Ok(v) }
+        fn visit_i64<E>(self, v : i64) -> Result<Self::Value, E>
+        where
+            E : DeserError
+        { Ok(v != 0) }
+        fn visit_u64<E>(self, v : u64) -> Result<Self::Value, E>
+        where
+            E : DeserError
+        { Ok(v != 0) }
+    }
+    deserer.deserialize_any(FlagVisitor)
+}
+
+fn deser_opt_flag<'de, D>(deserer : D) -> Result<Option<bool>, D::Error>
+where
+    D : Deserer<'de>
+{ Ok(Some(deser_flag(deserer)?)) }
+
+/// Deserialises translation arguments, which may also be numbers or booleans.
+pub(super) fn deser_args<'de, D>(deserer : D) -> Result<Cow<'static, [Text]>, D::Error>
+where
+    D : Deserer<'de>
+{
+    #[derive(Deser)]
+    #[serde(untagged)]
+    enum Arg {
+        Text(Text),
+        Int(i64),
+        Float(f64),
+        Bool(bool)
+    }
+    Ok(Cow::Owned(Vec::<Arg>::deserialize(deserer)?.into_iter().map(|arg| match (arg) {
+        Arg::Text(text) => text,
+        Arg::Int(v)     => Text::literal(v.to_string()),
+        Arg::Float(v)   => Text::literal(v.to_string()),
+        Arg::Bool(v)    => Text::literal(v.to_string())
+    }).collect()))
 }
 
-impl From<SingleableText> for Text {
-    fn from(value : SingleableText) -> Self {
-        Self { components : match (value) {
-            SingleableText::Single(component) => Cow::Owned(vec![component]),
-            SingleableText::Many(components)  => components
-        } }
+impl SingleableText {
+
+    /// Pushes the components of this text, inheriting any missing style fields from `inherited`.
+    ///
+    /// The first element of an array is the parent of the rest, and an empty parent which only carries the style of
+    ///  its children is left out. Returns the style of the outermost component, which its children inherit.
+    fn flatten_into<E>(self, inherited : &TextStyle, components : &mut Vec<TextComponent>) -> Result<TextStyle, E>
+    where
+        E : DeserError
+    {
+        Ok(match (self) {
+            Self::Literal(text) => {
+                components.push(TextComponent { content : TextContent::Literal { text }, style : inherited.clone() });
+                inherited.clone()
+            },
+            Self::Many(texts) => {
+                let mut texts = texts.into_iter();
+                let Some(first) = texts.next()
+                    else { return Err(E::custom("text array must not be empty")); };
+                let style = first.flatten_into(inherited, components)?;
+                for text in texts {
+                    text.flatten_into(&style, components)?;
+                }
+                style
+            },
+            Self::Single(component) => {
+                let InheritingTextComponent { content, style, extra } = *component;
+                let style = style.inherit(inherited);
+                let is_empty_parent = matches!(&content, TextContent::Literal { text } if text.is_empty()) && (! extra.is_empty());
+                if (! is_empty_parent) {
+                    components.push(TextComponent { content, style : style.clone() });
+                }
+                for text in extra {
+                    text.flatten_into(&style, components)?;
+                }
+                style
+            }
+        })
+    }
+
+}
+
+impl InheritingTextStyle {
+    fn inherit(self, inherited : &TextStyle) -> TextStyle {
+        TextStyle {
+            colour    : self.colour.unwrap_or(inherited.colour),
+            font      : self.font.or_else(|| inherited.font.clone()),
+            bold      : self.bold.unwrap_or(inherited.bold),
+            italic    : self.italic.unwrap_or(inherited.italic),
+            underline : self.underline.unwrap_or(inherited.underline),
+            strike    : self.strike.unwrap_or(inherited.strike),
+            obfuscate : self.obfuscate.unwrap_or(inherited.obfuscate),
+            shadow    : self.shadow.or(inherited.shadow),
+            insertion : self.insertion.or_else(|| inherited.insertion.clone()),
+            on_click  : self.on_click.or_else(|| inherited.on_click.clone()),
+            on_hover  : self.on_hover.or_else(|| inherited.on_hover.clone())
+        }
     }
 }
 
@@ -67,31 +231,39 @@ impl From<TextComponent> for ExtraedTextComponent {
 }
 
 
-#[derive(Ser, Deser)]
-#[serde(tag = "action")]
-pub(super) enum HoverEventedTooltip<'l> {
-    #[serde(rename = "show_text")]
-    Tooltip {
-        #[serde(rename = "value")]
-        text : Cow<'l, Text>
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::{ NbtElement, from_element };
+
+    fn parse(snbt : &str) -> Text {
+        from_element(&snbt.parse::<NbtElement>().unwrap()).unwrap()
     }
-}
 
+    #[test]
+    fn array_inherits_from_parent() {
+        // "b" inherits the bold parent, not its red child.
+        let text = parse(r#"[{text:"",bold:1b,extra:[{text:"a",color:"red"}]},"b"]"#);
+        assert_eq!(text.components.len(), 2);
+        assert!(text.components[0].style.bold && text.components[0].style.colour == Rgb::RED);
+        assert!(text.components[1].style.bold && text.components[1].style.colour == Rgb::WHITE);
+    }
 
-pub(super) fn ser_hover_event_tooltip<S>(tooltip : &Option<Text>, serer : S) -> Result<S::Ok, S::Error>
-where
-    S : Serer
-{
-    let Some(tooltip) = tooltip
-        else { unreachable!(); };
-    HoverEventedTooltip::Tooltip { text : Cow::Borrowed(tooltip) }.serialize(serer)
-}
+    #[test]
+    fn numeric_args() {
+        let text = parse(r#"{translate:"k",with:[5,1.5d,"a"]}"#);
+        let TextContent::Translate { with, .. } = &text.components[0].content
+            else { panic!("expected translate content"); };
+        assert_eq!(format!("{with:?}"), format!("{:?}", [Text::literal("5"), Text::literal("1.5"), Text::literal("a")]));
+    }
+
+    #[test]
+    fn explicit_style_round_trip() {
+        let text = parse(r#"{text:"a",italic:1b,extra:[{text:"b",italic:0b,underlined:true},"c"]}"#);
+        let styles = text.components.iter().map(|component| (component.style.italic, component.style.underline,)).collect::<Vec<_>>();
+        assert_eq!(styles, [(true, false,), (false, true,), (true, false,)]);
+        let nbt = text.to_nbt().unwrap();
+        assert_eq!(format!("{:?}", from_element::<Text>(&nbt).unwrap()), format!("{text:?}"));
+    }
 
-pub(super) fn deser_hover_event_tooltip<'de, D>(deserer : D) -> Result<Option<Text>, D::Error>
-where
-    D : Deserer<'de>
-{
-    Ok(Option::<HoverEventedTooltip>::deserialize(deserer)?
-        .map(|HoverEventedTooltip::Tooltip { text }| text.into_owned())
-    )
 }
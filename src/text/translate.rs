@@ -0,0 +1,371 @@
+use super::{
+    Text,
+    TextComponent,
+    TextContent
+};
+use core::{
+    error::Error as StdError,
+    fmt::{ self, Display, Formatter }
+};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs,
+    io,
+    path::Path
+};
+
+
+/// A table of translations for a single language, used to render [`TextContent::Translate`] components.
+#[derive(Clone, Debug, Default)]
+pub struct Translator {
+    entries : HashMap<String, String>
+}
+
+
+impl Translator {
+
+    /// Creates a new [`Translator`] with no translations.
+    #[inline]
+    pub fn new() -> Self { Self::default() }
+
+    /// Loads the language file `{language}.json` from a directory, such as `en_us`.
+    #[inline]
+    pub fn load<P>(dir : P, language : &str) -> Result<Self, TranslatorLoadError>
+    where
+        P : AsRef<Path>
+    { Self::load_file(dir.as_ref().join(format!("{language}.json"))) }
+
+    /// Loads a vanilla-format language file.
+    pub fn load_file<P>(path : P) -> Result<Self, TranslatorLoadError>
+    where
+        P : AsRef<Path>
+    {
+        let mut translator = Self::new();
+        translator.extend_json(&fs::read_to_string(path)?)?;
+        Ok(translator)
+    }
+
+    /// Adds the translations from a vanilla-format language file, a JSON object mapping keys to strings.
+    ///
+    /// Translations which already exist are replaced, so that resource packs can be layered over each other.
+    pub fn extend_json(&mut self, json : &str) -> Result<(), TranslatorLoadError> {
+        let mut parser = JsonParser { json, offset : 0 };
+        parser.skip_whitespace();
+        parser.expect('{')?;
+        parser.skip_whitespace();
+        if (! parser.eat('}')) {
+            loop {
+                parser.skip_whitespace();
+                let key = parser.string()?;
+                parser.skip_whitespace();
+                parser.expect(':')?;
+                parser.skip_whitespace();
+                let value = parser.string()?;
+                self.entries.insert(key, value);
+                parser.skip_whitespace();
+                if (parser.eat('}')) { break; }
+                parser.expect(',')?;
+            }
+        }
+        parser.skip_whitespace();
+        if (parser.offset < json.len()) {
+            return Err(TranslatorLoadError::Json { offset : parser.offset, reason : "trailing characters" });
+        }
+        Ok(())
+    }
+
+    /// Adds or replaces a translation.
+    #[inline]
+    pub fn insert<K, V>(&mut self, key : K, value : V) -> Option<String>
+    where
+        K : Into<String>,
+        V : Into<String>
+    { self.entries.insert(key.into(), value.into()) }
+
+    /// Returns the format string of a translation key.
+    #[inline]
+    pub fn get(&self, key : &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    /// Returns the number of translations.
+    #[inline]
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    /// Returns `true` if there are no translations.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+}
+
+impl<K, V> FromIterator<(K, V,)> for Translator
+where
+    K : Into<String>,
+    V : Into<String>
+{
+    fn from_iter<I>(iter : I) -> Self
+    where
+        I : IntoIterator<Item = (K, V,)>
+    { Self { entries : iter.into_iter().map(|(key, value,)| (key.into(), value.into(),)).collect() } }
+}
+
+
+impl Translator {
+
+    /// Replaces the translated components in some text with literal components, including those in translation arguments.
+    ///
+    /// Missing keys use the component's fallback, or the key itself if it has none.
+    /// `%s` is replaced by the next argument, `%1$s` by the first argument, and `%%` by `%`.
+    /// Like in vanilla, a format string which refers to a missing argument, or which uses any other conversion, is
+    ///  shown as it is. Arguments keep their own styles, and the rest of the format string takes the component's style.
+    pub fn translate(&self, text : &Text) -> Text {
+        text.resolve(&mut |component : &TextComponent| self.translate_component(component))
+    }
+
+    fn translate_component(&self, component : &TextComponent) -> Option<Text> {
+        let TextContent::Translate { key, fallback, with } = &component.content
+            else { return None; };
+        let format = self.get(key).or(fallback.as_deref()).unwrap_or(key);
+        let literal = |text : &str| TextComponent {
+            content : TextContent::Literal { text : Cow::Owned(text.to_string()) },
+            style   : component.style.clone()
+        };
+        let mut components = Vec::new();
+        let mut pending    = String::new();
+        let mut next_arg   = 0;
+        let mut rest       = format;
+        while let Some(i) = rest.find('%') {
+            pending.push_str(&rest[..i]);
+            rest = &rest[(i + 1)..];
+            let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+            let arg = if (rest.starts_with('%')) {
+                pending.push('%');
+                rest = &rest[1..];
+                continue;
+            } else if (rest.starts_with('s')) {
+                rest = &rest[1..];
+                next_arg += 1;
+                next_arg - 1
+            } else if (digits > 0 && rest[digits..].starts_with("$s")) {
+                let index = rest[..digits].parse::<usize>().ok().and_then(|index| index.checked_sub(1));
+                rest = &rest[(digits + 2)..];
+                match (index) {
+                    Some(index) => index,
+                    None        => { return Some(literal(format).into()); }
+                }
+            } else {
+                return Some(literal(format).into());
+            };
+            let Some(arg) = with.get(arg)
+                else { return Some(literal(format).into()); };
+            if (! pending.is_empty()) {
+                components.push(literal(&pending));
+                pending.clear();
+            }
+            components.extend_from_slice(&self.translate(arg).components);
+        }
+        pending.push_str(rest);
+        if (! pending.is_empty()) {
+            components.push(literal(&pending));
+        }
+        Some(Text { components : Cow::Owned(components) })
+    }
+
+}
+
+
+/// Returned by [`Translator`] loaders when a language file can not be read.
+#[derive(Debug)]
+pub enum TranslatorLoadError {
+    /// An io error occured.
+    Io(io::Error),
+    /// The language file is not a JSON object mapping keys to strings.
+    Json {
+        /// The byte offset at which parsing failed.
+        offset : usize,
+        /// Why parsing failed.
+        reason : &'static str
+    }
+}
+impl From<io::Error> for TranslatorLoadError {
+    #[inline]
+    fn from(err : io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl Display for TranslatorLoadError {
+    fn fmt(&self, f : &mut Formatter<'_>) -> fmt::Result { match (self) {
+        Self::Io(err)                  => write!(f, "{err}"),
+        Self::Json { offset, reason } => write!(f, "{reason} at offset {offset}")
+    } }
+}
+impl StdError for TranslatorLoadError { }
+
+
+/// Reads the parts of a flat JSON object of strings.
+struct JsonParser<'l> {
+    json   : &'l str,
+    offset : usize
+}
+
+impl JsonParser<'_> {
+
+    #[inline]
+    fn error(&self, reason : &'static str) -> TranslatorLoadError {
+        TranslatorLoadError::Json { offset : self.offset, reason }
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.json[self.offset..].chars().next()?;
+        self.offset += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn eat(&mut self, expected : char) -> bool {
+        let eaten = self.json[self.offset..].starts_with(expected);
+        if (eaten) { self.offset += expected.len_utf8(); }
+        eaten
+    }
+
+    fn expect(&mut self, expected : char) -> Result<(), TranslatorLoadError> {
+        if (self.eat(expected)) { Ok(()) }
+        else { Err(self.error(match (expected) {
+            '{' => "expected `{`",
+            ':' => "expected `:`",
+            ',' => "expected `,` or `}`",
+            _   => "unexpected character"
+        })) }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.json[self.offset..];
+        // A byte order mark is allowed at the start of the file.
+        let rest_trimmed = rest.trim_start_matches([' ', '\t', '\n', '\r', '\u{feff}']);
+        self.offset += rest.len() - rest_trimmed.len();
+    }
+
+    fn string(&mut self) -> Result<String, TranslatorLoadError> {
+        if (! self.eat('"')) { return Err(self.error("expected string")); }
+        let mut string = String::new();
+        loop {
+            match (self.next().ok_or_else(|| self.error("unterminated string"))?) {
+                '"'  => { return Ok(string); },
+                '\\' => {
+                    let escaped = match (self.next().ok_or_else(|| self.error("unterminated string"))?) {
+                        '"'  => '"',
+                        '\\' => '\\',
+                        '/'  => '/',
+                        'b'  => '\u{8}',
+                        'f'  => '\u{c}',
+                        'n'  => '\n',
+                        'r'  => '\r',
+                        't'  => '\t',
+                        'u'  => {
+                            let high = self.hex4()?;
+                            if ((0xD800..0xDC00).contains(&high)) {
+                                if (! (self.eat('\\') && self.eat('u'))) { return Err(self.error("unpaired surrogate")); }
+                                let low = self.hex4()?;
+                                if (! (0xDC00..0xE000).contains(&low)) { return Err(self.error("unpaired surrogate")); }
+                                char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)).ok_or_else(|| self.error("invalid escape"))?
+                            } else {
+                                char::from_u32(high).ok_or_else(|| self.error("unpaired surrogate"))?
+                            }
+                        },
+                        _ => { return Err(self.error("invalid escape")); }
+                    };
+                    string.push(escaped);
+                },
+                ch if (ch < ' ') => { return Err(self.error("control character in string")); },
+                ch => { string.push(ch); }
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, TranslatorLoadError> {
+        let digits = self.json.get(self.offset..(self.offset + 4)).ok_or_else(|| self.error("invalid escape"))?;
+        let value  = u32::from_str_radix(digits, 16).map_err(|_| self.error("invalid escape"))?;
+        self.offset += 4;
+        Ok(value)
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        colour::Rgb,
+        text::TextFormatted as _
+    };
+
+    fn json_error(json : &str) -> (usize, &'static str,) {
+        match (Translator::new().extend_json(json)) {
+            Err(TranslatorLoadError::Json { offset, reason }) => (offset, reason,),
+            other                                            => panic!("expected JSON error, found {other:?}")
+        }
+    }
+
+    #[test]
+    fn json() {
+        let mut translator = Translator::new();
+        translator.extend_json("\u{feff} { \"a\" : \"x\\\"\\\\\\/\\b\\f\\n\\r\\t\" ,\n\"b\":\"\\u00e9\\ud83d\\ude00\u{e9}\", \"a.b\" : \"\" } ").unwrap();
+        assert_eq!(translator.get("a"), Some("x\"\\/\u{8}\u{c}\n\r\t"));
+        assert_eq!(translator.get("b"), Some("\u{e9}\u{1f600}\u{e9}"));
+        assert_eq!(translator.get("a.b"), Some(""));
+        assert_eq!(translator.len(), 3);
+        // Later files replace earlier translations.
+        translator.extend_json(r#"{"a":"y"}"#).unwrap();
+        assert_eq!(translator.get("a"), Some("y"));
+        Translator::new().extend_json("{}").unwrap();
+    }
+
+    #[test]
+    fn json_errors() {
+        assert_eq!(json_error(""), (0, "expected `{`",));
+        assert_eq!(json_error(r#"{"a":1}"#), (5, "expected string",));
+        assert_eq!(json_error(r#"{"a" "b"}"#), (5, "expected `:`",));
+        assert_eq!(json_error(r#"{"a":"b" "c":"d"}"#), (9, "expected `,` or `}`",));
+        assert_eq!(json_error(r#"{"a":"b",}"#), (9, "expected string",));
+        assert_eq!(json_error(r#"{"a":"b"} x"#), (10, "trailing characters",));
+        assert_eq!(json_error(r#"{"a":"b"#), (7, "unterminated string",));
+        assert_eq!(json_error("{\"a\":\"\n\"}"), (7, "control character in string",));
+        assert_eq!(json_error(r#"{"a":"\x"}"#), (8, "invalid escape",));
+        assert_eq!(json_error(r#"{"a":"\u12"}"#), (8, "invalid escape",));
+        assert_eq!(json_error(r#"{"a":"\ud83d"}"#), (12, "unpaired surrogate",));
+        assert_eq!(json_error(r#"{"a":"\ud83dA"}"#), (12, "unpaired surrogate",));
+        assert_eq!(json_error(r#"{"a":"\ud83d\u0041"}"#), (18, "unpaired surrogate",));
+        assert_eq!(json_error(r#"{"a":"\ude00"}"#), (12, "unpaired surrogate",));
+    }
+
+    #[test]
+    fn format() {
+        let translator = [
+            ("next",    "%s and %s",),
+            ("indexed", "%2$s before %1$s, 100%%",),
+            ("missing", "%s and %s",),
+            ("bad",     "%d",)
+        ].into_iter().collect::<Translator>();
+        let translate = |key : &'static str, with : Vec<Text>| translator.translate(&Text::translate_with(key, with)).to_plain();
+        assert_eq!(translate("next", vec![Text::literal("a"), Text::literal("b")]), "a and b");
+        assert_eq!(translate("indexed", vec![Text::literal("a"), Text::literal("b")]), "b before a, 100%");
+        assert_eq!(translate("missing", vec![Text::literal("a")]), "%s and %s");
+        assert_eq!(translate("bad", vec![Text::literal("a")]), "%d");
+        assert_eq!(translate("unknown.key", vec![]), "unknown.key");
+
+        // Arguments keep their own styles, and are translated themselves.
+        let text = translator.translate(&Text::translate_with("next", vec![Text::translate_with("next", vec![Text::literal("x"), Text::literal("y")]), Text::literal("z").bold()]).red());
+        assert_eq!(text.to_plain(), "x and y and z");
+        let components = text.components.iter().map(|component| (component.content.to_string(), component.style.colour, component.style.bold,)).collect::<Vec<_>>();
+        assert_eq!(components, [
+            ("x".to_string(),     Rgb::WHITE, false,),
+            (" and ".to_string(), Rgb::WHITE, false,),
+            ("y".to_string(),     Rgb::WHITE, false,),
+            (" and ".to_string(), Rgb::RED,   false,),
+            ("z".to_string(),     Rgb::WHITE, true,)
+        ]);
+    }
+
+}
@@ -4,7 +4,7 @@
 use crate::{
     colour::Rgb,
     ident::Ident,
-    nbt::to_network as to_network_nbt,
+    nbt::{ NbtSerError, to_network as to_network_nbt },
     num::weighted::Weighted,
     particle::Particle,
     registry_entry::*
@@ -202,11 +202,11 @@ include!("../../../pipeworkmc-vanilla-datagen/output/generated/worldgen/biome.rs
 impl RegistryEntryType for WorldgenBiome<'_> {
     const REGISTRY_ID : Ident = Ident::new("minecraft:worldgen/biome");
 
-    fn to_network_nbt<W>(&self, writer : W) -> bool
+    fn to_network_nbt<W>(&self, writer : W) -> Result<bool, NbtSerError>
     where
         W : Write
     {
-        to_network_nbt(writer, self).unwrap();
-        true
+        to_network_nbt(writer, self)?;
+        Ok(true)
     }
 }